@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ItemFn, Result};
+
+use crate::args::{err_on_unknown_args, pop_bool_arg, pop_string_arg};
+
+pub(crate) struct Iter {
+    item: ItemFn,
+    target: Option<String>,
+    sleepable: bool,
+}
+
+impl Iter {
+    pub(crate) fn parse(attrs: TokenStream, item: TokenStream) -> Result<Self> {
+        let item = syn::parse2(item)?;
+        let mut args = syn::parse2(attrs)?;
+        let target = pop_string_arg(&mut args, "target");
+        let sleepable = pop_bool_arg(&mut args, "sleepable");
+        err_on_unknown_args(&args)?;
+        Ok(Self {
+            item,
+            target,
+            sleepable,
+        })
+    }
+
+    pub(crate) fn expand(&self) -> TokenStream {
+        let Self {
+            item,
+            target,
+            sleepable,
+        } = self;
+        let ItemFn {
+            attrs: _,
+            vis,
+            sig,
+            block: _,
+        } = item;
+        let section_prefix = if *sleepable { "iter.s" } else { "iter" };
+        let section_name: Cow<'_, _> = if let Some(target) = target {
+            format!("{section_prefix}/{target}").into()
+        } else {
+            section_prefix.into()
+        };
+        let fn_name = &sig.ident;
+        quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = #section_name)]
+            #vis fn #fn_name(ctx: *mut ::core::ffi::c_void) -> i32 {
+                return #fn_name(::aya_ebpf::programs::IterContext::new(ctx));
+
+                #item
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_iter() {
+        let prog = Iter::parse(
+            parse_quote! { target = "task" },
+            parse_quote! {
+                fn dump_task(ctx: ::aya_ebpf::programs::IterContext<bpf_iter__task>) -> i32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "iter/task")]
+            fn dump_task(ctx: *mut ::core::ffi::c_void) -> i32 {
+                return dump_task(::aya_ebpf::programs::IterContext::new(ctx));
+
+                fn dump_task(ctx: ::aya_ebpf::programs::IterContext<bpf_iter__task>) -> i32 {
+                    0
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+
+    #[test]
+    fn test_iter_sleepable() {
+        let prog = Iter::parse(
+            parse_quote! { target = "task", sleepable },
+            parse_quote! {
+                fn dump_task(ctx: ::aya_ebpf::programs::IterContext<bpf_iter__task>) -> i32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "iter.s/task")]
+            fn dump_task(ctx: *mut ::core::ffi::c_void) -> i32 {
+                return dump_task(::aya_ebpf::programs::IterContext::new(ctx));
+
+                fn dump_task(ctx: ::aya_ebpf::programs::IterContext<bpf_iter__task>) -> i32 {
+                    0
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+}
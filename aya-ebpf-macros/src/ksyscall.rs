@@ -0,0 +1,145 @@
+use std::borrow::Cow;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ItemFn, Result};
+
+use crate::args::{err_on_unknown_args, pop_string_arg};
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum KSyscallKind {
+    KSyscall,
+    KRetSyscall,
+}
+
+impl std::fmt::Display for KSyscallKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use KSyscallKind::*;
+        match self {
+            KSyscall => write!(f, "ksyscall"),
+            KRetSyscall => write!(f, "kretsyscall"),
+        }
+    }
+}
+
+pub(crate) struct KSyscall {
+    kind: KSyscallKind,
+    syscall: Option<String>,
+    item: ItemFn,
+}
+
+impl KSyscall {
+    pub(crate) fn parse(kind: KSyscallKind, attrs: TokenStream, item: TokenStream) -> Result<Self> {
+        let item = syn::parse2(item)?;
+        let mut args = syn::parse2(attrs)?;
+        let syscall = pop_string_arg(&mut args, "syscall");
+        err_on_unknown_args(&args)?;
+        Ok(Self {
+            kind,
+            syscall,
+            item,
+        })
+    }
+
+    pub(crate) fn expand(&self) -> TokenStream {
+        let Self {
+            kind,
+            syscall,
+            item,
+        } = self;
+        let ItemFn {
+            attrs: _,
+            vis,
+            sig,
+            block: _,
+        } = item;
+        let section_name: Cow<'_, _> = match syscall {
+            Some(syscall) => format!("{kind}/{syscall}").into(),
+            None => kind.to_string().into(),
+        };
+        // The arguments of the syscall can only be read on entry, on return
+        // the registers pointing to them have been clobbered.
+        let context = match kind {
+            KSyscallKind::KSyscall => quote! { KSyscallContext },
+            KSyscallKind::KRetSyscall => quote! { RetProbeContext },
+        };
+        let fn_name = &sig.ident;
+        quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = #section_name)]
+            #vis fn #fn_name(ctx: *mut ::core::ffi::c_void) -> u32 {
+                let _ = #fn_name(::aya_ebpf::programs::#context::new(ctx));
+                return 0;
+
+                #item
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_ksyscall() {
+        let ksyscall = KSyscall::parse(
+            KSyscallKind::KSyscall,
+            parse_quote! { syscall = "openat" },
+            parse_quote! {
+                fn foo(ctx: KSyscallContext) -> u32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            ksyscall.expand().to_string(),
+            quote! {
+                #[unsafe(no_mangle)]
+                #[unsafe(link_section = "ksyscall/openat")]
+                fn foo(ctx: *mut ::core::ffi::c_void) -> u32 {
+                    let _ = foo(::aya_ebpf::programs::KSyscallContext::new(ctx));
+                    return 0;
+
+                    fn foo(ctx: KSyscallContext) -> u32 {
+                        0
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_kretsyscall() {
+        let ksyscall = KSyscall::parse(
+            KSyscallKind::KRetSyscall,
+            parse_quote! { syscall = "openat" },
+            parse_quote! {
+                fn foo(ctx: RetProbeContext) -> u32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            ksyscall.expand().to_string(),
+            quote! {
+                #[unsafe(no_mangle)]
+                #[unsafe(link_section = "kretsyscall/openat")]
+                fn foo(ctx: *mut ::core::ffi::c_void) -> u32 {
+                    let _ = foo(::aya_ebpf::programs::RetProbeContext::new(ctx));
+                    return 0;
+
+                    fn foo(ctx: RetProbeContext) -> u32 {
+                        0
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
+}
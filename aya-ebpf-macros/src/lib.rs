@@ -9,19 +9,28 @@ mod cgroup_sysctl;
 mod fentry;
 mod fexit;
 mod flow_dissector;
+mod fmod_ret;
+mod iter;
 mod kprobe;
+mod ksyscall;
 mod lsm;
+mod lwt;
 mod map;
+mod netfilter;
 mod perf_event;
 mod raw_tracepoint;
 mod sk_lookup;
 mod sk_msg;
+mod sk_reuseport;
 mod sk_skb;
 mod sock_ops;
 mod socket_filter;
+mod struct_ops;
+mod syscall;
 mod tc;
 mod tracepoint;
 mod uprobe;
+mod usdt;
 mod xdp;
 
 use btf_tracepoint::BtfTracePoint;
@@ -34,20 +43,29 @@ use cgroup_sysctl::CgroupSysctl;
 use fentry::FEntry;
 use fexit::FExit;
 use flow_dissector::FlowDissector;
+use fmod_ret::FModRet;
+use iter::Iter;
 use kprobe::{KProbe, KProbeKind};
+use ksyscall::{KSyscall, KSyscallKind};
 use lsm::Lsm;
+use lwt::{Lwt, LwtKind};
 use map::Map;
+use netfilter::Netfilter;
 use perf_event::PerfEvent;
 use proc_macro::TokenStream;
 use raw_tracepoint::RawTracePoint;
 use sk_lookup::SkLookup;
 use sk_msg::SkMsg;
+use sk_reuseport::SkReuseport;
 use sk_skb::{SkSkb, SkSkbKind};
 use sock_ops::SockOps;
 use socket_filter::SocketFilter;
+use struct_ops::{StructOps, StructOpsMap};
+use syscall::Syscall;
 use tc::SchedClassifier;
 use tracepoint::TracePoint;
 use uprobe::{UProbe, UProbeKind};
+use usdt::Usdt;
 use xdp::Xdp;
 
 #[proc_macro_attribute]
@@ -74,6 +92,77 @@ pub fn kretprobe(attrs: TokenStream, item: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Marks a function as a kprobe eBPF program that is attached to a syscall.
+///
+/// The syscall name, without any architecture specific prefix, may be
+/// provided as the `syscall` argument. In user space, the program is attached
+/// with `KProbe::attach_syscall`.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.17 on x86_64
+/// and 4.19 on aarch64, where syscalls are implemented by wrappers.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{cty::c_int, macros::ksyscall, programs::KSyscallContext};
+///
+/// #[ksyscall(syscall = "close")]
+/// pub fn close(ctx: KSyscallContext) -> u32 {
+///     match try_close(ctx) {
+///         Ok(ret) => ret,
+///         Err(ret) => ret,
+///     }
+/// }
+///
+/// fn try_close(ctx: KSyscallContext) -> Result<u32, u32> {
+///     let _fd: c_int = ctx.arg(0).ok_or(1u32)?;
+///     Ok(0)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn ksyscall(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match KSyscall::parse(KSyscallKind::KSyscall, attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.into_compile_error(),
+    }
+    .into()
+}
+
+/// Marks a function as a kretprobe eBPF program that is attached to the return
+/// of a syscall.
+///
+/// The syscall name, without any architecture specific prefix, may be
+/// provided as the `syscall` argument. In user space, the program is attached
+/// with `KProbe::attach_syscall`.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.17 on x86_64
+/// and 4.19 on aarch64, where syscalls are implemented by wrappers.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{cty::c_long, macros::kretsyscall, programs::RetProbeContext};
+///
+/// #[kretsyscall(syscall = "close")]
+/// pub fn close_ret(ctx: RetProbeContext) -> u32 {
+///     let _ret: Option<c_long> = ctx.ret();
+///     0
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn kretsyscall(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match KSyscall::parse(KSyscallKind::KRetSyscall, attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.into_compile_error(),
+    }
+    .into()
+}
+
 #[proc_macro_attribute]
 pub fn uprobe(attrs: TokenStream, item: TokenStream) -> TokenStream {
     match UProbe::parse(UProbeKind::UProbe, attrs.into(), item.into()) {
@@ -96,6 +185,44 @@ pub fn uretprobe(attrs: TokenStream, item: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Marks a function as a USDT (user statically-defined tracepoint) eBPF program.
+///
+/// The target of the probe can be given with the `path`, `provider` and `name`
+/// arguments, or when attaching the program from user space. Sleepable programs
+/// are declared with the `sleepable` argument.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.15.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{macros::usdt, programs::UsdtContext};
+///
+/// #[usdt(path = "/usr/bin/postgres", provider = "postgresql", name = "query__start")]
+/// pub fn query_start(ctx: UsdtContext) -> u32 {
+///     match try_query_start(ctx) {
+///         Ok(ret) => ret,
+///         Err(ret) => ret,
+///     }
+/// }
+///
+/// fn try_query_start(ctx: UsdtContext) -> Result<u32, u32> {
+///     let _query: *const u8 = ctx.arg(0).ok_or(1u32)?;
+///     Ok(0)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn usdt(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match Usdt::parse(attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.emit_as_expr_tokens(),
+    }
+    .into()
+}
+
 #[proc_macro_attribute]
 pub fn sock_ops(attrs: TokenStream, item: TokenStream) -> TokenStream {
     match SockOps::parse(attrs.into(), item.into()) {
@@ -254,9 +381,14 @@ pub fn perf_event(attrs: TokenStream, item: TokenStream) -> TokenStream {
 /// be attached to. See `/sys/kernel/debug/tracing/events` for a list of which
 /// events can be traced.
 ///
+/// You may provide `writable` to create a writable raw tracepoint program,
+/// which can write to the buffer passed as the first argument of tracepoints
+/// such as the `nbd` ones.
+///
 /// # Minimum kernel version
 ///
-/// The minimum kernel version required to use this feature is 4.7.
+/// The minimum kernel version required to use this feature is 4.7, or 5.2 for
+/// writable raw tracepoints.
 ///
 /// # Examples
 ///
@@ -288,7 +420,9 @@ pub fn raw_tracepoint(attrs: TokenStream, item: TokenStream) -> TokenStream {
 /// Used to implement security policy and audit logging.
 ///
 /// The hook name is the first argument to the macro.
-/// You may also provide `sleepable` to mark the program as sleepable.
+/// You may also provide `sleepable` to mark the program as sleepable, or
+/// `cgroup` to create a program that is attached to a cgroup and only runs for
+/// the tasks inside it. Cgroup programs can't be sleepable.
 /// Arguments should be comma separated.
 ///
 /// LSM probes can be attached to the kernel's security hooks to implement mandatory
@@ -300,7 +434,8 @@ pub fn raw_tracepoint(attrs: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// # Minimum kernel version
 ///
-/// The minimum kernel version required to use this feature is 5.7.
+/// The minimum kernel version required to use this feature is 5.7, or 6.0 for
+/// cgroup programs.
 ///
 /// # Examples
 ///
@@ -318,6 +453,12 @@ pub fn raw_tracepoint(attrs: TokenStream, item: TokenStream) -> TokenStream {
 /// unsafe fn try_file_open(_ctx: LsmContext) -> Result<i32, i32> {
 ///     Ok(0)
 /// }
+///
+/// #[lsm(hook = "socket_bind", cgroup)]
+/// pub fn socket_bind(_ctx: LsmContext) -> i32 {
+///     // Allow the operation.
+///     1
+/// }
 /// ```
 #[proc_macro_attribute]
 pub fn lsm(attrs: TokenStream, item: TokenStream) -> TokenStream {
@@ -328,6 +469,135 @@ pub fn lsm(attrs: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Marks a function as an iterator eBPF program, which dumps kernel objects to
+/// a `seq_file` read from user space.
+///
+/// The iterator target, such as `task`, `bpf_map_elem` or `tcp`, may be
+/// provided as the `target` argument. You may also provide `sleepable` to mark
+/// the program as sleepable. The type of the context is inferred from the
+/// signature of the function, e.g. `IterContext<bpf_iter__task>` for `task`
+/// iterators.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.8.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{
+///     helpers::bpf_seq_printf,
+///     macros::iter,
+///     programs::{IterContext, iter::bpf_iter__task},
+/// };
+///
+/// #[iter(target = "task")]
+/// pub fn dump_task(ctx: IterContext<bpf_iter__task>) -> i32 {
+///     if ctx.task().is_null() {
+///         return 0;
+///     }
+///     if ctx.seq_num() == 0 {
+///         let _ = ctx.seq_write(b"tasks:\n");
+///     }
+///     unsafe { bpf_seq_printf!(ctx.seq(), b"%llu\n", ctx.seq_num()) };
+///     0
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn iter(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match Iter::parse(attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.into_compile_error(),
+    }
+    .into()
+}
+
+/// Marks a function as a struct_ops eBPF program that implements a function
+/// pointer member of a kernel struct.
+///
+/// struct_ops programs are referenced by a static instance of the kernel
+/// struct declared with [`macro@struct_ops_map`], which is registered with
+/// the kernel from user space. You may provide `name` to set the section name
+/// suffix and `sleepable` to mark the program as sleepable.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 6.4.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{macros::{struct_ops, struct_ops_map}, programs::StructOpsContext};
+///
+/// #[struct_ops]
+/// pub fn ssthresh(_ctx: StructOpsContext) -> u32 {
+///     2
+/// }
+///
+/// #[repr(C)]
+/// #[expect(non_camel_case_types)]
+/// pub struct tcp_congestion_ops {
+///     ssthresh: Option<fn(*mut core::ffi::c_void) -> u32>,
+///     // The other members of the kernel struct which are used.
+/// }
+///
+/// #[struct_ops_map]
+/// static mut TCP_CC: tcp_congestion_ops = tcp_congestion_ops {
+///     ssthresh: Some(ssthresh),
+/// };
+/// ```
+#[proc_macro_attribute]
+pub fn struct_ops(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match StructOps::parse(attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.into_compile_error(),
+    }
+    .into()
+}
+
+/// Declares a struct_ops map, a static instance of a kernel struct whose
+/// function pointer members are implemented by [`macro@struct_ops`] programs.
+///
+/// The local definition of the struct only needs the members which are used,
+/// it's matched with the kernel one by name when the map is loaded. The map is
+/// registered with the kernel by creating a link from user space. Pass
+/// `no_link` to declare a map which is registered by setting its value
+/// instead, as required by kernels older than 6.4.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.6.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{macros::{struct_ops, struct_ops_map}, programs::StructOpsContext};
+///
+/// #[struct_ops]
+/// pub fn ssthresh(_ctx: StructOpsContext) -> u32 {
+///     2
+/// }
+///
+/// #[repr(C)]
+/// #[expect(non_camel_case_types)]
+/// pub struct tcp_congestion_ops {
+///     ssthresh: Option<fn(*mut core::ffi::c_void) -> u32>,
+/// }
+///
+/// #[struct_ops_map(no_link)]
+/// static mut TCP_CC: tcp_congestion_ops = tcp_congestion_ops {
+///     ssthresh: Some(ssthresh),
+/// };
+/// ```
+#[proc_macro_attribute]
+pub fn struct_ops_map(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match StructOpsMap::parse(attrs.into(), item.into()) {
+        Ok(map) => map.expand(),
+        Err(err) => err.into_compile_error(),
+    }
+    .into()
+}
+
 /// Marks a function as a [BTF-enabled raw tracepoint][1] eBPF program that can be attached at
 /// a pre-defined kernel trace point.
 ///
@@ -544,6 +814,44 @@ pub fn fexit(attrs: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Marks a function as a fmod_ret eBPF program that can override the return
+/// value of a kernel function. When the program returns a non-zero value, the
+/// kernel function is skipped and the value is returned to its caller instead.
+/// Only functions marked with `ALLOW_ERROR_INJECTION` and security hooks can be
+/// targeted.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.7.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{cty::c_void, macros::fmod_ret, programs::FModRetContext};
+///
+/// #[fmod_ret(function = "security_file_open")]
+/// fn security_file_open(ctx: FModRetContext) -> i32 {
+///     match unsafe { try_security_file_open(ctx) } {
+///         Ok(ret) => ret,
+///         Err(ret) => ret,
+///     }
+/// }
+///
+/// unsafe fn try_security_file_open(ctx: FModRetContext) -> Result<i32, i32> {
+///     let _file: *const c_void = ctx.arg(0);
+///
+///     Ok(0)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn fmod_ret(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match FModRet::parse(attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.into_compile_error(),
+    }
+    .into()
+}
+
 /// Marks a function as an eBPF Flow Dissector program.
 ///
 /// Flow dissector is a program type that parses metadata out of the packets.
@@ -612,6 +920,220 @@ pub fn sk_lookup(attrs: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Marks a function as an eBPF Socket Reuseport program that can be attached
+/// to a group of sockets bound with `SO_REUSEPORT`.
+///
+/// The program selects the socket receiving a packet or connection from a
+/// `ReusePortSockArray` map. You may provide `migrate` to also select the
+/// socket which requests are migrated to when a listener is closed.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.19, and 5.14
+/// for `migrate`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{
+///     bindings::sk_action, macros::{map, sk_reuseport}, maps::ReusePortSockArray,
+///     programs::SkReuseportContext,
+/// };
+///
+/// #[map]
+/// static SOCKETS: ReusePortSockArray = ReusePortSockArray::with_max_entries(4, 0);
+///
+/// #[sk_reuseport]
+/// pub fn select_socket(ctx: SkReuseportContext) -> u32 {
+///     match ctx.select_reuseport(&SOCKETS, ctx.hash() % 4, 0) {
+///         Ok(()) => sk_action::SK_PASS,
+///         Err(_) => sk_action::SK_DROP,
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn sk_reuseport(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match SkReuseport::parse(attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.into_compile_error(),
+    }
+    .into()
+}
+
+/// Marks a function as a lightweight tunnel eBPF program that runs on the
+/// packets received by a route.
+///
+/// The program can inspect and drop packets but can't modify them.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.10
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{bindings::bpf_ret_code::BPF_OK, macros::lwt_in, programs::LwtInContext};
+///
+/// #[lwt_in]
+/// pub fn route_in(_ctx: LwtInContext) -> i32 {
+///     BPF_OK as i32
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn lwt_in(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    lwt(LwtKind::In, attrs, item)
+}
+
+/// Marks a function as a lightweight tunnel eBPF program that runs on the
+/// packets sent through a route.
+///
+/// The program can inspect and drop packets but can't modify them.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.10
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{bindings::bpf_ret_code::BPF_OK, macros::lwt_out, programs::LwtOutContext};
+///
+/// #[lwt_out]
+/// pub fn route_out(_ctx: LwtOutContext) -> i32 {
+///     BPF_OK as i32
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn lwt_out(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    lwt(LwtKind::Out, attrs, item)
+}
+
+/// Marks a function as a lightweight tunnel eBPF program that runs on the
+/// packets transmitted through a route.
+///
+/// The program can modify packets and encapsulate them with
+/// `LwtXmitContext::push_encap`.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.10
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{bindings::bpf_ret_code::BPF_OK, macros::lwt_xmit, programs::LwtXmitContext};
+///
+/// #[lwt_xmit]
+/// pub fn encap(_ctx: LwtXmitContext) -> i32 {
+///     BPF_OK as i32
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn lwt_xmit(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    lwt(LwtKind::Xmit, attrs, item)
+}
+
+/// Marks a function as an eBPF program implementing the `End.BPF` action of an
+/// SRv6 local segment.
+///
+/// The program can modify the segment routing header of packets and apply
+/// SRv6 actions with the `LwtSeg6LocalContext::seg6_*` methods.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.18
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{
+///     bindings::bpf_ret_code::BPF_OK, macros::lwt_seg6local, programs::LwtSeg6LocalContext,
+/// };
+///
+/// #[lwt_seg6local]
+/// pub fn end_bpf(_ctx: LwtSeg6LocalContext) -> i32 {
+///     BPF_OK as i32
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn lwt_seg6local(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    lwt(LwtKind::Seg6Local, attrs, item)
+}
+
+fn lwt(kind: LwtKind, attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match Lwt::parse(kind, attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.emit_as_expr_tokens(),
+    }
+    .into()
+}
+
+/// Marks a function as a netfilter eBPF program that can be attached to a
+/// netfilter hook.
+///
+/// The program returns `NF_ACCEPT` to let the packet continue through the hook
+/// or `NF_DROP` to drop it.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 6.4
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{macros::netfilter, programs::NetfilterContext};
+///
+/// const NF_ACCEPT: i32 = 1;
+///
+/// #[netfilter]
+/// pub fn accept_all(_ctx: NetfilterContext) -> i32 {
+///     NF_ACCEPT
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn netfilter(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match Netfilter::parse(attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.emit_as_expr_tokens(),
+    }
+    .into()
+}
+
+/// Marks a function as a syscall eBPF program.
+///
+/// Syscall programs aren't attached to any kernel event. They are run from
+/// userspace with `Syscall::run`, which passes them a user-defined context
+/// struct, and can call the `bpf` syscall with the `bpf_sys_bpf` helper.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.14
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{macros::syscall, programs::SyscallContext};
+///
+/// #[repr(C)]
+/// pub struct Args {
+///     value: u64,
+/// }
+///
+/// #[syscall]
+/// pub fn setup(mut ctx: SyscallContext<Args>) -> i32 {
+///     unsafe { ctx.args_mut() }.value += 1;
+///     0
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn syscall(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match Syscall::parse(attrs.into(), item.into()) {
+        Ok(prog) => prog.expand(),
+        Err(err) => err.emit_as_expr_tokens(),
+    }
+    .into()
+}
+
 /// Marks a function as a cgroup device eBPF program that can be attached to a
 /// cgroup.
 ///
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::{ItemFn, Result};
+use syn::{Error, ItemFn, Result};
 
 use crate::args::{err_on_unknown_args, pop_bool_arg, pop_string_arg};
 
@@ -10,6 +10,7 @@ pub(crate) struct Lsm {
     item: ItemFn,
     hook: Option<String>,
     sleepable: bool,
+    cgroup: bool,
 }
 
 impl Lsm {
@@ -18,11 +19,19 @@ impl Lsm {
         let mut args = syn::parse2(attrs)?;
         let hook = pop_string_arg(&mut args, "hook");
         let sleepable = pop_bool_arg(&mut args, "sleepable");
+        let cgroup = pop_bool_arg(&mut args, "cgroup");
         err_on_unknown_args(&args)?;
+        if sleepable && cgroup {
+            return Err(Error::new(
+                Span::call_site(),
+                "cgroup LSM programs can't be sleepable",
+            ));
+        }
         Ok(Self {
             item,
             hook,
             sleepable,
+            cgroup,
         })
     }
 
@@ -31,6 +40,7 @@ impl Lsm {
             item,
             hook,
             sleepable,
+            cgroup,
         } = self;
         let ItemFn {
             attrs: _,
@@ -38,7 +48,13 @@ impl Lsm {
             sig,
             block: _,
         } = item;
-        let section_prefix = if *sleepable { "lsm.s" } else { "lsm" };
+        let section_prefix = if *cgroup {
+            "lsm_cgroup"
+        } else if *sleepable {
+            "lsm.s"
+        } else {
+            "lsm"
+        };
         let section_name: Cow<'_, _> = if let Some(hook) = hook {
             format!("{section_prefix}/{hook}").into()
         } else {
@@ -95,6 +111,54 @@ mod tests {
         assert_eq!(expected.to_string(), expanded.to_string());
     }
 
+    #[test]
+    fn test_lsm_cgroup() {
+        let prog = Lsm::parse(
+            parse_quote! {
+                cgroup,
+                hook = "socket_bind"
+            },
+            parse_quote! {
+                fn socket_bind(ctx: &mut ::aya_ebpf::programs::LsmContext) -> i32 {
+                    1
+                }
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "lsm_cgroup/socket_bind")]
+            fn socket_bind(ctx: *mut ::core::ffi::c_void) -> i32 {
+                return socket_bind(::aya_ebpf::programs::LsmContext::new(ctx));
+
+                fn socket_bind(ctx: &mut ::aya_ebpf::programs::LsmContext) -> i32 {
+                    1
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+
+    #[test]
+    fn test_lsm_cgroup_sleepable() {
+        let err = Lsm::parse(
+            parse_quote! {
+                cgroup,
+                sleepable,
+                hook = "socket_bind"
+            },
+            parse_quote! {
+                fn socket_bind(ctx: &mut ::aya_ebpf::programs::LsmContext) -> i32 {
+                    1
+                }
+            },
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.to_string(), "cgroup LSM programs can't be sleepable");
+    }
+
     #[test]
     fn test_lsm() {
         let prog = Lsm::parse(
@@ -0,0 +1,194 @@
+use std::borrow::Cow;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt as _};
+use quote::quote;
+use syn::{ItemFn, spanned::Spanned as _};
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum LwtKind {
+    In,
+    Out,
+    Xmit,
+    Seg6Local,
+}
+
+impl LwtKind {
+    fn context(&self) -> &'static str {
+        use LwtKind::*;
+        match self {
+            In => "LwtInContext",
+            Out => "LwtOutContext",
+            Xmit => "LwtXmitContext",
+            Seg6Local => "LwtSeg6LocalContext",
+        }
+    }
+}
+
+impl std::fmt::Display for LwtKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use LwtKind::*;
+        match self {
+            In => write!(f, "lwt_in"),
+            Out => write!(f, "lwt_out"),
+            Xmit => write!(f, "lwt_xmit"),
+            Seg6Local => write!(f, "lwt_seg6local"),
+        }
+    }
+}
+
+pub(crate) struct Lwt {
+    kind: LwtKind,
+    item: ItemFn,
+}
+
+impl Lwt {
+    pub(crate) fn parse(
+        kind: LwtKind,
+        attrs: TokenStream,
+        item: TokenStream,
+    ) -> Result<Self, Diagnostic> {
+        if !attrs.is_empty() {
+            return Err(attrs.span().error("unexpected attribute"));
+        }
+        let item = syn::parse2(item)?;
+        Ok(Self { kind, item })
+    }
+
+    pub(crate) fn expand(&self) -> TokenStream {
+        let Self { kind, item } = self;
+        let ItemFn {
+            attrs: _,
+            vis,
+            sig,
+            block: _,
+        } = item;
+        let section_name: Cow<'_, _> = kind.to_string().into();
+        let context = Ident::new(kind.context(), Span::call_site());
+        let fn_name = &sig.ident;
+        quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = #section_name)]
+            #vis fn #fn_name(ctx: *mut ::aya_ebpf::bindings::__sk_buff) -> i32 {
+                return #fn_name(::aya_ebpf::programs::#context::new(ctx));
+
+                #item
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_lwt_in() {
+        let prog = Lwt::parse(
+            LwtKind::In,
+            parse_quote! {},
+            parse_quote! {
+                fn prog(ctx: &mut ::aya_ebpf::programs::LwtInContext) -> i32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "lwt_in")]
+            fn prog(ctx: *mut ::aya_ebpf::bindings::__sk_buff) -> i32 {
+                return prog(::aya_ebpf::programs::LwtInContext::new(ctx));
+
+                fn prog(ctx: &mut ::aya_ebpf::programs::LwtInContext) -> i32 {
+                    0
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+
+    #[test]
+    fn test_lwt_out() {
+        let prog = Lwt::parse(
+            LwtKind::Out,
+            parse_quote! {},
+            parse_quote! {
+                fn prog(ctx: &mut ::aya_ebpf::programs::LwtOutContext) -> i32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "lwt_out")]
+            fn prog(ctx: *mut ::aya_ebpf::bindings::__sk_buff) -> i32 {
+                return prog(::aya_ebpf::programs::LwtOutContext::new(ctx));
+
+                fn prog(ctx: &mut ::aya_ebpf::programs::LwtOutContext) -> i32 {
+                    0
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+
+    #[test]
+    fn test_lwt_xmit() {
+        let prog = Lwt::parse(
+            LwtKind::Xmit,
+            parse_quote! {},
+            parse_quote! {
+                fn prog(ctx: &mut ::aya_ebpf::programs::LwtXmitContext) -> i32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "lwt_xmit")]
+            fn prog(ctx: *mut ::aya_ebpf::bindings::__sk_buff) -> i32 {
+                return prog(::aya_ebpf::programs::LwtXmitContext::new(ctx));
+
+                fn prog(ctx: &mut ::aya_ebpf::programs::LwtXmitContext) -> i32 {
+                    0
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+
+    #[test]
+    fn test_lwt_seg6local() {
+        let prog = Lwt::parse(
+            LwtKind::Seg6Local,
+            parse_quote! {},
+            parse_quote! {
+                fn prog(ctx: &mut ::aya_ebpf::programs::LwtSeg6LocalContext) -> i32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "lwt_seg6local")]
+            fn prog(ctx: *mut ::aya_ebpf::bindings::__sk_buff) -> i32 {
+                return prog(::aya_ebpf::programs::LwtSeg6LocalContext::new(ctx));
+
+                fn prog(ctx: &mut ::aya_ebpf::programs::LwtSeg6LocalContext) -> i32 {
+                    0
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+}
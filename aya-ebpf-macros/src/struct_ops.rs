@@ -0,0 +1,190 @@
+use std::borrow::Cow;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ItemFn, ItemStatic, Result};
+
+use crate::args::{err_on_unknown_args, pop_bool_arg, pop_string_arg};
+
+pub(crate) struct StructOps {
+    item: ItemFn,
+    name: Option<String>,
+    sleepable: bool,
+}
+
+impl StructOps {
+    pub(crate) fn parse(attrs: TokenStream, item: TokenStream) -> Result<Self> {
+        let item = syn::parse2(item)?;
+        let mut args = syn::parse2(attrs)?;
+        let name = pop_string_arg(&mut args, "name");
+        let sleepable = pop_bool_arg(&mut args, "sleepable");
+        err_on_unknown_args(&args)?;
+        Ok(Self {
+            item,
+            name,
+            sleepable,
+        })
+    }
+
+    pub(crate) fn expand(&self) -> TokenStream {
+        let Self {
+            item,
+            name,
+            sleepable,
+        } = self;
+        let ItemFn {
+            attrs: _,
+            vis,
+            sig,
+            block: _,
+        } = item;
+        let section_prefix = if *sleepable {
+            "struct_ops.s"
+        } else {
+            "struct_ops"
+        };
+        let section_name: Cow<'_, _> = if let Some(name) = name {
+            format!("{section_prefix}/{name}").into()
+        } else {
+            section_prefix.into()
+        };
+        // The return type depends on the implemented member, so it's taken
+        // from the annotated function.
+        let fn_name = &sig.ident;
+        let output = &sig.output;
+        quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = #section_name)]
+            #vis fn #fn_name(ctx: *mut ::core::ffi::c_void) #output {
+                return #fn_name(::aya_ebpf::programs::StructOpsContext::new(ctx));
+
+                #item
+            }
+        }
+    }
+}
+
+pub(crate) struct StructOpsMap {
+    item: ItemStatic,
+    link: bool,
+}
+
+impl StructOpsMap {
+    pub(crate) fn parse(attrs: TokenStream, item: TokenStream) -> Result<Self> {
+        let item = syn::parse2(item)?;
+        let mut args = syn::parse2(attrs)?;
+        let link = !pop_bool_arg(&mut args, "no_link");
+        err_on_unknown_args(&args)?;
+        Ok(Self { item, link })
+    }
+
+    pub(crate) fn expand(&self) -> TokenStream {
+        let Self { item, link } = self;
+        let section_name = if *link {
+            ".struct_ops.link"
+        } else {
+            ".struct_ops"
+        };
+        // The map is matched with its BTF variable by name, so the symbol
+        // can't be renamed.
+        quote! {
+            #[unsafe(link_section = #section_name)]
+            #[unsafe(no_mangle)]
+            #item
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_struct_ops() {
+        let prog = StructOps::parse(
+            parse_quote! {},
+            parse_quote! {
+                fn ssthresh(ctx: StructOpsContext) -> u32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "struct_ops")]
+            fn ssthresh(ctx: *mut ::core::ffi::c_void) -> u32 {
+                return ssthresh(::aya_ebpf::programs::StructOpsContext::new(ctx));
+
+                fn ssthresh(ctx: StructOpsContext) -> u32 {
+                    0
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+
+    #[test]
+    fn test_struct_ops_sleepable() {
+        let prog = StructOps::parse(
+            parse_quote! {
+                sleepable,
+                name = "init"
+            },
+            parse_quote! {
+                fn init(ctx: StructOpsContext) {}
+            },
+        )
+        .unwrap();
+        let expanded = prog.expand();
+        let expected = quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = "struct_ops.s/init")]
+            fn init(ctx: *mut ::core::ffi::c_void) {
+                return init(::aya_ebpf::programs::StructOpsContext::new(ctx));
+
+                fn init(ctx: StructOpsContext) {}
+            }
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+
+    #[test]
+    fn test_struct_ops_map() {
+        let map = StructOpsMap::parse(
+            parse_quote! {},
+            parse_quote! {
+                static mut TCP_CC: tcp_congestion_ops = tcp_congestion_ops::new();
+            },
+        )
+        .unwrap();
+        let expanded = map.expand();
+        let expected = quote! {
+            #[unsafe(link_section = ".struct_ops.link")]
+            #[unsafe(no_mangle)]
+            static mut TCP_CC: tcp_congestion_ops = tcp_congestion_ops::new();
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+
+    #[test]
+    fn test_struct_ops_map_no_link() {
+        let map = StructOpsMap::parse(
+            parse_quote! { no_link },
+            parse_quote! {
+                static mut TCP_CC: tcp_congestion_ops = tcp_congestion_ops::new();
+            },
+        )
+        .unwrap();
+        let expanded = map.expand();
+        let expected = quote! {
+            #[unsafe(link_section = ".struct_ops")]
+            #[unsafe(no_mangle)]
+            static mut TCP_CC: tcp_congestion_ops = tcp_congestion_ops::new();
+        };
+        assert_eq!(expected.to_string(), expanded.to_string());
+    }
+}
@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+
+use proc_macro2::TokenStream;
+use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt as _};
+use quote::quote;
+use syn::{ItemFn, spanned::Spanned as _};
+
+use crate::args::{err_on_unknown_args, pop_bool_arg, pop_string_arg};
+
+pub(crate) struct Usdt {
+    path: Option<String>,
+    provider: Option<String>,
+    name: Option<String>,
+    item: ItemFn,
+    sleepable: bool,
+}
+
+impl Usdt {
+    pub(crate) fn parse(attrs: TokenStream, item: TokenStream) -> Result<Self, Diagnostic> {
+        let item = syn::parse2(item)?;
+        let span = attrs.span();
+        let mut args = syn::parse2(attrs)?;
+        let path = pop_string_arg(&mut args, "path");
+        let provider = pop_string_arg(&mut args, "provider");
+        let name = pop_string_arg(&mut args, "name");
+        let sleepable = pop_bool_arg(&mut args, "sleepable");
+        err_on_unknown_args(&args)?;
+        if path.is_some() && (provider.is_none() || name.is_none()) {
+            return Err(span.error("`path` requires both `provider` and `name`"));
+        }
+        Ok(Self {
+            path,
+            provider,
+            name,
+            item,
+            sleepable,
+        })
+    }
+
+    pub(crate) fn expand(&self) -> TokenStream {
+        let Self {
+            path,
+            provider,
+            name,
+            item,
+            sleepable,
+        } = self;
+        let ItemFn {
+            attrs: _,
+            vis,
+            sig,
+            block: _,
+        } = item;
+        let prefix = if *sleepable { "usdt.s" } else { "usdt" };
+        let section_name: Cow<'_, _> = match (path, provider, name) {
+            (Some(path), Some(provider), Some(name)) => {
+                let path = path.strip_prefix("/").unwrap_or(path);
+                format!("{prefix}/{path}:{provider}:{name}").into()
+            }
+            _ => prefix.into(),
+        };
+        let fn_name = &sig.ident;
+        // The spec map is named after the program, where user space looks it up.
+        let spec_map_name = format!("{fn_name}_usdt_specs");
+        quote! {
+            #[unsafe(no_mangle)]
+            #[unsafe(link_section = #section_name)]
+            #vis fn #fn_name(ctx: *mut ::core::ffi::c_void) -> u32 {
+                let specs = {
+                    #[unsafe(link_section = "maps")]
+                    #[unsafe(export_name = #spec_map_name)]
+                    static SPECS: ::aya_ebpf::programs::usdt::UsdtSpecMap =
+                        ::aya_ebpf::programs::usdt::UsdtSpecMap::new();
+                    &SPECS
+                };
+                let _ = #fn_name(::aya_ebpf::programs::UsdtContext::new(ctx, specs));
+                return 0;
+
+                #item
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn usdt() {
+        let usdt = Usdt::parse(
+            parse_quote! {},
+            parse_quote! {
+                fn foo(ctx: UsdtContext) -> u32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            usdt.expand().to_string(),
+            quote! {
+                #[unsafe(no_mangle)]
+                #[unsafe(link_section = "usdt")]
+                fn foo(ctx: *mut ::core::ffi::c_void) -> u32 {
+                    let specs = {
+                        #[unsafe(link_section = "maps")]
+                        #[unsafe(export_name = "foo_usdt_specs")]
+                        static SPECS: ::aya_ebpf::programs::usdt::UsdtSpecMap =
+                            ::aya_ebpf::programs::usdt::UsdtSpecMap::new();
+                        &SPECS
+                    };
+                    let _ = foo(::aya_ebpf::programs::UsdtContext::new(ctx, specs));
+                    return 0;
+
+                    fn foo(ctx: UsdtContext) -> u32 {
+                        0
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn usdt_with_target_sleepable() {
+        let usdt = Usdt::parse(
+            parse_quote! {
+                path = "/usr/lib/libc.so.6",
+                provider = "libc",
+                name = "setjmp",
+                sleepable
+            },
+            parse_quote! {
+                fn foo(ctx: UsdtContext) -> u32 {
+                    0
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            usdt.expand().to_string(),
+            quote! {
+                #[unsafe(no_mangle)]
+                #[unsafe(link_section = "usdt.s/usr/lib/libc.so.6:libc:setjmp")]
+                fn foo(ctx: *mut ::core::ffi::c_void) -> u32 {
+                    let specs = {
+                        #[unsafe(link_section = "maps")]
+                        #[unsafe(export_name = "foo_usdt_specs")]
+                        static SPECS: ::aya_ebpf::programs::usdt::UsdtSpecMap =
+                            ::aya_ebpf::programs::usdt::UsdtSpecMap::new();
+                        &SPECS
+                    };
+                    let _ = foo(::aya_ebpf::programs::UsdtContext::new(ctx, specs));
+                    return 0;
+
+                    fn foo(ctx: UsdtContext) -> u32 {
+                        0
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
+}
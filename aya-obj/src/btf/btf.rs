@@ -15,7 +15,7 @@ use crate::{
     Object,
     btf::{
         Array, BtfEnum, BtfKind, BtfMember, BtfType, Const, Enum, FuncInfo, FuncLinkage, Int,
-        IntEncoding, LineInfo, Struct, Typedef, Union, VarLinkage,
+        IntEncoding, LineInfo, Struct, Typedef, Union, Var, VarLinkage,
         info::{FuncSecInfo, LineSecInfo},
         relocation::Relocation,
     },
@@ -245,6 +245,9 @@ pub struct Btf {
     header: btf_header,
     strings: Vec<u8>,
     types: BtfTypes,
+    // The id of the first type that isn't part of the base BTF, 0 unless this
+    // is split BTF.
+    start_id: u32,
     _endianness: Endianness,
 }
 
@@ -264,6 +267,7 @@ impl Btf {
             },
             strings: vec![0],
             types: BtfTypes::default(),
+            start_id: 0,
             _endianness: Endianness::default(),
         }
     }
@@ -277,6 +281,17 @@ impl Btf {
         self.types.types.iter()
     }
 
+    /// Returns the types that aren't part of the base BTF along with their
+    /// ids, or all the types if this isn't split BTF.
+    pub(crate) fn split_types(&self) -> impl Iterator<Item = (u32, &BtfType)> {
+        self.types
+            .types
+            .iter()
+            .enumerate()
+            .skip(self.start_id as usize)
+            .map(|(type_id, ty)| (type_id as u32, ty))
+    }
+
     /// Adds a string to BTF metadata, returning an offset
     pub fn add_string(&mut self, name: &str) -> u32 {
         let str = name.bytes().chain(core::iter::once(0));
@@ -296,6 +311,26 @@ impl Btf {
         type_id as u32
     }
 
+    /// Creates BTF describing the key of a local storage map as an `int` and its
+    /// value as an opaque array of `value_size` bytes.
+    ///
+    /// Returns the BTF along with the ids of the key and value types. Local
+    /// storage maps can't be created without BTF for their key and value, which
+    /// legacy map definitions don't have.
+    #[doc(hidden)]
+    pub fn local_storage(value_size: u32) -> (Btf, u32, u32) {
+        let mut btf = Btf::new();
+        let name_offset = btf.add_string("int");
+        let int_type = BtfType::Int(Int::new(name_offset, 4, IntEncoding::Signed, 0));
+        let key_type_id = btf.add_type(int_type);
+        let name_offset = btf.add_string("unsigned char");
+        let byte_type = BtfType::Int(Int::new(name_offset, 1, IntEncoding::None, 0));
+        let byte_type_id = btf.add_type(byte_type);
+        let value_type = BtfType::Array(Array::new(0, byte_type_id, key_type_id, value_size));
+        let value_type_id = btf.add_type(value_type);
+        (btf, key_type_id, value_type_id)
+    }
+
     /// Loads BTF metadata from `/sys/kernel/btf/vmlinux`.
     #[cfg(feature = "std")]
     pub fn from_sys_fs() -> Result<Btf, BtfError> {
@@ -319,6 +354,124 @@ impl Btf {
         )
     }
 
+    /// Loads the BTF metadata of the kernel module `module` from
+    /// `/sys/kernel/btf/<module>`.
+    ///
+    /// Module BTF is split BTF, `base` must be the BTF of the running kernel,
+    /// see [`Btf::parse_split`].
+    #[cfg(feature = "std")]
+    pub fn from_sys_fs_module(base: &Btf, module: &str) -> Result<Btf, BtfError> {
+        Btf::parse_split_file(
+            base,
+            std::path::Path::new("/sys/kernel/btf").join(module),
+            Endianness::default(),
+        )
+    }
+
+    /// Loads split BTF metadata from the given `path`.
+    ///
+    /// See [`Btf::parse_split`].
+    #[cfg(feature = "std")]
+    pub fn parse_split_file<P: AsRef<std::path::Path>>(
+        base: &Btf,
+        path: P,
+        endianness: Endianness,
+    ) -> Result<Btf, BtfError> {
+        use std::{borrow::ToOwned as _, fs};
+        let path = path.as_ref();
+        Btf::parse_split(
+            base,
+            &fs::read(path).map_err(|error| BtfError::FileError {
+                path: path.to_owned(),
+                error,
+            })?,
+            endianness,
+        )
+    }
+
+    /// Parses split BTF from binary data of the given endianness.
+    ///
+    /// Split BTF only contains the types that aren't already part of its
+    /// `base` BTF, and refers to the types and strings of `base` as if they
+    /// were its own. Kernel modules expose their BTF this way, with the BTF of
+    /// the kernel itself as the base.
+    ///
+    /// The returned BTF contains the types of both `base` and `data`, with
+    /// the ids of the types from `data` following the ids of the types from
+    /// `base`.
+    pub fn parse_split(base: &Btf, data: &[u8], endianness: Endianness) -> Result<Btf, BtfError> {
+        let (header, types, strings) = Btf::read_split(data, endianness)?;
+
+        let mut btf = base.clone();
+        btf.start_id = btf.types.len() as u32;
+        // The string offsets of split BTF start where the strings of the base
+        // end, and its type ids start where the types of the base end.
+        btf.strings.extend_from_slice(strings);
+        btf.types.types.extend(types.types.into_iter().skip(1));
+        btf.header.type_len += header.type_len;
+        btf.header.str_off = btf.header.type_off + btf.header.type_len;
+        btf.header.str_len = btf.strings.len() as u32;
+        Ok(btf)
+    }
+
+    /// Returns the id of the type named `name` of the given `kind` in the
+    /// split BTF `data`, without copying `base`.
+    ///
+    /// Only the types of `data` are searched. The returned id is the id of the
+    /// type in the BTF returned by [`Btf::parse_split`], so this can be used to
+    /// find which kernel module defines a type before parsing its BTF.
+    pub fn split_id_by_type_name_kind(
+        base: &Btf,
+        data: &[u8],
+        endianness: Endianness,
+        name: &str,
+        kind: BtfKind,
+    ) -> Result<u32, BtfError> {
+        let (_, types, strings) = Btf::read_split(data, endianness)?;
+        for (index, ty) in types.types.iter().enumerate().skip(1) {
+            if ty.kind() != kind {
+                continue;
+            }
+            let offset = ty.name_offset() as usize;
+            let (strings, offset) = match offset.checked_sub(base.strings.len()) {
+                Some(offset) => (strings, offset),
+                None => (base.strings.as_slice(), offset),
+            };
+            let type_name = strings
+                .get(offset..)
+                .and_then(|s| CStr::from_bytes_until_nul(s).ok())
+                .ok_or(BtfError::InvalidStringOffset { offset })?;
+            if type_name.to_bytes() == name.as_bytes() {
+                return Ok((base.types.len() + index - 1) as u32);
+            }
+        }
+
+        Err(BtfError::UnknownBtfTypeName {
+            type_name: name.to_owned(),
+        })
+    }
+
+    fn read_split(
+        data: &[u8],
+        endianness: Endianness,
+    ) -> Result<(btf_header, BtfTypes, &[u8]), BtfError> {
+        if data.len() < mem::size_of::<btf_header>() {
+            return Err(BtfError::InvalidHeader);
+        }
+
+        // safety: btf_header is POD so read_unaligned is safe
+        let header = unsafe { read_btf_header(data) };
+
+        let str_off = header.hdr_len as usize + header.str_off as usize;
+        let str_len = header.str_len as usize;
+        if str_off + str_len > data.len() {
+            return Err(BtfError::InvalidHeader);
+        }
+
+        let types = Btf::read_type_info(&header, data, endianness)?;
+        Ok((header, types, &data[str_off..str_off + str_len]))
+    }
+
     /// Parses BTF from binary data of the given endianness
     pub fn parse(data: &[u8], endianness: Endianness) -> Result<Btf, BtfError> {
         if data.len() < mem::size_of::<btf_header>() {
@@ -341,6 +494,7 @@ impl Btf {
             header,
             strings,
             types,
+            start_id: 0,
             _endianness: endianness,
         })
     }
@@ -496,6 +650,30 @@ impl Btf {
                 0,
             )))
         });
+        // Likewise, the placeholder VAR for kfuncs declared in the .ksyms DATASEC.
+        let ksym_placeholder_id = (features.btf_datasec
+            && self.types().any(|t| match t {
+                BtfType::DataSec(d) => d
+                    .entries
+                    .iter()
+                    .any(|e| matches!(self.type_by_id(e.btf_type), Ok(BtfType::Func(_)))),
+                _ => false,
+            }))
+        .then(|| {
+            let name_offset = self.add_string("int");
+            let int_type_id = self.add_type(BtfType::Int(Int::new(
+                name_offset,
+                4,
+                IntEncoding::Signed,
+                0,
+            )));
+            let name_offset = self.add_string("__ksym_placeholder");
+            self.add_type(BtfType::Var(Var::new(
+                name_offset,
+                int_type_id,
+                VarLinkage::Global,
+            )))
+        });
         let mut types = mem::take(&mut self.types);
         for i in 0..types.types.len() {
             let t = &mut types.types[i];
@@ -562,8 +740,29 @@ impl Btf {
                         d.name_offset = self.add_string(&fixed_name);
                     }
 
-                    // There are some cases when the compiler does indeed populate the size.
-                    if d.size > 0 {
+                    // The kernel only accepts VAR entries in a DATASEC, so kfuncs
+                    // declared in .ksyms are pointed at a placeholder VAR, each
+                    // in its own slot. This mirrors libbpf.
+                    if let (".ksyms", Some(placeholder_id)) = (name.as_str(), ksym_placeholder_id) {
+                        let mut entries = mem::take(&mut d.entries);
+                        let mut fixed_section = d.clone();
+                        let size = mem::size_of::<i32>() as u32;
+                        for (offset, e) in (0..).step_by(size as usize).zip(entries.iter_mut()) {
+                            if let BtfType::Func(_) = types.type_by_id(e.btf_type)? {
+                                e.btf_type = placeholder_id;
+                            }
+                            e.offset = offset;
+                            e.size = size;
+                        }
+                        debug!("{kind} {name}: fixup {} kfunc entries", entries.len());
+                        fixed_section.size = size * entries.len() as u32;
+                        fixed_section.entries = entries;
+
+                        // Must reborrow here because we borrow `types` immutably above.
+                        let t = &mut types.types[i];
+                        *t = BtfType::DataSec(fixed_section);
+                    } else if d.size > 0 {
+                        // There are some cases when the compiler does indeed populate the size.
                         debug!("{kind} {name}: size fixup not required");
                     } else {
                         // We need to get the size of the section from the ELF file.
@@ -644,6 +843,16 @@ impl Btf {
                     if !features.btf_func {
                         debug!("{kind}: not supported. replacing with TYPEDEF");
                         *t = BtfType::Typedef(Typedef::new(ty.name_offset, ty.btf_type));
+                    } else if ty.linkage() == FuncLinkage::Extern {
+                        // The kernel rejects BTF_FUNC_EXTERN, which is used to
+                        // declare kfuncs.
+                        let linkage = if features.btf_func_global {
+                            FuncLinkage::Global
+                        } else {
+                            FuncLinkage::Static
+                        };
+                        debug!("changing extern FUNC {name} linkage to {linkage:?}");
+                        ty.set_linkage(linkage);
                     } else if !features.btf_func_global
                         || name == "memset"
                         || name == "memcpy"
@@ -1319,6 +1528,93 @@ mod tests {
         assert_eq!(btf.string_at(5).unwrap(), "widget");
     }
 
+    #[test]
+    fn test_parse_split_btf() {
+        let mut base = Btf::new();
+        let name_offset = base.add_string("int");
+        let int_type_id = base.add_type(BtfType::Int(Int::new(
+            name_offset,
+            4,
+            IntEncoding::Signed,
+            0,
+        )));
+
+        // Split BTF string offsets start after the strings of the base.
+        let mut split = Btf::new();
+        split.strings.clear();
+        split.header.str_len = 0;
+        let name_offset = base.strings.len() as u32 + split.add_string("widget");
+        split.add_type(BtfType::Typedef(Typedef::new(name_offset, int_type_id)));
+
+        let btf = Btf::parse_split(&base, &split.to_bytes(), Endianness::default()).unwrap();
+        let widget_id = btf
+            .id_by_type_name_kind("widget", BtfKind::Typedef)
+            .unwrap();
+        assert_eq!(widget_id, int_type_id + 1);
+        assert_eq!(btf.resolve_type(widget_id).unwrap(), int_type_id);
+        assert_eq!(
+            btf.id_by_type_name_kind("int", BtfKind::Int).unwrap(),
+            int_type_id
+        );
+        assert_eq!(
+            btf.split_types().map(|(id, _)| id).collect::<Vec<_>>(),
+            [widget_id]
+        );
+
+        let btf = Btf::parse(&btf.to_bytes(), Endianness::default()).unwrap();
+        assert_eq!(
+            btf.type_name(btf.type_by_id(widget_id).unwrap()).unwrap(),
+            "widget"
+        );
+    }
+
+    #[test]
+    fn test_split_id_by_type_name_kind() {
+        let mut base = Btf::new();
+        let int_name_offset = base.add_string("int");
+        let int_type_id = base.add_type(BtfType::Int(Int::new(
+            int_name_offset,
+            4,
+            IntEncoding::Signed,
+            0,
+        )));
+
+        // One name is in the split strings, the other refers to the base ones.
+        let mut split = Btf::new();
+        split.strings.clear();
+        split.header.str_len = 0;
+        let name_offset = base.strings.len() as u32 + split.add_string("widget");
+        split.add_type(BtfType::Typedef(Typedef::new(name_offset, int_type_id)));
+        split.add_type(BtfType::Typedef(Typedef::new(int_name_offset, int_type_id)));
+        let data = split.to_bytes();
+
+        let btf = Btf::parse_split(&base, &data, Endianness::default()).unwrap();
+        for name in ["widget", "int"] {
+            assert_eq!(
+                Btf::split_id_by_type_name_kind(
+                    &base,
+                    &data,
+                    Endianness::default(),
+                    name,
+                    BtfKind::Typedef
+                )
+                .unwrap(),
+                btf.id_by_type_name_kind(name, BtfKind::Typedef).unwrap(),
+            );
+        }
+        // Types of the base aren't searched.
+        assert_matches!(
+            Btf::split_id_by_type_name_kind(
+                &base,
+                &data,
+                Endianness::default(),
+                "int",
+                BtfKind::Int
+            ),
+            Err(BtfError::UnknownBtfTypeName { .. })
+        );
+    }
+
     #[test]
     fn test_fixup_ptr() {
         let mut btf = Btf::new();
@@ -1658,6 +1954,71 @@ mod tests {
         Btf::parse(&raw, Endianness::default()).unwrap();
     }
 
+    #[test]
+    fn test_fixup_ksyms() {
+        let mut btf = Btf::new();
+        let func_proto_type_id = btf.add_type(BtfType::FuncProto(FuncProto::new(vec![], 0)));
+        let name_offset = btf.add_string("bpf_kfunc");
+        let func_type_id = btf.add_type(BtfType::Func(Func::new(
+            name_offset,
+            func_proto_type_id,
+            FuncLinkage::Extern,
+        )));
+        let name_offset = btf.add_string("bpf_other_kfunc");
+        let other_func_type_id = btf.add_type(BtfType::Func(Func::new(
+            name_offset,
+            func_proto_type_id,
+            FuncLinkage::Extern,
+        )));
+
+        let name_offset = btf.add_string(".ksyms");
+        let entries = [func_type_id, other_func_type_id]
+            .into_iter()
+            .map(|btf_type| DataSecEntry {
+                btf_type,
+                offset: 0,
+                size: 0,
+            })
+            .collect();
+        let datasec_type_id = btf.add_type(BtfType::DataSec(DataSec::new(name_offset, entries, 0)));
+
+        let features = BtfFeatures {
+            btf_func: true,
+            btf_func_global: true,
+            btf_datasec: true,
+            ..Default::default()
+        };
+
+        btf.fixup_and_sanitize(&HashMap::new(), &HashMap::new(), &features)
+            .unwrap();
+
+        assert_matches!(btf.type_by_id(func_type_id).unwrap(), BtfType::Func(fixed) => {
+            assert_eq!(fixed.linkage(), FuncLinkage::Global);
+        });
+        assert_matches!(btf.type_by_id(datasec_type_id).unwrap(), BtfType::DataSec(fixed) => {
+            assert_eq!(fixed.size, 8);
+            assert_matches!(*fixed.entries, [
+                    DataSecEntry {
+                        btf_type: first,
+                        offset: 0,
+                        size: 4,
+                    },
+                    DataSecEntry {
+                        btf_type: second,
+                        offset: 4,
+                        size: 4,
+                    },
+                ] => {
+                    assert_eq!(first, second);
+                    assert_matches!(btf.type_by_id(first).unwrap(), BtfType::Var(_));
+                }
+            );
+        });
+        // Ensure we can convert to bytes and back again
+        let raw = btf.to_bytes();
+        Btf::parse(&raw, Endianness::default()).unwrap();
+    }
+
     #[test]
     fn test_sanitize_mem_builtins() {
         let mut btf = Btf::new();
@@ -4,9 +4,11 @@
 mod btf;
 mod info;
 mod relocation;
+mod struct_ops;
 mod types;
 
 pub use btf::*;
 pub use info::*;
 pub use relocation::BtfRelocationError;
+pub use struct_ops::StructOpsRelocationError;
 pub use types::*;
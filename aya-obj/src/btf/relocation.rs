@@ -13,8 +13,8 @@ use object::SectionIndex;
 use crate::{
     Function, Object,
     btf::{
-        Array, Btf, BtfError, BtfMember, BtfType, IntEncoding, MAX_SPEC_LEN, Struct, Union,
-        fields_are_compatible, types_are_compatible,
+        Array, Btf, BtfError, BtfKind, BtfMember, BtfType, IntEncoding, MAX_SPEC_LEN, Struct,
+        Union, fields_are_compatible, types_are_compatible,
     },
     generated::{
         BPF_ALU, BPF_ALU64, BPF_B, BPF_CALL, BPF_DW, BPF_H, BPF_JMP, BPF_K, BPF_LD, BPF_LDX,
@@ -220,6 +220,20 @@ impl Relocation {
 impl Object {
     /// Relocates programs inside this object file with loaded BTF info.
     pub fn relocate_btf(&mut self, target_btf: &Btf) -> Result<(), BtfRelocationError> {
+        self.relocate_btf_with_modules(target_btf, &[])
+    }
+
+    /// Relocates programs inside this object file with loaded BTF info,
+    /// including the BTF of kernel modules.
+    ///
+    /// Types are looked up in `target_btf` first. Types that can't be found
+    /// there are looked up in `module_btfs`, the split BTF of kernel modules
+    /// using `target_btf` as their base.
+    pub fn relocate_btf_with_modules(
+        &mut self,
+        target_btf: &Btf,
+        module_btfs: &[Btf],
+    ) -> Result<(), BtfRelocationError> {
         let (local_btf, btf_ext) = match (&self.btf, &self.btf_ext) {
             (Some(btf), Some(btf_ext)) => (btf, btf_ext),
             _ => return Ok(()),
@@ -249,6 +263,7 @@ impl Object {
                 relos,
                 local_btf,
                 target_btf,
+                module_btfs,
                 &mut candidates_cache,
             ) {
                 Ok(()) => {}
@@ -263,6 +278,40 @@ impl Object {
 
         Ok(())
     }
+
+    /// Returns the names and kinds of the types referenced by BTF relocations
+    /// which can't be found in `target_btf`.
+    ///
+    /// These types are usually defined by kernel modules, whose BTF must then
+    /// be passed to [`Object::relocate_btf_with_modules`].
+    pub fn unresolved_btf_types(
+        &self,
+        target_btf: &Btf,
+    ) -> Result<Vec<(String, BtfKind)>, BtfError> {
+        let (local_btf, btf_ext) = match (&self.btf, &self.btf_ext) {
+            (Some(btf), Some(btf_ext)) => (btf, btf_ext),
+            _ => return Ok(Vec::new()),
+        };
+
+        let mut types = Vec::new();
+        for (_, relos) in btf_ext.relocations() {
+            for rel in relos {
+                if matches!(rel.kind, RelocationKind::TypeIdLocal) {
+                    continue;
+                }
+                let local_ty = local_btf.type_by_id(rel.type_id)?;
+                let local_name = &*local_btf.type_name(local_ty)?;
+                if !find_candidates(local_ty, local_name, target_btf)?.is_empty() {
+                    continue;
+                }
+                let ty = (flavorless_name(local_name).to_owned(), local_ty.kind());
+                if !types.contains(&ty) {
+                    types.push(ty);
+                }
+            }
+        }
+        Ok(types)
+    }
 }
 
 fn is_relocation_inside_function(
@@ -301,6 +350,7 @@ fn relocate_btf_functions<'target>(
     relos: &[Relocation],
     local_btf: &Btf,
     target_btf: &'target Btf,
+    module_btfs: &'target [Btf],
     candidates_cache: &mut HashMap<u32, Vec<Candidate<'target>>>,
 ) -> Result<(), RelocationError> {
     let mut last_function_opt: Option<&mut Function> = None;
@@ -333,10 +383,16 @@ fn relocate_btf_functions<'target>(
                 let candidates = match candidates_cache.get(&rel.type_id) {
                     Some(cands) => cands,
                     None => {
-                        candidates_cache.insert(
-                            rel.type_id,
-                            find_candidates(local_ty, local_name, target_btf)?,
-                        );
+                        let mut candidates = find_candidates(local_ty, local_name, target_btf)?;
+                        // Like libbpf, only look at modules when the kernel
+                        // itself doesn't have a matching type.
+                        for module_btf in module_btfs {
+                            if !candidates.is_empty() {
+                                break;
+                            }
+                            candidates = find_candidates(local_ty, local_name, module_btf)?;
+                        }
+                        candidates_cache.insert(rel.type_id, candidates);
                         candidates_cache.get(&rel.type_id).unwrap()
                     }
                 };
@@ -354,7 +410,7 @@ fn relocate_btf_functions<'target>(
             }
         };
 
-        let comp_rel = if !matches.is_empty() {
+        let (comp_rel, target_btf) = if !matches.is_empty() {
             let mut matches = matches.into_iter();
             let (_, target_spec, target_comp_rel) = matches.next().unwrap();
 
@@ -382,12 +438,12 @@ fn relocate_btf_functions<'target>(
                     candidates: conflicts,
                 });
             }
-            target_comp_rel
+            (target_comp_rel, target_spec.btf)
         } else {
             // there are no candidate matches and therefore no target_spec. This might mean
             // that matching failed, or that the relocation can be applied looking at local
             // types only (eg with EnumVariantExists, FieldExists etc)
-            ComputedRelocation::new(rel, &local_spec, None)?
+            (ComputedRelocation::new(rel, &local_spec, None)?, target_btf)
         };
 
         comp_rel.apply(function, rel, local_btf, target_btf)?;
@@ -409,7 +465,7 @@ fn find_candidates<'target>(
 ) -> Result<Vec<Candidate<'target>>, BtfError> {
     let mut candidates = Vec::new();
     let local_name = flavorless_name(local_name);
-    for (type_id, ty) in target_btf.types().enumerate() {
+    for (type_id, ty) in target_btf.split_types() {
         if local_ty.kind() != ty.kind() {
             continue;
         }
@@ -422,7 +478,7 @@ fn find_candidates<'target>(
             name: name.to_owned(),
             btf: target_btf,
             _ty: ty,
-            type_id: type_id as u32,
+            type_id,
         });
     }
 
@@ -0,0 +1,385 @@
+use alloc::{
+    format,
+    string::{String, ToString as _},
+    vec,
+};
+
+use crate::{
+    Object,
+    btf::{Btf, BtfError, BtfKind, BtfType, Struct},
+    maps::{Map, StructOpsMap},
+};
+
+/// The error type returned by [`Object::relocate_struct_ops`].
+#[derive(thiserror::Error, Debug)]
+#[error("error relocating struct_ops map `{map}`")]
+pub struct StructOpsRelocationError {
+    /// The map name
+    pub map: String,
+    #[source]
+    /// The original error
+    error: StructOpsError,
+}
+
+/// struct_ops relocation failures
+#[derive(thiserror::Error, Debug)]
+enum StructOpsError {
+    /// BTF error
+    #[error(transparent)]
+    BtfError(#[from] BtfError),
+
+    /// The kernel struct wasn't found in the target BTF
+    #[error("struct `{type_name}` not found in the target BTF")]
+    TypeNotFound {
+        /// The type name
+        type_name: String,
+    },
+
+    /// The kernel struct doesn't have a member with the same name
+    #[error("member `{member}` not found in struct `{type_name}`")]
+    MemberNotFound {
+        /// The type name
+        type_name: String,
+        /// The member name
+        member: String,
+    },
+
+    /// The kernel member is a bitfield
+    #[error("member `{member}` of struct `{type_name}` is a bitfield")]
+    BitfieldMember {
+        /// The type name
+        type_name: String,
+        /// The member name
+        member: String,
+    },
+
+    /// The local and kernel members have different sizes
+    #[error(
+        "member `{member}` of struct `{type_name}` has size {local_size}, expected {kernel_size}"
+    )]
+    MemberSizeMismatch {
+        /// The type name
+        type_name: String,
+        /// The member name
+        member: String,
+        /// The size of the local member
+        local_size: usize,
+        /// The size of the kernel member
+        kernel_size: usize,
+    },
+
+    /// A program implements a member which isn't a function pointer
+    #[error("member `{member}` of struct `{type_name}` is not a function pointer")]
+    NotAFunctionPointer {
+        /// The type name
+        type_name: String,
+        /// The member name
+        member: String,
+    },
+}
+
+impl Object {
+    /// Relocates the struct_ops maps inside this object file against the
+    /// target BTF.
+    ///
+    /// The value of each map is rebuilt using the layout of the
+    /// `bpf_struct_ops_<type_name>` kernel struct: scalar members are copied
+    /// to the offset of the kernel member with the same name, while the index
+    /// and offset of the function pointer members implemented by programs are
+    /// recorded so that the programs can be loaded and attached.
+    pub fn relocate_struct_ops(
+        &mut self,
+        target_btf: &Btf,
+    ) -> Result<(), StructOpsRelocationError> {
+        let Some(local_btf) = &self.btf else {
+            return Ok(());
+        };
+        for (name, map) in self.maps.iter_mut() {
+            if let Map::StructOps(map) = map {
+                relocate_struct_ops_map(map, local_btf, target_btf).map_err(|error| {
+                    StructOpsRelocationError {
+                        map: name.clone(),
+                        error,
+                    }
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn struct_by_name<'a>(btf: &'a Btf, type_name: &str) -> Result<(u32, &'a Struct), StructOpsError> {
+    let type_id = btf
+        .id_by_type_name_kind(type_name, BtfKind::Struct)
+        .map_err(|_| StructOpsError::TypeNotFound {
+            type_name: type_name.to_string(),
+        })?;
+    match btf.type_by_id(type_id)? {
+        BtfType::Struct(s) => Ok((type_id, s)),
+        _ => Err(BtfError::UnexpectedBtfType { type_id }.into()),
+    }
+}
+
+fn is_func_ptr(btf: &Btf, type_id: u32) -> Result<bool, BtfError> {
+    let BtfType::Ptr(ptr) = btf.type_by_id(btf.resolve_type(type_id)?)? else {
+        return Ok(false);
+    };
+    Ok(matches!(
+        btf.type_by_id(btf.resolve_type(ptr.btf_type)?)?,
+        BtfType::FuncProto(_)
+    ))
+}
+
+fn relocate_struct_ops_map(
+    map: &mut StructOpsMap,
+    local_btf: &Btf,
+    target_btf: &Btf,
+) -> Result<(), StructOpsError> {
+    let type_name = map.type_name.as_str();
+    let (kernel_type_id, kernel_struct) = struct_by_name(target_btf, type_name)?;
+    let (kernel_value_type_id, kernel_value) =
+        struct_by_name(target_btf, &format!("bpf_struct_ops_{type_name}"))?;
+
+    // The kernel struct is embedded in the value struct, after some fields
+    // used by the kernel to keep track of the state of the map.
+    let data_offset = kernel_value
+        .members
+        .iter()
+        .find(|m| target_btf.resolve_type(m.btf_type).ok() == Some(kernel_type_id))
+        .map(|m| kernel_value.member_bit_offset(m) / 8)
+        .ok_or_else(|| StructOpsError::MemberNotFound {
+            type_name: format!("bpf_struct_ops_{type_name}"),
+            member: "data".to_string(),
+        })?;
+
+    let local_struct = match local_btf.type_by_id(map.btf_type_id)? {
+        BtfType::Struct(s) => s,
+        _ => {
+            return Err(BtfError::UnexpectedBtfType {
+                type_id: map.btf_type_id,
+            }
+            .into());
+        }
+    };
+
+    let mut data = vec![0u8; kernel_value.size as usize];
+    for local_member in &local_struct.members {
+        let member = local_btf.string_at(local_member.name_offset)?;
+        let local_offset = local_struct.member_bit_offset(local_member) / 8;
+        let local_size = local_btf.type_size(local_member.btf_type)?;
+        let local_data = map
+            .data
+            .get(local_offset..local_offset + local_size)
+            .ok_or(BtfError::InvalidTypeInfo)?;
+        let program = map.programs.iter_mut().find(|p| p.member_name == member);
+
+        let kernel_member =
+            kernel_struct.members.iter().enumerate().find(|(_, m)| {
+                target_btf.string_at(m.name_offset).ok().as_deref() == Some(&member)
+            });
+        let Some((member_index, kernel_member)) = kernel_member else {
+            // Members which are unset can safely be skipped, which allows the
+            // same program to support multiple kernel versions.
+            if program.is_none() && local_data.iter().all(|b| *b == 0) {
+                continue;
+            }
+            return Err(StructOpsError::MemberNotFound {
+                type_name: type_name.to_string(),
+                member: member.to_string(),
+            });
+        };
+        if kernel_struct.member_bit_field_size(kernel_member) != 0 {
+            return Err(StructOpsError::BitfieldMember {
+                type_name: type_name.to_string(),
+                member: member.to_string(),
+            });
+        }
+        let offset = data_offset + kernel_struct.member_bit_offset(kernel_member) / 8;
+
+        if is_func_ptr(target_btf, kernel_member.btf_type)? {
+            if let Some(program) = program {
+                program.member_index = member_index as u32;
+                program.offset = offset as u32;
+            }
+            continue;
+        }
+        if program.is_some() {
+            return Err(StructOpsError::NotAFunctionPointer {
+                type_name: type_name.to_string(),
+                member: member.to_string(),
+            });
+        }
+
+        let kernel_size = target_btf.type_size(kernel_member.btf_type)?;
+        if local_size != kernel_size {
+            return Err(StructOpsError::MemberSizeMismatch {
+                type_name: type_name.to_string(),
+                member: member.to_string(),
+                local_size,
+                kernel_size,
+            });
+        }
+        data[offset..offset + kernel_size].copy_from_slice(local_data);
+    }
+
+    map.def.value_size = kernel_value.size;
+    map.kernel_type_id = kernel_type_id;
+    map.kernel_value_type_id = kernel_value_type_id;
+    map.data = data;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString as _, vec, vec::Vec};
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::{
+        btf::{BtfMember, FuncProto, Int, IntEncoding, Ptr},
+        generated::{BPF_F_LINK, bpf_map_type::BPF_MAP_TYPE_STRUCT_OPS},
+        maps::{BtfMapDef, StructOpsProgram},
+    };
+
+    // Adds a `foo_ops` struct with the given (name, is_func_ptr) members, each
+    // taking 8 bytes, and returns its type id.
+    fn add_foo_ops(btf: &mut Btf, members: &[(&str, bool)]) -> u32 {
+        let name_offset = btf.add_string("long");
+        let long_type_id = btf.add_type(BtfType::Int(Int::new(
+            name_offset,
+            8,
+            IntEncoding::Signed,
+            0,
+        )));
+        let proto_type_id = btf.add_type(BtfType::FuncProto(FuncProto::new(vec![], long_type_id)));
+        let ptr_type_id = btf.add_type(BtfType::Ptr(Ptr::new(0, proto_type_id)));
+        let members = members
+            .iter()
+            .enumerate()
+            .map(|(i, (name, is_func_ptr))| BtfMember {
+                name_offset: btf.add_string(name),
+                btf_type: if *is_func_ptr {
+                    ptr_type_id
+                } else {
+                    long_type_id
+                },
+                offset: i as u32 * 64,
+            })
+            .collect::<Vec<_>>();
+        let size = members.len() as u32 * 8;
+        let name_offset = btf.add_string("foo_ops");
+        btf.add_type(BtfType::Struct(Struct::new(name_offset, members, size)))
+    }
+
+    fn target_btf() -> Btf {
+        let mut btf = Btf::new();
+        let struct_type_id = add_foo_ops(&mut btf, &[("init", true), ("flags", false)]);
+        let name_offset = btf.add_string("int");
+        let int_type_id =
+            btf.add_type(BtfType::Int(Int::new(name_offset, 4, IntEncoding::None, 0)));
+        let refcnt_offset = btf.add_string("refcnt");
+        let data_offset = btf.add_string("data");
+        let name_offset = btf.add_string("bpf_struct_ops_foo_ops");
+        btf.add_type(BtfType::Struct(Struct::new(
+            name_offset,
+            vec![
+                BtfMember {
+                    name_offset: refcnt_offset,
+                    btf_type: int_type_id,
+                    offset: 0,
+                },
+                BtfMember {
+                    name_offset: data_offset,
+                    btf_type: struct_type_id,
+                    offset: 64,
+                },
+            ],
+            24,
+        )));
+        btf
+    }
+
+    fn fake_obj(members: &[(&str, bool)], data: Vec<u8>) -> Object {
+        let mut obj = Object::new(
+            object::Endianness::Little,
+            alloc::ffi::CString::new("GPL").unwrap(),
+            None,
+        );
+        let mut btf = Btf::new();
+        let btf_type_id = add_foo_ops(&mut btf, members);
+        obj.btf = Some(btf);
+        obj.maps.insert(
+            "my_ops".to_string(),
+            Map::StructOps(StructOpsMap {
+                def: BtfMapDef {
+                    map_type: BPF_MAP_TYPE_STRUCT_OPS as u32,
+                    key_size: 4,
+                    value_size: data.len() as u32,
+                    max_entries: 1,
+                    map_flags: BPF_F_LINK,
+                    ..Default::default()
+                },
+                section_index: 0,
+                symbol_index: 0,
+                type_name: "foo_ops".to_string(),
+                btf_type_id,
+                kernel_type_id: 0,
+                kernel_value_type_id: 0,
+                programs: vec![StructOpsProgram {
+                    member_name: "init".to_string(),
+                    program_name: "my_init".to_string(),
+                    member_index: 0,
+                    offset: 0,
+                }],
+                data,
+            }),
+        );
+        obj
+    }
+
+    #[test]
+    fn test_relocate_struct_ops() {
+        // The local struct has its members in a different order than the
+        // kernel one, and an unset member unknown to the kernel.
+        let mut obj = fake_obj(
+            &[("flags", false), ("unknown", false), ("init", true)],
+            [42u64, 0, 0].iter().flat_map(|v| v.to_ne_bytes()).collect(),
+        );
+        let target_btf = target_btf();
+        obj.relocate_struct_ops(&target_btf).unwrap();
+
+        assert_matches!(obj.maps.get("my_ops"), Some(Map::StructOps(m)) => {
+            assert_eq!(
+                m.kernel_type_id(),
+                target_btf.id_by_type_name_kind("foo_ops", BtfKind::Struct).unwrap()
+            );
+            assert_eq!(
+                m.kernel_value_type_id(),
+                target_btf
+                    .id_by_type_name_kind("bpf_struct_ops_foo_ops", BtfKind::Struct)
+                    .unwrap()
+            );
+            assert_eq!(m.def.value_size, 24);
+            assert_eq!(&m.data[..16], &[0; 16]);
+            assert_eq!(&m.data[16..], &42u64.to_ne_bytes());
+            assert_matches!(m.programs(), [StructOpsProgram { member_index: 0, offset: 8, .. }]);
+        });
+    }
+
+    #[test]
+    fn test_relocate_struct_ops_unknown_member() {
+        let mut obj = fake_obj(
+            &[("init", true), ("unknown", false)],
+            [0u64, 1].iter().flat_map(|v| v.to_ne_bytes()).collect(),
+        );
+        assert_matches!(
+            obj.relocate_struct_ops(&target_btf()),
+            Err(StructOpsRelocationError {
+                map,
+                error: StructOpsError::MemberNotFound { member, .. },
+            }) if map == "my_ops" && member == "unknown"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Map struct and type bindings.
 
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 use core::mem;
 
 use crate::{EbpfSectionKind, InvalidTypeBinding};
@@ -67,6 +67,8 @@ pub struct BtfMapDef {
     pub btf_key_type_id: u32,
     /// BTF type id of the map value
     pub btf_value_type_id: u32,
+    /// The map specific `map_extra` value
+    pub map_extra: u64,
 }
 
 /// The pinning type
@@ -138,6 +140,8 @@ pub enum Map {
     Legacy(LegacyMap),
     /// A map defined in the `.maps` section
     Btf(BtfMap),
+    /// A map defined in the `.struct_ops` or `.struct_ops.link` section
+    StructOps(StructOpsMap),
 }
 
 impl Map {
@@ -146,6 +150,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.def.map_type,
             Map::Btf(m) => m.def.map_type,
+            Map::StructOps(m) => m.def.map_type,
         }
     }
 
@@ -154,6 +159,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.def.key_size,
             Map::Btf(m) => m.def.key_size,
+            Map::StructOps(m) => m.def.key_size,
         }
     }
 
@@ -162,6 +168,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.def.value_size,
             Map::Btf(m) => m.def.value_size,
+            Map::StructOps(m) => m.def.value_size,
         }
     }
 
@@ -170,6 +177,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.def.value_size = size,
             Map::Btf(m) => m.def.value_size = size,
+            Map::StructOps(m) => m.def.value_size = size,
         }
     }
 
@@ -178,6 +186,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.def.max_entries,
             Map::Btf(m) => m.def.max_entries,
+            Map::StructOps(m) => m.def.max_entries,
         }
     }
 
@@ -186,6 +195,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.def.max_entries = v,
             Map::Btf(m) => m.def.max_entries = v,
+            Map::StructOps(m) => m.def.max_entries = v,
         }
     }
 
@@ -194,6 +204,19 @@ impl Map {
         match self {
             Map::Legacy(m) => m.def.map_flags,
             Map::Btf(m) => m.def.map_flags,
+            Map::StructOps(m) => m.def.map_flags,
+        }
+    }
+
+    /// Returns the map specific `map_extra` value
+    ///
+    /// For `BPF_MAP_TYPE_ARENA` maps this is the address at which the arena is
+    /// mapped in userspace, or zero to let the kernel pick one.
+    pub fn map_extra(&self) -> u64 {
+        match self {
+            Map::Legacy(m) => m.map_extra,
+            Map::Btf(m) => m.def.map_extra,
+            Map::StructOps(m) => m.def.map_extra,
         }
     }
 
@@ -202,6 +225,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.def.pinning,
             Map::Btf(m) => m.def.pinning,
+            Map::StructOps(m) => m.def.pinning,
         }
     }
 
@@ -210,6 +234,7 @@ impl Map {
         match self {
             Map::Legacy(m) => &m.data,
             Map::Btf(m) => &m.data,
+            Map::StructOps(m) => &m.data,
         }
     }
 
@@ -218,6 +243,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.data.as_mut(),
             Map::Btf(m) => m.data.as_mut(),
+            Map::StructOps(m) => m.data.as_mut(),
         }
     }
 
@@ -226,6 +252,7 @@ impl Map {
         match self {
             Map::Legacy(m) => m.section_index,
             Map::Btf(m) => m.section_index,
+            Map::StructOps(m) => m.section_index,
         }
     }
 
@@ -234,6 +261,38 @@ impl Map {
         match self {
             Map::Legacy(m) => m.section_kind,
             Map::Btf(_) => EbpfSectionKind::BtfMaps,
+            Map::StructOps(_) => EbpfSectionKind::StructOps,
+        }
+    }
+
+    /// Returns the definition of the inner map template of a map-in-map.
+    ///
+    /// Only `BPF_MAP_TYPE_ARRAY_OF_MAPS` and `BPF_MAP_TYPE_HASH_OF_MAPS` maps
+    /// have a template, which is used to create the map whose file descriptor
+    /// is passed as `inner_map_fd` when creating the outer map.
+    pub fn inner(&self) -> Option<Map> {
+        match self {
+            Map::Legacy(m) => m.inner_def.map(|def| {
+                Map::Legacy(LegacyMap {
+                    def,
+                    section_index: m.section_index,
+                    section_kind: m.section_kind,
+                    symbol_index: None,
+                    inner_def: None,
+                    map_extra: 0,
+                    data: Vec::new(),
+                })
+            }),
+            Map::Btf(m) => m.inner_def.map(|def| {
+                Map::Btf(BtfMap {
+                    def,
+                    section_index: m.section_index,
+                    symbol_index: None,
+                    inner_def: None,
+                    data: Vec::new(),
+                })
+            }),
+            Map::StructOps(_) => None,
         }
     }
 
@@ -244,7 +303,8 @@ impl Map {
     pub fn symbol_index(&self) -> Option<usize> {
         match self {
             Map::Legacy(m) => m.symbol_index,
-            Map::Btf(m) => Some(m.symbol_index),
+            Map::Btf(m) => m.symbol_index,
+            Map::StructOps(m) => Some(m.symbol_index),
         }
     }
 }
@@ -267,16 +327,121 @@ pub struct LegacyMap {
     /// symbols to relocate those since they don't contain multiple maps, but
     /// are just a flat array of bytes.
     pub symbol_index: Option<usize>,
+    // The definition of the inner map template, for map-in-map types, see
+    // `LegacyMap::inner_def`.
+    pub(crate) inner_def: Option<bpf_map_def>,
+    // The map specific `map_extra` value, see `Map::map_extra`.
+    //
+    // Legacy arena definitions carry it as a `u64` immediately following the
+    // `bpf_map_def`.
+    pub(crate) map_extra: u64,
     /// The map data
     pub data: Vec<u8>,
 }
 
+impl LegacyMap {
+    /// Creates a legacy map from its definition.
+    pub fn new(
+        def: bpf_map_def,
+        section_index: usize,
+        section_kind: EbpfSectionKind,
+        symbol_index: Option<usize>,
+        data: Vec<u8>,
+    ) -> Self {
+        Self {
+            def,
+            section_index,
+            section_kind,
+            symbol_index,
+            inner_def: None,
+            map_extra: 0,
+            data,
+        }
+    }
+
+    /// Returns the definition of the inner map template, for map-in-map types.
+    ///
+    /// Legacy map-in-map definitions carry the template as a second
+    /// [`bpf_map_def`] immediately following the outer one.
+    pub fn inner_def(&self) -> Option<&bpf_map_def> {
+        self.inner_def.as_ref()
+    }
+
+    /// Sets the definition of the inner map template, for map-in-map types.
+    pub fn set_inner_def(&mut self, inner_def: Option<bpf_map_def>) {
+        self.inner_def = inner_def;
+    }
+}
+
 /// A BTF-defined map, most likely from a `.maps` section.
 #[derive(Debug, Clone)]
 pub struct BtfMap {
+    /// The definition of the map
+    pub def: BtfMapDef,
+    pub(crate) section_index: usize,
+    pub(crate) symbol_index: Option<usize>,
+    pub(crate) inner_def: Option<BtfMapDef>,
+    pub(crate) data: Vec<u8>,
+}
+
+/// A struct_ops map, most likely from a `.struct_ops.link` section.
+///
+/// The value of a struct_ops map is an instance of a kernel struct, such as
+/// `tcp_congestion_ops`, whose function pointer members are implemented by eBPF
+/// programs. The local definition of the struct is matched against the kernel
+/// one by [`Object::relocate_struct_ops`](crate::Object::relocate_struct_ops),
+/// which must be called before the map can be created.
+#[derive(Debug, Clone)]
+pub struct StructOpsMap {
     /// The definition of the map
     pub def: BtfMapDef,
     pub(crate) section_index: usize,
     pub(crate) symbol_index: usize,
+    pub(crate) type_name: String,
+    pub(crate) btf_type_id: u32,
+    pub(crate) kernel_type_id: u32,
+    pub(crate) kernel_value_type_id: u32,
+    pub(crate) programs: Vec<StructOpsProgram>,
     pub(crate) data: Vec<u8>,
 }
+
+impl StructOpsMap {
+    /// Returns the name of the kernel struct implemented by the map.
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Returns the BTF type id of the kernel struct implemented by the map.
+    ///
+    /// This is `0` until the map has been relocated.
+    pub fn kernel_type_id(&self) -> u32 {
+        self.kernel_type_id
+    }
+
+    /// Returns the BTF type id of the `bpf_struct_ops_<type_name>` kernel
+    /// struct, which wraps the kernel struct and is the actual value type of
+    /// the map.
+    ///
+    /// This is `0` until the map has been relocated.
+    pub fn kernel_value_type_id(&self) -> u32 {
+        self.kernel_value_type_id
+    }
+
+    /// Returns the programs implementing the members of the struct.
+    pub fn programs(&self) -> &[StructOpsProgram] {
+        &self.programs
+    }
+}
+
+/// A program implementing a function pointer member of a [`StructOpsMap`].
+#[derive(Debug, Clone)]
+pub struct StructOpsProgram {
+    /// The name of the struct member
+    pub member_name: String,
+    /// The name of the program
+    pub program_name: String,
+    /// The index of the member in the kernel struct, set when the map is relocated
+    pub member_index: u32,
+    /// The offset of the member in the map value, set when the map is relocated
+    pub offset: u32,
+}
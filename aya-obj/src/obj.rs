@@ -19,13 +19,22 @@ use object::{
 
 use crate::{
     btf::{
-        Array, Btf, BtfError, BtfExt, BtfFeatures, BtfType, DataSecEntry, FuncSecInfo, LineSecInfo,
+        Array, Btf, BtfEnum64, BtfError, BtfExt, BtfFeatures, BtfType, DataSecEntry, Enum, Enum64,
+        FuncSecInfo, LineSecInfo,
     },
     generated::{
-        BPF_CALL, BPF_F_RDONLY_PROG, BPF_JMP, BPF_K, bpf_func_id::*, bpf_insn, bpf_map_info,
-        bpf_map_type::BPF_MAP_TYPE_ARRAY,
+        BPF_CALL, BPF_F_LINK, BPF_F_RDONLY_PROG, BPF_JMP, BPF_K,
+        bpf_func_id::*,
+        bpf_insn, bpf_map_info,
+        bpf_map_type::{
+            BPF_MAP_TYPE_ARENA, BPF_MAP_TYPE_ARRAY, BPF_MAP_TYPE_ARRAY_OF_MAPS,
+            BPF_MAP_TYPE_HASH_OF_MAPS, BPF_MAP_TYPE_STRUCT_OPS,
+        },
+    },
+    maps::{
+        BtfMap, BtfMapDef, LegacyMap, MINIMUM_MAP_SIZE, Map, PinningType, StructOpsMap,
+        StructOpsProgram, bpf_map_def,
     },
-    maps::{BtfMap, BtfMapDef, LegacyMap, MINIMUM_MAP_SIZE, Map, PinningType, bpf_map_def},
     programs::{
         CgroupSockAddrAttachType, CgroupSockAttachType, CgroupSockoptAttachType, XdpAttachType,
     },
@@ -208,29 +217,29 @@ pub struct Function {
 ///
 /// Currently, the following section names are not supported yet:
 /// - `flow_dissector`: `BPF_PROG_TYPE_FLOW_DISSECTOR`
-/// - `ksyscall+` or `kretsyscall+`
-/// - `usdt+`
-/// - `kprobe.multi+` or `kretprobe.multi+`: `BPF_TRACE_KPROBE_MULTI`
-/// - `lsm_cgroup+`
-/// - `lwt_in`, `lwt_out`, `lwt_seg6local`, `lwt_xmit`
-/// - `raw_tp.w+`, `raw_tracepoint.w+`
 /// - `action`
-/// - `sk_reuseport/migrate`, `sk_reuseport`
-/// - `syscall`
-/// - `struct_ops+`
-/// - `fmod_ret+`, `fmod_ret.s+`
-/// - `iter+`, `iter.s+`
 #[derive(Debug, Clone)]
 #[expect(missing_docs)]
 pub enum ProgramSection {
     KRetProbe,
     KProbe,
+    KRetProbeMulti,
+    KProbeMulti,
     UProbe {
         sleepable: bool,
     },
     URetProbe {
         sleepable: bool,
     },
+    UProbeMulti {
+        sleepable: bool,
+    },
+    URetProbeMulti {
+        sleepable: bool,
+    },
+    Usdt {
+        sleepable: bool,
+    },
     TracePoint,
     SocketFilter,
     Xdp {
@@ -254,7 +263,9 @@ pub enum ProgramSection {
     },
     LircMode2,
     PerfEvent,
-    RawTracePoint,
+    RawTracePoint {
+        writable: bool,
+    },
     Lsm {
         sleepable: bool,
     },
@@ -265,9 +276,22 @@ pub enum ProgramSection {
     FExit {
         sleepable: bool,
     },
+    FModRet {
+        sleepable: bool,
+    },
     FlowDissector,
     Extension,
     SkLookup,
+    SkReuseport {
+        migrate: bool,
+    },
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
+    Netfilter,
+    Syscall,
+    LsmCgroup,
     CgroupSock {
         attach_type: CgroupSockAttachType,
     },
@@ -275,6 +299,9 @@ pub enum ProgramSection {
     Iter {
         sleepable: bool,
     },
+    StructOps {
+        sleepable: bool,
+    },
 }
 
 impl FromStr for ProgramSection {
@@ -296,12 +323,20 @@ impl FromStr for ProgramSection {
         let kind = next()?;
 
         Ok(match kind {
-            "kprobe" => KProbe,
-            "kretprobe" => KRetProbe,
+            "kprobe" | "ksyscall" => KProbe,
+            "kretprobe" | "kretsyscall" => KRetProbe,
+            "kprobe.multi" => KProbeMulti,
+            "kretprobe.multi" => KRetProbeMulti,
             "uprobe" => UProbe { sleepable: false },
             "uprobe.s" => UProbe { sleepable: true },
             "uretprobe" => URetProbe { sleepable: false },
             "uretprobe.s" => URetProbe { sleepable: true },
+            "uprobe.multi" => UProbeMulti { sleepable: false },
+            "uprobe.multi.s" => UProbeMulti { sleepable: true },
+            "uretprobe.multi" => URetProbeMulti { sleepable: false },
+            "uretprobe.multi.s" => URetProbeMulti { sleepable: true },
+            "usdt" => Usdt { sleepable: false },
+            "usdt.s" => Usdt { sleepable: true },
             "xdp" | "xdp.frags" => Xdp {
                 frags: kind == "xdp.frags",
                 attach_type: match pieces.next() {
@@ -417,18 +452,41 @@ impl FromStr for ProgramSection {
             }
             "lirc_mode2" => LircMode2,
             "perf_event" => PerfEvent,
-            "raw_tp" | "raw_tracepoint" => RawTracePoint,
+            "raw_tp" | "raw_tracepoint" => RawTracePoint { writable: false },
+            "raw_tp.w" | "raw_tracepoint.w" => RawTracePoint { writable: true },
             "lsm" => Lsm { sleepable: false },
             "lsm.s" => Lsm { sleepable: true },
+            "lsm_cgroup" => LsmCgroup,
             "fentry" => FEntry { sleepable: false },
             "fentry.s" => FEntry { sleepable: true },
             "fexit" => FExit { sleepable: false },
             "fexit.s" => FExit { sleepable: true },
+            "fmod_ret" => FModRet { sleepable: false },
+            "fmod_ret.s" => FModRet { sleepable: true },
             "flow_dissector" => FlowDissector,
             "freplace" => Extension,
             "sk_lookup" => SkLookup,
+            "sk_reuseport" => SkReuseport {
+                migrate: match pieces.next() {
+                    None => false,
+                    Some("migrate") => true,
+                    Some(_) => {
+                        return Err(ParseError::InvalidProgramSection {
+                            section: section.to_owned(),
+                        });
+                    }
+                },
+            },
+            "lwt_in" => LwtIn,
+            "lwt_out" => LwtOut,
+            "lwt_xmit" => LwtXmit,
+            "lwt_seg6local" => LwtSeg6Local,
+            "netfilter" => Netfilter,
+            "syscall" => Syscall,
             "iter" => Iter { sleepable: false },
             "iter.s" => Iter { sleepable: true },
+            "struct_ops" => StructOps { sleepable: false },
+            "struct_ops.s" => StructOps { sleepable: true },
             _ => {
                 return Err(ParseError::InvalidProgramSection {
                     section: section.to_owned(),
@@ -511,7 +569,11 @@ impl Object {
         Ok(bpf_obj)
     }
 
-    fn new(endianness: Endianness, license: CString, kernel_version: Option<u32>) -> Object {
+    pub(crate) fn new(
+        endianness: Endianness,
+        license: CString,
+        kernel_version: Option<u32>,
+    ) -> Object {
         Object {
             endianness,
             license,
@@ -758,7 +820,7 @@ impl Object {
                 if type_name == section.name {
                     // each btf_var_secinfo contains a map
                     for info in &datasec.entries {
-                        let (map_name, def) = parse_btf_map_def(btf, info)?;
+                        let (map_name, def, inner_def) = parse_btf_map_def(btf, info)?;
                         let symbol_index =
                             maps.get(&map_name)
                                 .ok_or_else(|| ParseError::SymbolNotFound {
@@ -769,7 +831,8 @@ impl Object {
                             Map::Btf(BtfMap {
                                 def,
                                 section_index: section.index.0,
-                                symbol_index: *symbol_index,
+                                symbol_index: Some(*symbol_index),
+                                inner_def,
                                 data: Vec::new(),
                             }),
                         );
@@ -780,6 +843,128 @@ impl Object {
         Ok(())
     }
 
+    // Parses the struct_ops maps contained in a `.struct_ops` or `.struct_ops.link`
+    // section. Each variable in the section is a separate map, whose function
+    // pointer members are relocations to the programs implementing them.
+    fn parse_struct_ops(&mut self, section: &Section) -> Result<(), ParseError> {
+        let btf = self.btf.as_ref().ok_or(ParseError::NoBTF)?;
+        let symbols: HashMap<&String, &Symbol> = self
+            .symbols_by_section
+            .get(&section.index)
+            .ok_or(ParseError::NoSymbolsForSection {
+                section_name: section.name.to_owned(),
+            })?
+            .iter()
+            .filter_map(|s| {
+                let symbol = self.symbol_table.get(s).unwrap();
+                symbol.name.as_ref().map(|name| (name, symbol))
+            })
+            .collect();
+        let mut programs = HashMap::new();
+        for rel in &section.relocations {
+            let symbol =
+                self.symbol_table
+                    .get(&rel.symbol_index)
+                    .ok_or(ParseError::SymbolNotFound {
+                        name: rel.symbol_index.to_string(),
+                    })?;
+            match (symbol.name.as_ref(), symbol.kind) {
+                (Some(name), SymbolKind::Text) if !name.is_empty() => {
+                    programs.insert(rel.offset, name);
+                }
+                _ => {
+                    return Err(ParseError::InvalidSymbol {
+                        index: symbol.index,
+                        name: symbol.name.clone(),
+                    });
+                }
+            }
+        }
+
+        for t in btf.types() {
+            let BtfType::DataSec(datasec) = &t else {
+                continue;
+            };
+            match btf.type_name(t) {
+                Ok(name) if name == section.name => {}
+                _ => continue,
+            }
+            for info in &datasec.entries {
+                let var = match btf.type_by_id(info.btf_type)? {
+                    BtfType::Var(var) => var,
+                    other => {
+                        return Err(BtfError::UnexpectedBtfType {
+                            type_id: other.btf_type().unwrap_or(0),
+                        }
+                        .into());
+                    }
+                };
+                let map_name = btf.string_at(var.name_offset)?.to_string();
+                let btf_type_id = btf.resolve_type(var.btf_type)?;
+                let BtfType::Struct(s) = btf.type_by_id(btf_type_id)? else {
+                    return Err(BtfError::UnexpectedBtfType {
+                        type_id: btf_type_id,
+                    }
+                    .into());
+                };
+                let type_name = btf.string_at(s.name_offset)?.to_string();
+                let symbol = symbols
+                    .get(&map_name)
+                    .ok_or_else(|| ParseError::SymbolNotFound {
+                        name: map_name.clone(),
+                    })?;
+                let start = symbol.address as usize;
+                let data = section
+                    .data
+                    .get(start..start + s.size as usize)
+                    .ok_or_else(|| ParseError::InvalidMapDefinition {
+                        name: map_name.clone(),
+                    })?;
+                let mut map_programs = Vec::new();
+                for member in &s.members {
+                    let offset = symbol.address + (s.member_bit_offset(member) / 8) as u64;
+                    if let Some(program_name) = programs.get(&offset) {
+                        map_programs.push(StructOpsProgram {
+                            member_name: btf.string_at(member.name_offset)?.to_string(),
+                            program_name: program_name.to_string(),
+                            member_index: 0,
+                            offset: 0,
+                        });
+                    }
+                }
+                self.maps.insert(
+                    map_name,
+                    Map::StructOps(StructOpsMap {
+                        def: BtfMapDef {
+                            map_type: BPF_MAP_TYPE_STRUCT_OPS as u32,
+                            key_size: mem::size_of::<u32>() as u32,
+                            value_size: s.size,
+                            max_entries: 1,
+                            // Maps in `.struct_ops` are registered as soon as their value is
+                            // set, while the ones in `.struct_ops.link` are registered by
+                            // creating a link.
+                            map_flags: if section.name == ".struct_ops.link" {
+                                BPF_F_LINK
+                            } else {
+                                0
+                            },
+                            ..Default::default()
+                        },
+                        section_index: section.index.0,
+                        symbol_index: symbol.index,
+                        type_name,
+                        btf_type_id,
+                        kernel_type_id: 0,
+                        kernel_value_type_id: 0,
+                        programs: map_programs,
+                        data: data.to_vec(),
+                    }),
+                );
+            }
+        }
+        Ok(())
+    }
+
     // Parses multiple map definition contained in a single `maps` section (which is
     // different from `.maps` which is used for BTF). We can tell where each map is
     // based on the symbol table.
@@ -803,6 +988,8 @@ impl Object {
                 .as_ref()
                 .ok_or(ParseError::MapSymbolNameNotFound { i: *i })?;
             let def = parse_map_def(name, data)?;
+            let inner_def = parse_inner_map_def(name, &def, data)?;
+            let map_extra = parse_map_extra(&def, data);
             maps.insert(
                 name.to_string(),
                 Map::Legacy(LegacyMap {
@@ -810,6 +997,8 @@ impl Object {
                     section_kind: section.kind,
                     symbol_index: Some(sym.index),
                     def,
+                    inner_def,
+                    map_extra,
                     data: Vec::new(),
                 }),
             );
@@ -836,6 +1025,7 @@ impl Object {
             EbpfSectionKind::Btf => self.parse_btf(&section)?,
             EbpfSectionKind::BtfExt => self.parse_btf_ext(&section)?,
             EbpfSectionKind::BtfMaps => self.parse_btf_maps(&section)?,
+            EbpfSectionKind::StructOps => self.parse_struct_ops(&section)?,
             EbpfSectionKind::Maps => {
                 // take out self.maps so we can borrow the iterator below
                 // without cloning or collecting
@@ -1026,6 +1216,8 @@ pub enum EbpfSectionKind {
     License,
     /// `version`
     Version,
+    /// `.struct_ops` or `.struct_ops.link`
+    StructOps,
 }
 
 impl EbpfSectionKind {
@@ -1050,6 +1242,8 @@ impl EbpfSectionKind {
             EbpfSectionKind::Btf
         } else if name == ".BTF.ext" {
             EbpfSectionKind::BtfExt
+        } else if name == ".struct_ops" || name == ".struct_ops.link" {
+            EbpfSectionKind::StructOps
         } else {
             EbpfSectionKind::Undefined
         }
@@ -1176,6 +1370,28 @@ fn get_map_field(btf: &Btf, type_id: u32) -> Result<u32, BtfError> {
     Ok(arr.len)
 }
 
+// Fields declared with `__ulong` are encoded as an enum whose only variant holds
+// the value. `__uint` fields are accepted too.
+fn get_map_field_long(btf: &Btf, type_id: u32) -> Result<u64, BtfError> {
+    match btf.type_by_id(type_id)? {
+        BtfType::Ptr(_) => get_map_field(btf, type_id).map(u64::from),
+        BtfType::Enum(Enum { variants, .. }) if variants.len() == 1 => {
+            Ok(u64::from(variants[0].value))
+        }
+        BtfType::Enum64(Enum64 { variants, .. }) if variants.len() == 1 => {
+            let BtfEnum64 {
+                value_low,
+                value_high,
+                ..
+            } = variants[0];
+            Ok((u64::from(value_high) << 32) | u64::from(value_low))
+        }
+        other => Err(BtfError::UnexpectedBtfType {
+            type_id: other.btf_type().unwrap_or(0),
+        }),
+    }
+}
+
 // Parsed '.bss' '.data' and '.rodata' sections. These sections are arrays of
 // bytes and are relocated based on their section index.
 fn parse_data_map_section(section: &Section) -> Result<Map, ParseError> {
@@ -1216,6 +1432,8 @@ fn parse_data_map_section(section: &Section) -> Result<Map, ParseError> {
         // Data maps don't require symbols to be relocated
         symbol_index: None,
         def,
+        inner_def: None,
+        map_extra: 0,
         data,
     }))
 }
@@ -1240,7 +1458,40 @@ fn parse_map_def(name: &str, data: &[u8]) -> Result<bpf_map_def, ParseError> {
     }
 }
 
-fn parse_btf_map_def(btf: &Btf, info: &DataSecEntry) -> Result<(String, BtfMapDef), BtfError> {
+// Legacy map-in-map definitions are followed by the definition of their inner
+// map template.
+fn parse_inner_map_def(
+    name: &str,
+    def: &bpf_map_def,
+    data: &[u8],
+) -> Result<Option<bpf_map_def>, ParseError> {
+    if def.map_type != BPF_MAP_TYPE_ARRAY_OF_MAPS as u32
+        && def.map_type != BPF_MAP_TYPE_HASH_OF_MAPS as u32
+    {
+        return Ok(None);
+    }
+    match data.get(mem::size_of::<bpf_map_def>()..) {
+        Some(data) if !data.is_empty() => parse_map_def(name, data).map(Some),
+        _ => Ok(None),
+    }
+}
+
+// Legacy arena definitions are followed by their `map_extra` value, aligned
+// as a `u64`.
+fn parse_map_extra(def: &bpf_map_def, data: &[u8]) -> u64 {
+    if def.map_type != BPF_MAP_TYPE_ARENA as u32 {
+        return 0;
+    }
+    let offset = mem::size_of::<bpf_map_def>().next_multiple_of(mem::align_of::<u64>());
+    data.get(offset..offset + mem::size_of::<u64>())
+        .map(|data| u64::from_ne_bytes(data.try_into().unwrap()))
+        .unwrap_or_default()
+}
+
+fn parse_btf_map_def(
+    btf: &Btf,
+    info: &DataSecEntry,
+) -> Result<(String, BtfMapDef, Option<BtfMapDef>), BtfError> {
     let ty = match btf.type_by_id(info.btf_type)? {
         BtfType::Var(var) => var,
         other => {
@@ -1250,10 +1501,21 @@ fn parse_btf_map_def(btf: &Btf, info: &DataSecEntry) -> Result<(String, BtfMapDe
         }
     };
     let map_name = btf.string_at(ty.name_offset)?;
+    let (map_def, inner_def) = parse_btf_map_struct(btf, ty.btf_type)?;
+    Ok((map_name.to_string(), map_def, inner_def))
+}
+
+// Parses the struct describing a BTF map. For map-in-maps, the `values` member
+// is an array of pointers to the struct describing the inner map template.
+fn parse_btf_map_struct(
+    btf: &Btf,
+    type_id: u32,
+) -> Result<(BtfMapDef, Option<BtfMapDef>), BtfError> {
     let mut map_def = BtfMapDef::default();
+    let mut inner_def = None;
 
     // Safety: union
-    let root_type = btf.resolve_type(ty.btf_type)?;
+    let root_type = btf.resolve_type(type_id)?;
     let s = match btf.type_by_id(root_type)? {
         BtfType::Struct(s) => s,
         other => {
@@ -1297,12 +1559,38 @@ fn parse_btf_map_def(btf: &Btf, info: &DataSecEntry) -> Result<(String, BtfMapDe
             "value_size" => {
                 map_def.value_size = get_map_field(btf, m.btf_type)?;
             }
+            "values" => {
+                let arr = match btf.type_by_id(m.btf_type)? {
+                    BtfType::Array(Array { array, .. }) => array,
+                    other => {
+                        return Err(BtfError::UnexpectedBtfType {
+                            type_id: other.btf_type().unwrap_or(0),
+                        });
+                    }
+                };
+                let pty = match btf.type_by_id(arr.element_type)? {
+                    BtfType::Ptr(pty) => pty,
+                    other => {
+                        return Err(BtfError::UnexpectedBtfType {
+                            type_id: other.btf_type().unwrap_or(0),
+                        });
+                    }
+                };
+                let (def, _) = parse_btf_map_struct(btf, pty.btf_type)?;
+                inner_def = Some(def);
+                // The values of a map-in-map are the file descriptors of the
+                // inner maps.
+                map_def.value_size = mem::size_of::<u32>() as u32;
+            }
             "max_entries" => {
                 map_def.max_entries = get_map_field(btf, m.btf_type)?;
             }
             "map_flags" => {
                 map_def.map_flags = get_map_field(btf, m.btf_type)?;
             }
+            "map_extra" => {
+                map_def.map_extra = get_map_field_long(btf, m.btf_type)?;
+            }
             "pinning" => {
                 let pinning = get_map_field(btf, m.btf_type)?;
                 map_def.pinning = PinningType::try_from(pinning).unwrap_or_else(|_| {
@@ -1316,7 +1604,7 @@ fn parse_btf_map_def(btf: &Btf, info: &DataSecEntry) -> Result<(String, BtfMapDe
             }
         }
     }
-    Ok((map_name.to_string(), map_def))
+    Ok((map_def, inner_def))
 }
 
 /// Parses a [bpf_map_info] into a [Map].
@@ -1332,9 +1620,11 @@ pub fn parse_map_info(info: bpf_map_info, pinned: PinningType) -> Map {
                 pinning: pinned,
                 btf_key_type_id: info.btf_key_type_id,
                 btf_value_type_id: info.btf_value_type_id,
+                map_extra: info.map_extra,
             },
             section_index: 0,
-            symbol_index: 0,
+            symbol_index: Some(0),
+            inner_def: None,
             data: Vec::new(),
         })
     } else {
@@ -1351,6 +1641,8 @@ pub fn parse_map_info(info: bpf_map_info, pinned: PinningType) -> Map {
             section_index: 0,
             symbol_index: None,
             section_kind: EbpfSectionKind::Undefined,
+            inner_def: None,
+            map_extra: info.map_extra,
             data: Vec::new(),
         })
     }
@@ -1419,7 +1711,10 @@ mod tests {
     use assert_matches::assert_matches;
 
     use super::*;
-    use crate::generated::btf_ext_header;
+    use crate::{
+        btf::{BtfMember, DataSec, FuncProto, Int, IntEncoding, Ptr, Struct, Var, VarLinkage},
+        generated::{BPF_F_MMAPABLE, btf_ext_header},
+    };
 
     const FAKE_INS_LEN: u64 = 8;
 
@@ -1606,6 +1901,8 @@ mod tests {
                     id: 0,
                     pinning: PinningType::None,
                 },
+                inner_def: None,
+                map_extra: 0,
                 data,
             })) if data == map_data && value_size == map_data.len() as u32
         )
@@ -1724,6 +2021,82 @@ mod tests {
         assert!(obj.maps.contains_key("foo"));
     }
 
+    #[test]
+    fn test_parse_section_map_of_maps() {
+        let mut obj = fake_obj();
+        fake_sym(
+            &mut obj,
+            0,
+            0,
+            "outer",
+            2 * mem::size_of::<bpf_map_def>() as u64,
+        );
+        let defs = [
+            bpf_map_def {
+                map_type: BPF_MAP_TYPE_ARRAY_OF_MAPS as u32,
+                key_size: 4,
+                value_size: 4,
+                max_entries: 8,
+                ..Default::default()
+            },
+            bpf_map_def {
+                map_type: BPF_MAP_TYPE_ARRAY as u32,
+                key_size: 4,
+                value_size: 8,
+                max_entries: 1,
+                ..Default::default()
+            },
+        ];
+        obj.parse_section(fake_section(
+            EbpfSectionKind::Maps,
+            "maps",
+            bytes_of(&defs),
+            None,
+        ))
+        .unwrap();
+        let map = obj.maps.get("outer").unwrap();
+        assert_matches!(map, Map::Legacy(LegacyMap { def, inner_def: Some(inner_def), .. }) => {
+            assert_eq!(def, &defs[0]);
+            assert_eq!(inner_def, &defs[1]);
+        });
+        assert_matches!(map.inner(), Some(Map::Legacy(LegacyMap { def, symbol_index: None, inner_def: None, .. })) => {
+            assert_eq!(def, defs[1]);
+        });
+    }
+
+    #[test]
+    fn test_parse_section_arena() {
+        #[repr(C)]
+        struct ArenaDef {
+            def: bpf_map_def,
+            map_extra: u64,
+        }
+
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "arena", mem::size_of::<ArenaDef>() as u64);
+        let def = bpf_map_def {
+            map_type: BPF_MAP_TYPE_ARENA as u32,
+            max_entries: 16,
+            map_flags: BPF_F_MMAPABLE,
+            ..Default::default()
+        };
+        obj.parse_section(fake_section(
+            EbpfSectionKind::Maps,
+            "maps",
+            bytes_of(&ArenaDef {
+                def,
+                map_extra: 1 << 32,
+            }),
+            None,
+        ))
+        .unwrap();
+        let map = obj.maps.get("arena").unwrap();
+        assert_matches!(map, Map::Legacy(LegacyMap { def: parsed, .. }) => {
+            assert_eq!(parsed, &def);
+        });
+        assert_eq!(map.map_extra(), 1 << 32);
+    }
+
     #[test]
     fn test_parse_multiple_program_in_same_section() {
         let mut obj = fake_obj();
@@ -1906,14 +2279,15 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_section_uprobe() {
+    fn test_parse_section_ksyscall() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+        fake_sym(&mut obj, 1, 0, "bar", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "uprobe/foo",
+                "ksyscall/openat",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -1922,47 +2296,38 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::UProbe { .. },
+                section: ProgramSection::KProbe,
                 ..
             })
         );
-    }
-
-    #[test]
-    fn test_parse_section_uprobe_sleepable() {
-        let mut obj = fake_obj();
-        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "uprobe.s/foo",
+                "kretsyscall/openat",
                 bytes_of(&fake_ins()),
-                None
+                Some(1)
             )),
             Ok(())
         );
         assert_matches!(
-            obj.programs.get("foo"),
+            obj.programs.get("bar"),
             Some(Program {
-                section: ProgramSection::UProbe {
-                    sleepable: true,
-                    ..
-                },
+                section: ProgramSection::KRetProbe,
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_uretprobe() {
+    fn test_parse_section_kprobe_multi() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "uretprobe/foo",
+                "kprobe.multi/tcp_*",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -1971,21 +2336,21 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::URetProbe { .. },
+                section: ProgramSection::KProbeMulti,
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_uretprobe_sleepable() {
+    fn test_parse_section_kretprobe_multi() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "uretprobe.s/foo",
+                "kretprobe.multi",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -1994,25 +2359,21 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::URetProbe {
-                    sleepable: true,
-                    ..
-                },
+                section: ProgramSection::KRetProbeMulti,
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_trace_point() {
+    fn test_parse_section_uprobe() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
-        fake_sym(&mut obj, 1, 0, "bar", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "tracepoint/foo",
+                "uprobe/foo",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2021,38 +2382,47 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::TracePoint,
+                section: ProgramSection::UProbe { .. },
                 ..
             })
         );
+    }
+
+    #[test]
+    fn test_parse_section_uprobe_sleepable() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "tp/foo/bar",
+                "uprobe.s/foo",
                 bytes_of(&fake_ins()),
-                Some(1),
+                None
             )),
             Ok(())
         );
         assert_matches!(
-            obj.programs.get("bar"),
+            obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::TracePoint,
+                section: ProgramSection::UProbe {
+                    sleepable: true,
+                    ..
+                },
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_socket_filter() {
+    fn test_parse_section_uretprobe() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "socket/foo",
+                "uretprobe/foo",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2061,21 +2431,21 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::SocketFilter,
+                section: ProgramSection::URetProbe { .. },
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_xdp() {
+    fn test_parse_section_uretprobe_sleepable() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "xdp",
+                "uretprobe.s/foo",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2084,21 +2454,24 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::Xdp { frags: false, .. },
+                section: ProgramSection::URetProbe {
+                    sleepable: true,
+                    ..
+                },
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_xdp_frags() {
+    fn test_parse_section_uprobe_multi() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "xdp.frags",
+                "uprobe.multi/usr/lib/libc.so.6:malloc",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2107,22 +2480,21 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::Xdp { frags: true, .. },
+                section: ProgramSection::UProbeMulti { sleepable: false },
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_raw_tp() {
+    fn test_parse_section_uretprobe_multi_sleepable() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
-        fake_sym(&mut obj, 1, 0, "bar", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "raw_tp/foo",
+                "uretprobe.multi.s",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2131,38 +2503,44 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::RawTracePoint,
+                section: ProgramSection::URetProbeMulti { sleepable: true },
                 ..
             })
         );
+    }
+
+    #[test]
+    fn test_parse_section_usdt() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "raw_tracepoint/bar",
+                "usdt/usr/lib/libc.so.6:libc:setjmp",
                 bytes_of(&fake_ins()),
-                Some(1)
+                None
             )),
             Ok(())
         );
         assert_matches!(
-            obj.programs.get("bar"),
+            obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::RawTracePoint,
+                section: ProgramSection::Usdt { sleepable: false },
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_lsm() {
+    fn test_parse_section_usdt_sleepable() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "lsm/foo",
+                "usdt.s",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2171,24 +2549,21 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::Lsm {
-                    sleepable: false,
-                    ..
-                },
+                section: ProgramSection::Usdt { sleepable: true },
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_lsm_sleepable() {
+    fn test_parse_section_struct_ops() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "lsm.s/foo",
+                "struct_ops.s/foo",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2197,24 +2572,22 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::Lsm {
-                    sleepable: true,
-                    ..
-                },
+                section: ProgramSection::StructOps { sleepable: true },
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_btf_tracepoint() {
+    fn test_parse_section_trace_point() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+        fake_sym(&mut obj, 1, 0, "bar", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "tp_btf/foo",
+                "tracepoint/foo",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2223,67 +2596,61 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::BtfTracePoint,
+                section: ProgramSection::TracePoint,
                 ..
             })
         );
-    }
-
-    #[test]
-    fn test_parse_section_skskb_unnamed() {
-        let mut obj = fake_obj();
-        fake_sym(&mut obj, 0, 0, "stream_parser", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "sk_skb/stream_parser",
+                "tp/foo/bar",
                 bytes_of(&fake_ins()),
-                None
+                Some(1),
             )),
             Ok(())
         );
         assert_matches!(
-            obj.programs.get("stream_parser"),
+            obj.programs.get("bar"),
             Some(Program {
-                section: ProgramSection::SkSkbStreamParser,
+                section: ProgramSection::TracePoint,
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_skskb_named() {
+    fn test_parse_section_socket_filter() {
         let mut obj = fake_obj();
-        fake_sym(&mut obj, 0, 0, "my_parser", FAKE_INS_LEN);
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "sk_skb/stream_parser/my_parser",
+                "socket/foo",
                 bytes_of(&fake_ins()),
                 None
             )),
             Ok(())
         );
         assert_matches!(
-            obj.programs.get("my_parser"),
+            obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::SkSkbStreamParser,
+                section: ProgramSection::SocketFilter,
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_fentry() {
+    fn test_parse_section_xdp() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "fentry/foo",
+                "xdp",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2292,21 +2659,21 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::FEntry { .. },
+                section: ProgramSection::Xdp { frags: false, .. },
                 ..
             })
         );
     }
 
     #[test]
-    fn test_parse_section_fentry_sleepable() {
+    fn test_parse_section_xdp_frags() {
         let mut obj = fake_obj();
         fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
 
         assert_matches!(
             obj.parse_section(fake_section(
                 EbpfSectionKind::Program,
-                "fentry.s/foo",
+                "xdp.frags",
                 bytes_of(&fake_ins()),
                 None
             )),
@@ -2315,8 +2682,280 @@ mod tests {
         assert_matches!(
             obj.programs.get("foo"),
             Some(Program {
-                section: ProgramSection::FEntry {
-                    sleepable: true,
+                section: ProgramSection::Xdp { frags: true, .. },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_raw_tp() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+        fake_sym(&mut obj, 1, 0, "bar", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "raw_tp/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::RawTracePoint { writable: false },
+                ..
+            })
+        );
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "raw_tracepoint/bar",
+                bytes_of(&fake_ins()),
+                Some(1)
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("bar"),
+            Some(Program {
+                section: ProgramSection::RawTracePoint { writable: false },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_raw_tp_writable() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+        fake_sym(&mut obj, 1, 0, "bar", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "raw_tp.w/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::RawTracePoint { writable: true },
+                ..
+            })
+        );
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "raw_tracepoint.w/bar",
+                bytes_of(&fake_ins()),
+                Some(1)
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("bar"),
+            Some(Program {
+                section: ProgramSection::RawTracePoint { writable: true },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_lsm() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "lsm/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::Lsm {
+                    sleepable: false,
+                    ..
+                },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_lsm_sleepable() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "lsm.s/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::Lsm {
+                    sleepable: true,
+                    ..
+                },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_lsm_cgroup() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "lsm_cgroup/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::LsmCgroup,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_btf_tracepoint() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "tp_btf/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::BtfTracePoint,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_skskb_unnamed() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "stream_parser", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "sk_skb/stream_parser",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("stream_parser"),
+            Some(Program {
+                section: ProgramSection::SkSkbStreamParser,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_skskb_named() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "my_parser", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "sk_skb/stream_parser/my_parser",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("my_parser"),
+            Some(Program {
+                section: ProgramSection::SkSkbStreamParser,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_fentry() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "fentry/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::FEntry { .. },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_fentry_sleepable() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "fentry.s/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::FEntry {
+                    sleepable: true,
                     ..
                 },
                 ..
@@ -2373,6 +3012,239 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_section_fmod_ret() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "fmod_ret/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::FModRet { .. },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_fmod_ret_sleepable() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "fmod_ret.s/foo",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::FModRet {
+                    sleepable: true,
+                    ..
+                },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_sk_reuseport() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "sk_reuseport",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::SkReuseport { migrate: false },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_sk_reuseport_migrate() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "sk_reuseport/migrate",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::SkReuseport { migrate: true },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_lwt_in() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "lwt_in",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::LwtIn,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_lwt_out() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "lwt_out",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::LwtOut,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_lwt_xmit() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "lwt_xmit",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::LwtXmit,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_lwt_seg6local() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "lwt_seg6local",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::LwtSeg6Local,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_netfilter() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "netfilter",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::Netfilter,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_syscall() {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 0, 0, "foo", FAKE_INS_LEN);
+
+        assert_matches!(
+            obj.parse_section(fake_section(
+                EbpfSectionKind::Program,
+                "syscall",
+                bytes_of(&fake_ins()),
+                None
+            )),
+            Ok(())
+        );
+        assert_matches!(
+            obj.programs.get("foo"),
+            Some(Program {
+                section: ProgramSection::Syscall,
+                ..
+            })
+        );
+    }
+
     #[test]
     fn test_parse_section_cgroup_skb_ingress_unnamed() {
         let mut obj = fake_obj();
@@ -2587,6 +3459,8 @@ mod tests {
                 section_index: 1,
                 section_kind: EbpfSectionKind::Rodata,
                 symbol_index: Some(1),
+                inner_def: None,
+                map_extra: 0,
                 data: vec![0, 0, 0],
             }),
         );
@@ -2783,4 +3657,233 @@ mod tests {
             assert_eq!(m.def.max_entries, 1);
         });
     }
+
+    #[test]
+    fn test_parse_btf_map_of_maps() {
+        let mut btf = Btf::new();
+        let name_offset = btf.add_string("int");
+        let int_type_id =
+            btf.add_type(BtfType::Int(Int::new(name_offset, 4, IntEncoding::None, 0)));
+        // `__uint(name, val)` is encoded as a pointer to an array of `val` ints.
+        let uint_field = |btf: &mut Btf, val: u32| {
+            let array_type_id =
+                btf.add_type(BtfType::Array(Array::new(0, int_type_id, int_type_id, val)));
+            btf.add_type(BtfType::Ptr(Ptr::new(0, array_type_id)))
+        };
+        let type_offset = btf.add_string("type");
+        let max_entries_offset = btf.add_string("max_entries");
+        let key_offset = btf.add_string("key");
+        let value_offset = btf.add_string("value");
+        let values_offset = btf.add_string("values");
+        let int_ptr_type_id = btf.add_type(BtfType::Ptr(Ptr::new(0, int_type_id)));
+
+        let inner_type = uint_field(&mut btf, BPF_MAP_TYPE_ARRAY as u32);
+        let inner_max_entries = uint_field(&mut btf, 1);
+        let inner_type_id = btf.add_type(BtfType::Struct(Struct::new(
+            0,
+            vec![
+                BtfMember {
+                    name_offset: type_offset,
+                    btf_type: inner_type,
+                    offset: 0,
+                },
+                BtfMember {
+                    name_offset: max_entries_offset,
+                    btf_type: inner_max_entries,
+                    offset: 64,
+                },
+                BtfMember {
+                    name_offset: key_offset,
+                    btf_type: int_ptr_type_id,
+                    offset: 128,
+                },
+                BtfMember {
+                    name_offset: value_offset,
+                    btf_type: int_ptr_type_id,
+                    offset: 192,
+                },
+            ],
+            32,
+        )));
+        let inner_ptr_type_id = btf.add_type(BtfType::Ptr(Ptr::new(0, inner_type_id)));
+        let values_type_id = btf.add_type(BtfType::Array(Array::new(
+            0,
+            inner_ptr_type_id,
+            int_type_id,
+            0,
+        )));
+
+        let outer_type = uint_field(&mut btf, BPF_MAP_TYPE_HASH_OF_MAPS as u32);
+        let outer_max_entries = uint_field(&mut btf, 16);
+        let outer_type_id = btf.add_type(BtfType::Struct(Struct::new(
+            0,
+            vec![
+                BtfMember {
+                    name_offset: type_offset,
+                    btf_type: outer_type,
+                    offset: 0,
+                },
+                BtfMember {
+                    name_offset: max_entries_offset,
+                    btf_type: outer_max_entries,
+                    offset: 64,
+                },
+                BtfMember {
+                    name_offset: key_offset,
+                    btf_type: int_ptr_type_id,
+                    offset: 128,
+                },
+                BtfMember {
+                    name_offset: values_offset,
+                    btf_type: values_type_id,
+                    offset: 192,
+                },
+            ],
+            24,
+        )));
+        let name_offset = btf.add_string("outer");
+        let var_type_id = btf.add_type(BtfType::Var(Var::new(
+            name_offset,
+            outer_type_id,
+            VarLinkage::Global,
+        )));
+
+        let (name, def, inner_def) = parse_btf_map_def(
+            &btf,
+            &DataSecEntry {
+                btf_type: var_type_id,
+                offset: 0,
+                size: 24,
+            },
+        )
+        .unwrap();
+        assert_eq!(name, "outer");
+        assert_eq!(def.map_type, BPF_MAP_TYPE_HASH_OF_MAPS as u32);
+        assert_eq!(def.key_size, 4);
+        assert_eq!(def.value_size, 4);
+        assert_eq!(def.max_entries, 16);
+        assert_matches!(inner_def, Some(inner_def) => {
+            assert_eq!(inner_def.map_type, BPF_MAP_TYPE_ARRAY as u32);
+            assert_eq!(inner_def.key_size, 4);
+            assert_eq!(inner_def.value_size, 4);
+            assert_eq!(inner_def.max_entries, 1);
+        });
+
+        // the inner template doesn't own the symbol of the outer map
+        let map = Map::Btf(BtfMap {
+            def,
+            section_index: 0,
+            symbol_index: Some(1),
+            inner_def,
+            data: Vec::new(),
+        });
+        assert_eq!(map.symbol_index(), Some(1));
+        assert_matches!(map.inner(), Some(inner) => {
+            assert_eq!(inner.symbol_index(), None);
+            assert_eq!(inner.map_type(), BPF_MAP_TYPE_ARRAY as u32);
+        });
+    }
+
+    fn parse_struct_ops_section(section_name: &'static str) -> Object {
+        let mut obj = fake_obj();
+        fake_sym(&mut obj, 1, 0, "my_ops", 16);
+        fake_sym(&mut obj, 2, 0, "my_init", FAKE_INS_LEN);
+
+        let mut btf = Btf::new();
+        let name_offset = btf.add_string("int");
+        let int_type_id = btf.add_type(BtfType::Int(Int::new(
+            name_offset,
+            4,
+            IntEncoding::Signed,
+            0,
+        )));
+        let proto_type_id = btf.add_type(BtfType::FuncProto(FuncProto::new(vec![], int_type_id)));
+        let ptr_type_id = btf.add_type(BtfType::Ptr(Ptr::new(0, proto_type_id)));
+        let name_offset = btf.add_string("foo_ops");
+        let flags_offset = btf.add_string("flags");
+        let init_offset = btf.add_string("init");
+        let struct_type_id = btf.add_type(BtfType::Struct(Struct::new(
+            name_offset,
+            vec![
+                BtfMember {
+                    name_offset: flags_offset,
+                    btf_type: int_type_id,
+                    offset: 0,
+                },
+                BtfMember {
+                    name_offset: init_offset,
+                    btf_type: ptr_type_id,
+                    offset: 64,
+                },
+            ],
+            16,
+        )));
+        let name_offset = btf.add_string("my_ops");
+        let var_type_id = btf.add_type(BtfType::Var(Var::new(
+            name_offset,
+            struct_type_id,
+            VarLinkage::Global,
+        )));
+        let name_offset = btf.add_string(section_name);
+        btf.add_type(BtfType::DataSec(DataSec::new(
+            name_offset,
+            vec![DataSecEntry {
+                btf_type: var_type_id,
+                offset: 0,
+                size: 16,
+            }],
+            16,
+        )));
+        obj.btf = Some(btf);
+
+        let data = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        obj.parse_section(Section {
+            index: SectionIndex(1),
+            kind: EbpfSectionKind::StructOps,
+            address: 0,
+            name: section_name,
+            data: &data,
+            size: data.len() as u64,
+            relocations: vec![Relocation {
+                offset: 8,
+                size: 64,
+                symbol_index: 2,
+            }],
+        })
+        .unwrap();
+        obj
+    }
+
+    #[test]
+    fn test_parse_struct_ops_link_section() {
+        let obj = parse_struct_ops_section(".struct_ops.link");
+
+        assert_matches!(obj.maps.get("my_ops"), Some(Map::StructOps(m)) => {
+            assert_eq!(m.def.map_type, BPF_MAP_TYPE_STRUCT_OPS as u32);
+            assert_eq!(m.def.key_size, 4);
+            assert_eq!(m.def.value_size, 16);
+            assert_eq!(m.def.max_entries, 1);
+            assert_eq!(m.def.map_flags, BPF_F_LINK);
+            assert_eq!(m.type_name(), "foo_ops");
+            assert_eq!(m.data, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            assert_matches!(m.programs(), [StructOpsProgram { member_name, program_name, .. }] => {
+                assert_eq!(member_name, "init");
+                assert_eq!(program_name, "my_init");
+            });
+        });
+    }
+
+    #[test]
+    fn test_parse_struct_ops_section() {
+        let obj = parse_struct_ops_section(".struct_ops");
+
+        assert_matches!(obj.maps.get("my_ops"), Some(Map::StructOps(m)) => {
+            assert_eq!(m.def.map_type, BPF_MAP_TYPE_STRUCT_OPS as u32);
+            assert_eq!(m.def.map_flags, 0);
+            assert_matches!(m.programs(), [StructOpsProgram { member_name, program_name, .. }] => {
+                assert_eq!(member_name, "init");
+                assert_eq!(program_name, "my_init");
+            });
+        });
+    }
 }
@@ -8,9 +8,10 @@ use object::{SectionIndex, SymbolKind};
 
 use crate::{
     EbpfSectionKind,
+    btf::{Btf, BtfKind},
     generated::{
-        BPF_CALL, BPF_JMP, BPF_K, BPF_PSEUDO_CALL, BPF_PSEUDO_FUNC, BPF_PSEUDO_MAP_FD,
-        BPF_PSEUDO_MAP_VALUE, bpf_insn,
+        BPF_CALL, BPF_JMP, BPF_K, BPF_PSEUDO_CALL, BPF_PSEUDO_FUNC, BPF_PSEUDO_KFUNC_CALL,
+        BPF_PSEUDO_MAP_FD, BPF_PSEUDO_MAP_VALUE, bpf_insn,
     },
     maps::Map,
     obj::{Function, Object},
@@ -24,7 +25,8 @@ type RawFd = core::ffi::c_int;
 
 pub(crate) const INS_SIZE: usize = mem::size_of::<bpf_insn>();
 
-/// The error type returned by [`Object::relocate_maps`] and [`Object::relocate_calls`]
+/// The error type returned by [`Object::relocate_maps`], [`Object::relocate_kfuncs`] and
+/// [`Object::relocate_calls`]
 #[derive(thiserror::Error, Debug)]
 #[error("error relocating `{function}`")]
 pub struct EbpfRelocationError {
@@ -77,6 +79,13 @@ pub enum RelocationError {
         address: u64,
     },
 
+    /// Unknown kernel function
+    #[error("kernel function `{name}` not found")]
+    UnknownKfunc {
+        /// The function name
+        name: String,
+    },
+
     /// Invalid relocation offset
     #[error("invalid offset `{offset}` applying relocation #{relocation_number}")]
     InvalidRelocationOffset {
@@ -143,6 +152,30 @@ impl Object {
         Ok(())
     }
 
+    /// Relocates calls to kernel functions (kfuncs)
+    ///
+    /// Calls to undefined functions are resolved by name against the `FUNC`
+    /// types of `target_btf`, which is normally the kernel BTF. This must be
+    /// done before [`Object::relocate_calls`].
+    pub fn relocate_kfuncs(&mut self, target_btf: &Btf) -> Result<(), EbpfRelocationError> {
+        for function in self.functions.values_mut() {
+            if let Some(relocations) = self.relocations.get(&function.section_index) {
+                relocate_kfuncs(
+                    function,
+                    relocations.values(),
+                    &self.symbol_table,
+                    target_btf,
+                )
+                .map_err(|error| EbpfRelocationError {
+                    function: function.name.clone(),
+                    error,
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Relocates function calls
     pub fn relocate_calls(
         &mut self,
@@ -275,6 +308,59 @@ fn relocate_maps<'a, I: Iterator<Item = &'a Relocation>>(
     Ok(())
 }
 
+fn relocate_kfuncs<'a, I: Iterator<Item = &'a Relocation>>(
+    fun: &mut Function,
+    relocations: I,
+    symbol_table: &HashMap<usize, Symbol>,
+    target_btf: &Btf,
+) -> Result<(), RelocationError> {
+    let section_offset = fun.section_offset;
+    let instructions = &mut fun.instructions;
+    let function_size = instructions.len() * INS_SIZE;
+
+    for (rel_n, rel) in relocations.enumerate() {
+        let rel_offset = rel.offset as usize;
+        if rel_offset < section_offset || rel_offset >= section_offset + function_size {
+            // the relocation doesn't apply to this function
+            continue;
+        }
+
+        let ins_offset = rel_offset - section_offset;
+        if ins_offset % INS_SIZE != 0 {
+            return Err(RelocationError::InvalidRelocationOffset {
+                offset: rel.offset,
+                relocation_number: rel_n,
+            });
+        }
+        let ins = &mut instructions[ins_offset / INS_SIZE];
+
+        let sym = symbol_table
+            .get(&rel.symbol_index)
+            .ok_or(RelocationError::UnknownSymbol {
+                index: rel.symbol_index,
+            })?;
+
+        // kfuncs are declared as external functions, so their symbols are undefined
+        let (None, Some(name)) = (sym.section_index, &sym.name) else {
+            continue;
+        };
+        if u32::from(ins.code) != BPF_JMP | BPF_CALL {
+            continue;
+        }
+
+        let btf_id = target_btf
+            .id_by_type_name_kind(name, BtfKind::Func)
+            .map_err(|_| RelocationError::UnknownKfunc { name: name.clone() })?;
+        debug!("relocating call to kfunc `{name}` with BTF id {btf_id}");
+
+        ins.set_src_reg(BPF_PSEUDO_KFUNC_CALL as u8);
+        ins.imm = btf_id as i32;
+        ins.off = 0;
+    }
+
+    Ok(())
+}
+
 struct FunctionLinker<'a> {
     functions: &'a BTreeMap<(usize, u64), Function>,
     linked_functions: HashMap<u64, usize>,
@@ -370,8 +456,9 @@ impl<'a> FunctionLinker<'a> {
                 })
                 .filter(|(_rel, sym)| {
                     // only consider text relocations, data relocations are
-                    // relocated in relocate_maps()
-                    sym.kind == SymbolKind::Text
+                    // relocated in relocate_maps() and calls to undefined
+                    // functions in relocate_kfuncs()
+                    (sym.kind == SymbolKind::Text && sym.section_index.is_some())
                         || sym
                             .section_index
                             .map(|section_index| self.text_sections.contains(&section_index))
@@ -529,6 +616,8 @@ mod test {
             section_index: 0,
             section_kind: EbpfSectionKind::Undefined,
             symbol_index: Some(symbol_index),
+            inner_def: None,
+            map_extra: 0,
             data: Vec::new(),
         })
     }
@@ -537,7 +626,8 @@ mod test {
         Map::Btf(BtfMap {
             def: Default::default(),
             section_index: 0,
-            symbol_index,
+            symbol_index: Some(symbol_index),
+            inner_def: None,
             data: Vec::new(),
         })
     }
@@ -687,6 +777,59 @@ mod test {
         assert_eq!(fun.instructions[0].imm, 1);
     }
 
+    #[test]
+    fn test_kfunc_relocation() {
+        use assert_matches::assert_matches;
+
+        use crate::btf::{BtfType, Func, FuncLinkage, FuncProto};
+
+        let mut fun = fake_func(
+            "test",
+            vec![ins(&[
+                0x85, 0x10, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, // call -1
+            ])],
+        );
+
+        let symbol_table = HashMap::from([(
+            1,
+            Symbol {
+                index: 1,
+                section_index: None,
+                name: Some("bpf_kfunc".to_string()),
+                address: 0,
+                size: 0,
+                is_definition: false,
+                kind: SymbolKind::Text,
+            },
+        )]);
+        let relocations = [Relocation {
+            offset: 0x0,
+            symbol_index: 1,
+            size: 32,
+        }];
+
+        let mut btf = Btf::new();
+        let proto = btf.add_type(BtfType::FuncProto(FuncProto::new(vec![], 0)));
+        let name_offset = btf.add_string("bpf_kfunc");
+        let func = btf.add_type(BtfType::Func(Func::new(
+            name_offset,
+            proto,
+            FuncLinkage::Global,
+        )));
+
+        relocate_kfuncs(&mut fun, relocations.iter(), &symbol_table, &btf).unwrap();
+
+        assert_eq!(fun.instructions[0].src_reg(), BPF_PSEUDO_KFUNC_CALL as u8);
+        assert_eq!(fun.instructions[0].imm, func as i32);
+        assert_eq!(fun.instructions[0].off, 0);
+
+        let btf = Btf::new();
+        assert_matches!(
+            relocate_kfuncs(&mut fun, relocations.iter(), &symbol_table, &btf),
+            Err(RelocationError::UnknownKfunc { name }) if name == "bpf_kfunc"
+        );
+    }
+
     #[test]
     fn test_multiple_btf_map_relocation() {
         let mut fun = fake_func(
@@ -9,24 +9,28 @@ use std::{
 
 use aya_obj::{
     EbpfSectionKind, Features, Object, ParseError, ProgramSection,
-    btf::{Btf, BtfError, BtfFeatures, BtfRelocationError},
+    btf::{Btf, BtfError, BtfFeatures, BtfKind, BtfRelocationError, StructOpsRelocationError},
     generated::{
         BPF_F_SLEEPABLE, BPF_F_XDP_HAS_FRAGS,
+        bpf_attach_type::{BPF_SK_REUSEPORT_SELECT, BPF_SK_REUSEPORT_SELECT_OR_MIGRATE},
         bpf_map_type::{self, *},
     },
     relocation::EbpfRelocationError,
 };
 use log::{debug, warn};
+use object::Endianness;
 use thiserror::Error;
 
 use crate::{
     maps::{Map, MapData, MapError},
     programs::{
         BtfTracePoint, CgroupDevice, CgroupSkb, CgroupSkbAttachType, CgroupSock, CgroupSockAddr,
-        CgroupSockopt, CgroupSysctl, Extension, FEntry, FExit, FlowDissector, Iter, KProbe,
-        LircMode2, Lsm, PerfEvent, ProbeKind, Program, ProgramData, ProgramError, RawTracePoint,
-        SchedClassifier, SkLookup, SkMsg, SkSkb, SkSkbKind, SockOps, SocketFilter, TracePoint,
-        UProbe, Xdp,
+        CgroupSockopt, CgroupSysctl, Extension, FEntry, FExit, FModRet, FlowDissector, Iter,
+        KProbe, KProbeMulti, LircMode2, Lsm, LsmCgroup, LwtIn, LwtOut, LwtSeg6Local, LwtXmit,
+        Netfilter, PerfEvent, ProbeKind, Program, ProgramData, ProgramError, RawTracePoint,
+        SchedClassifier, SkLookup, SkMsg, SkReuseport, SkSkb, SkSkbKind, SockOps, SocketFilter,
+        StructOps, Syscall, TracePoint, UProbe, UProbeMulti, Usdt, Xdp,
+        usdt::{UsdtSpecMap, usdt_spec_map_name},
     },
     sys::{
         bpf_load_btf, is_bpf_cookie_supported, is_bpf_global_data_supported,
@@ -117,6 +121,7 @@ pub fn features() -> &'static Features {
 #[derive(Debug)]
 pub struct EbpfLoader<'a> {
     btf: Option<Cow<'a, Btf>>,
+    module_btfs: ModuleBtfs<'a>,
     map_pin_path: Option<PathBuf>,
     globals: HashMap<&'a str, (&'a [u8], bool)>,
     max_entries: HashMap<&'a str, u32>,
@@ -125,6 +130,16 @@ pub struct EbpfLoader<'a> {
     allow_unsupported_maps: bool,
 }
 
+/// Where the [`EbpfLoader`] finds the BTF of kernel modules.
+#[derive(Debug)]
+enum ModuleBtfs<'a> {
+    /// The BTF of the modules defining the types missing from the kernel BTF
+    /// is loaded from `/sys/kernel/btf`.
+    SysFs,
+    /// The BTF set by the caller.
+    Custom(&'a [Btf]),
+}
+
 /// Builder style API for advanced loading of eBPF programs.
 #[deprecated(since = "0.13.0", note = "use `EbpfLoader` instead")]
 pub type BpfLoader<'a> = EbpfLoader<'a>;
@@ -155,6 +170,7 @@ impl<'a> EbpfLoader<'a> {
     pub fn new() -> Self {
         Self {
             btf: Btf::from_sys_fs().ok().map(Cow::Owned),
+            module_btfs: ModuleBtfs::SysFs,
             map_pin_path: None,
             globals: HashMap::new(),
             max_entries: HashMap::new(),
@@ -169,6 +185,11 @@ impl<'a> EbpfLoader<'a> {
     /// The loader defaults to loading `BTF` info using [Btf::from_sys_fs].
     /// Use this method if you want to load `BTF` from a custom location or
     /// pass `None` to disable `BTF` relocations entirely.
+    ///
+    /// Module BTF is only loaded from `/sys/kernel/btf` with the default
+    /// target BTF, since it must be based on the BTF of the running kernel.
+    /// When setting a custom target BTF, use [`Self::module_btfs`] to set the
+    /// BTF of kernel modules.
     /// # Example
     ///
     /// ```no_run
@@ -183,6 +204,41 @@ impl<'a> EbpfLoader<'a> {
     /// ```
     pub fn btf(&mut self, btf: Option<&'a Btf>) -> &mut Self {
         self.btf = btf.map(Cow::Borrowed);
+        if let ModuleBtfs::SysFs = self.module_btfs {
+            self.module_btfs = ModuleBtfs::Custom(&[]);
+        }
+        self
+    }
+
+    /// Sets the [BTF](Btf) info of kernel modules.
+    ///
+    /// Types which can't be found in the target BTF set with [`Self::btf`]
+    /// are looked up in the BTF of these modules when applying BTF
+    /// relocations. This allows programs to access the structs defined by
+    /// kernel modules.
+    ///
+    /// When neither the target BTF nor the module BTF is set, the loader finds
+    /// the modules defining these types itself and loads their BTF from
+    /// `/sys/kernel/btf`, only if some types can't be found in the kernel BTF.
+    /// Use this method if you want to load the BTF of modules from a custom
+    /// location.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use aya::{EbpfLoader, Btf};
+    ///
+    /// let btf = Btf::from_sys_fs()?;
+    /// let module_btfs = [Btf::from_sys_fs_module(&btf, "nf_conntrack")?];
+    /// let bpf = EbpfLoader::new()
+    ///     .btf(Some(&btf))
+    ///     .module_btfs(&module_btfs)
+    ///     .load_file("file.o")?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn module_btfs(&mut self, module_btfs: &'a [Btf]) -> &mut Self {
+        self.module_btfs = ModuleBtfs::Custom(module_btfs);
         self
     }
 
@@ -378,6 +434,7 @@ impl<'a> EbpfLoader<'a> {
     pub fn load(&mut self, data: &[u8]) -> Result<Ebpf, EbpfError> {
         let Self {
             btf,
+            module_btfs,
             map_pin_path,
             globals,
             max_entries,
@@ -399,15 +456,23 @@ impl<'a> EbpfLoader<'a> {
                                 ProgramSection::Extension
                                 | ProgramSection::FEntry { sleepable: _ }
                                 | ProgramSection::FExit { sleepable: _ }
+                                | ProgramSection::FModRet { sleepable: _ }
                                 | ProgramSection::Lsm { sleepable: _ }
+                                | ProgramSection::LsmCgroup
                                 | ProgramSection::BtfTracePoint
-                                | ProgramSection::Iter { sleepable: _ } => {
+                                | ProgramSection::Iter { sleepable: _ }
+                                | ProgramSection::StructOps { sleepable: _ } => {
                                     return Err(EbpfError::BtfError(err));
                                 }
                                 ProgramSection::KRetProbe
                                 | ProgramSection::KProbe
+                                | ProgramSection::KRetProbeMulti
+                                | ProgramSection::KProbeMulti
                                 | ProgramSection::UProbe { sleepable: _ }
                                 | ProgramSection::URetProbe { sleepable: _ }
+                                | ProgramSection::UProbeMulti { sleepable: _ }
+                                | ProgramSection::URetProbeMulti { sleepable: _ }
+                                | ProgramSection::Usdt { sleepable: _ }
                                 | ProgramSection::TracePoint
                                 | ProgramSection::SocketFilter
                                 | ProgramSection::Xdp {
@@ -427,8 +492,15 @@ impl<'a> EbpfLoader<'a> {
                                 | ProgramSection::CgroupSockopt { attach_type: _ }
                                 | ProgramSection::LircMode2
                                 | ProgramSection::PerfEvent
-                                | ProgramSection::RawTracePoint
+                                | ProgramSection::RawTracePoint { writable: _ }
                                 | ProgramSection::SkLookup
+                                | ProgramSection::SkReuseport { migrate: _ }
+                                | ProgramSection::LwtIn
+                                | ProgramSection::LwtOut
+                                | ProgramSection::LwtXmit
+                                | ProgramSection::LwtSeg6Local
+                                | ProgramSection::Netfilter
+                                | ProgramSection::Syscall
                                 | ProgramSection::FlowDissector
                                 | ProgramSection::CgroupSock { attach_type: _ }
                                 | ProgramSection::CgroupDevice => {}
@@ -448,8 +520,36 @@ impl<'a> EbpfLoader<'a> {
         };
 
         if let Some(btf) = &btf {
-            obj.relocate_btf(btf)?;
+            let sys_fs_module_btfs;
+            let module_btfs = match module_btfs {
+                ModuleBtfs::SysFs => {
+                    sys_fs_module_btfs =
+                        load_sys_fs_module_btfs(btf, &obj.unresolved_btf_types(btf)?)?;
+                    sys_fs_module_btfs.as_slice()
+                }
+                ModuleBtfs::Custom(module_btfs) => module_btfs,
+            };
+            obj.relocate_btf_with_modules(btf, module_btfs)?;
+            obj.relocate_struct_ops(btf)?;
         }
+        // Maps each struct_ops program to the kernel struct and member it
+        // implements.
+        let struct_ops_members = obj
+            .maps
+            .values()
+            .filter_map(|map| match map {
+                aya_obj::Map::StructOps(map) => Some(map),
+                _ => None,
+            })
+            .flat_map(|map| {
+                map.programs().iter().map(|program| {
+                    (
+                        program.program_name.clone(),
+                        (map.kernel_type_id(), program.member_index),
+                    )
+                })
+            })
+            .collect::<HashMap<_, _>>();
         let mut maps = HashMap::new();
         for (name, mut obj) in obj.maps.drain() {
             if let (false, EbpfSectionKind::Bss | EbpfSectionKind::Data | EbpfSectionKind::Rodata) =
@@ -510,9 +610,23 @@ impl<'a> EbpfLoader<'a> {
                 .map(|(s, data)| (s.as_str(), data.fd().as_fd().as_raw_fd(), data.obj())),
             &text_sections,
         )?;
+        if let Some(btf) = &btf {
+            obj.relocate_kfuncs(btf)?;
+        }
         obj.relocate_calls(&text_sections)?;
         obj.sanitize_functions(&FEATURES);
 
+        // Each USDT program has its own spec map, declared along with it.
+        let usdt_spec_maps = obj
+            .programs
+            .iter()
+            .filter(|(_, prog_obj)| matches!(prog_obj.section, ProgramSection::Usdt { .. }))
+            .filter_map(|(name, _)| {
+                let map = maps.get(&usdt_spec_map_name(name))?;
+                Some(UsdtSpecMap::new(map).map(|spec_map| (name.clone(), Arc::new(spec_map))))
+            })
+            .collect::<Result<HashMap<_, _>, MapError>>()?;
+
         let programs = obj
             .programs
             .drain()
@@ -538,6 +652,14 @@ impl<'a> EbpfLoader<'a> {
                             data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
                             kind: ProbeKind::KRetProbe,
                         }),
+                        ProgramSection::KProbeMulti => Program::KProbeMulti(KProbeMulti {
+                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                            kind: ProbeKind::KProbe,
+                        }),
+                        ProgramSection::KRetProbeMulti => Program::KProbeMulti(KProbeMulti {
+                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                            kind: ProbeKind::KRetProbe,
+                        }),
                         ProgramSection::UProbe { sleepable } => {
                             let mut data =
                                 ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
@@ -560,6 +682,39 @@ impl<'a> EbpfLoader<'a> {
                                 kind: ProbeKind::URetProbe,
                             })
                         }
+                        ProgramSection::UProbeMulti { sleepable } => {
+                            let mut data =
+                                ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
+                            if *sleepable {
+                                data.flags = BPF_F_SLEEPABLE;
+                            }
+                            Program::UProbeMulti(UProbeMulti {
+                                data,
+                                kind: ProbeKind::UProbe,
+                            })
+                        }
+                        ProgramSection::URetProbeMulti { sleepable } => {
+                            let mut data =
+                                ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
+                            if *sleepable {
+                                data.flags = BPF_F_SLEEPABLE;
+                            }
+                            Program::UProbeMulti(UProbeMulti {
+                                data,
+                                kind: ProbeKind::URetProbe,
+                            })
+                        }
+                        ProgramSection::Usdt { sleepable } => {
+                            let mut data =
+                                ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
+                            if *sleepable {
+                                data.flags = BPF_F_SLEEPABLE;
+                            }
+                            Program::Usdt(Usdt {
+                                data,
+                                spec_map: usdt_spec_maps.get(&name).cloned(),
+                            })
+                        }
                         ProgramSection::TracePoint => Program::TracePoint(TracePoint {
                             data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
                         }),
@@ -631,9 +786,12 @@ impl<'a> EbpfLoader<'a> {
                         ProgramSection::PerfEvent => Program::PerfEvent(PerfEvent {
                             data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
                         }),
-                        ProgramSection::RawTracePoint => Program::RawTracePoint(RawTracePoint {
-                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
-                        }),
+                        ProgramSection::RawTracePoint { writable } => {
+                            Program::RawTracePoint(RawTracePoint {
+                                data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                                writable: *writable,
+                            })
+                        }
                         ProgramSection::Lsm { sleepable } => {
                             let mut data =
                                 ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
@@ -642,6 +800,9 @@ impl<'a> EbpfLoader<'a> {
                             }
                             Program::Lsm(Lsm { data })
                         }
+                        ProgramSection::LsmCgroup => Program::LsmCgroup(LsmCgroup {
+                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                        }),
                         ProgramSection::BtfTracePoint => Program::BtfTracePoint(BtfTracePoint {
                             data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
                         }),
@@ -661,6 +822,14 @@ impl<'a> EbpfLoader<'a> {
                             }
                             Program::FExit(FExit { data })
                         }
+                        ProgramSection::FModRet { sleepable } => {
+                            let mut data =
+                                ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
+                            if *sleepable {
+                                data.flags = BPF_F_SLEEPABLE;
+                            }
+                            Program::FModRet(FModRet { data })
+                        }
                         ProgramSection::FlowDissector => Program::FlowDissector(FlowDissector {
                             data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
                         }),
@@ -670,6 +839,39 @@ impl<'a> EbpfLoader<'a> {
                         ProgramSection::SkLookup => Program::SkLookup(SkLookup {
                             data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
                         }),
+                        ProgramSection::SkReuseport { migrate } => {
+                            let mut data =
+                                ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
+                            data.expected_attach_type = Some(if *migrate {
+                                BPF_SK_REUSEPORT_SELECT_OR_MIGRATE
+                            } else {
+                                BPF_SK_REUSEPORT_SELECT
+                            });
+                            Program::SkReuseport(SkReuseport { data })
+                        }
+                        ProgramSection::LwtIn => Program::LwtIn(LwtIn {
+                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                        }),
+                        ProgramSection::LwtOut => Program::LwtOut(LwtOut {
+                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                        }),
+                        ProgramSection::LwtXmit => Program::LwtXmit(LwtXmit {
+                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                        }),
+                        ProgramSection::LwtSeg6Local => Program::LwtSeg6Local(LwtSeg6Local {
+                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                        }),
+                        ProgramSection::Netfilter => Program::Netfilter(Netfilter {
+                            data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
+                        }),
+                        ProgramSection::Syscall => {
+                            let mut data =
+                                ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
+                            // The kernel only allows syscall programs to be
+                            // loaded as sleepable.
+                            data.flags = BPF_F_SLEEPABLE;
+                            Program::Syscall(Syscall { data })
+                        }
                         ProgramSection::CgroupSock { attach_type, .. } => {
                             Program::CgroupSock(CgroupSock {
                                 data: ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level),
@@ -687,6 +889,19 @@ impl<'a> EbpfLoader<'a> {
                             }
                             Program::Iter(Iter { data })
                         }
+                        ProgramSection::StructOps { sleepable } => {
+                            let mut data =
+                                ProgramData::new(prog_name, obj, btf_fd, *verifier_log_level);
+                            if *sleepable {
+                                data.flags = BPF_F_SLEEPABLE;
+                            }
+                            let member = struct_ops_members.get(&name);
+                            data.attach_btf_id = member.map(|(type_id, _)| *type_id);
+                            Program::StructOps(StructOps {
+                                data,
+                                member_index: member.map(|(_, index)| *index),
+                            })
+                        }
                     }
                 };
                 (name, program)
@@ -709,25 +924,37 @@ fn parse_map(
     let map_type = bpf_map_type::try_from(map.obj().map_type()).map_err(MapError::from)?;
     let map = match map_type {
         BPF_MAP_TYPE_ARRAY => Map::Array(map),
+        BPF_MAP_TYPE_ARRAY_OF_MAPS => Map::ArrayOfMaps(map),
+        BPF_MAP_TYPE_ARENA => Map::Arena(map),
         BPF_MAP_TYPE_PERCPU_ARRAY => Map::PerCpuArray(map),
         BPF_MAP_TYPE_PROG_ARRAY => Map::ProgramArray(map),
         BPF_MAP_TYPE_HASH => Map::HashMap(map),
+        BPF_MAP_TYPE_HASH_OF_MAPS => Map::HashOfMaps(map),
         BPF_MAP_TYPE_LRU_HASH => Map::LruHashMap(map),
         BPF_MAP_TYPE_PERCPU_HASH => Map::PerCpuHashMap(map),
         BPF_MAP_TYPE_LRU_PERCPU_HASH => Map::PerCpuLruHashMap(map),
         BPF_MAP_TYPE_PERF_EVENT_ARRAY => Map::PerfEventArray(map),
         BPF_MAP_TYPE_RINGBUF => Map::RingBuf(map),
+        BPF_MAP_TYPE_USER_RINGBUF => Map::UserRingBuf(map),
         BPF_MAP_TYPE_SOCKHASH => Map::SockHash(map),
         BPF_MAP_TYPE_SOCKMAP => Map::SockMap(map),
+        BPF_MAP_TYPE_REUSEPORT_SOCKARRAY => Map::ReusePortSockArray(map),
         BPF_MAP_TYPE_BLOOM_FILTER => Map::BloomFilter(map),
         BPF_MAP_TYPE_LPM_TRIE => Map::LpmTrie(map),
         BPF_MAP_TYPE_STACK => Map::Stack(map),
         BPF_MAP_TYPE_STACK_TRACE => Map::StackTraceMap(map),
+        BPF_MAP_TYPE_STRUCT_OPS => Map::StructOpsMap(map),
         BPF_MAP_TYPE_QUEUE => Map::Queue(map),
         BPF_MAP_TYPE_CPUMAP => Map::CpuMap(map),
         BPF_MAP_TYPE_DEVMAP => Map::DevMap(map),
         BPF_MAP_TYPE_DEVMAP_HASH => Map::DevMapHash(map),
         BPF_MAP_TYPE_XSKMAP => Map::XskMap(map),
+        BPF_MAP_TYPE_SK_STORAGE => Map::SkStorage(map),
+        BPF_MAP_TYPE_INODE_STORAGE => Map::InodeStorage(map),
+        BPF_MAP_TYPE_TASK_STORAGE => Map::TaskStorage(map),
+        BPF_MAP_TYPE_CGRP_STORAGE => Map::CgrpStorage(map),
+        BPF_MAP_TYPE_CGROUP_STORAGE_DEPRECATED => Map::CgroupStorage(map),
+        BPF_MAP_TYPE_PERCPU_CGROUP_STORAGE_DEPRECATED => Map::PerCpuCgroupStorage(map),
         m_type => {
             if allow_unsupported_maps {
                 Map::Unsupported(map)
@@ -755,14 +982,17 @@ fn max_entries_override(
     let max_entries = || user_override.unwrap_or_else(&current_value);
     Ok(match map_type {
         BPF_MAP_TYPE_PERF_EVENT_ARRAY if max_entries() == 0 => Some(num_cpus()?),
-        BPF_MAP_TYPE_RINGBUF => Some(adjust_to_page_size(max_entries(), page_size()))
-            .filter(|adjusted| *adjusted != max_entries())
-            .or(user_override),
+        BPF_MAP_TYPE_RINGBUF | BPF_MAP_TYPE_USER_RINGBUF => {
+            Some(adjust_to_page_size(max_entries(), page_size()))
+                .filter(|adjusted| *adjusted != max_entries())
+                .or(user_override)
+        }
         _ => user_override,
     })
 }
 
-// Adjusts the byte size of a RingBuf map to match a power-of-two multiple of the page size.
+// Adjusts the byte size of a RingBuf or UserRingBuf map to match a power-of-two multiple of the
+// page size.
 //
 // This mirrors the logic used by libbpf.
 // See https://github.com/libbpf/libbpf/blob/ec6f716eda43/src/libbpf.c#L2461-L2463
@@ -813,6 +1043,7 @@ mod tests {
             (BPF_MAP_TYPE_RINGBUF, Some(1), 1, Some(PAGE_SIZE)),
             (BPF_MAP_TYPE_RINGBUF, None, 1, Some(PAGE_SIZE)),
             (BPF_MAP_TYPE_RINGBUF, None, PAGE_SIZE, None),
+            (BPF_MAP_TYPE_USER_RINGBUF, None, 1, Some(PAGE_SIZE)),
             (BPF_MAP_TYPE_PERF_EVENT_ARRAY, None, 1, None),
             (BPF_MAP_TYPE_PERF_EVENT_ARRAY, Some(42), 1, Some(42)),
             (BPF_MAP_TYPE_PERF_EVENT_ARRAY, Some(0), 1, Some(NUM_CPUS)),
@@ -873,9 +1104,7 @@ impl Ebpf {
     /// # Ok::<(), aya::EbpfError>(())
     /// ```
     pub fn load_file<P: AsRef<Path>>(path: P) -> Result<Self, EbpfError> {
-        EbpfLoader::new()
-            .btf(Btf::from_sys_fs().ok().as_ref())
-            .load_file(path)
+        EbpfLoader::new().load_file(path)
     }
 
     /// Loads eBPF bytecode from a buffer.
@@ -902,9 +1131,7 @@ impl Ebpf {
     /// # Ok::<(), aya::EbpfError>(())
     /// ```
     pub fn load(data: &[u8]) -> Result<Self, EbpfError> {
-        EbpfLoader::new()
-            .btf(Btf::from_sys_fs().ok().as_ref())
-            .load(data)
+        EbpfLoader::new().load(data)
     }
 
     /// Returns a reference to the map with the given name.
@@ -1103,6 +1330,10 @@ pub enum EbpfError {
     #[error("error relocating section")]
     BtfRelocationError(#[from] BtfRelocationError),
 
+    /// Error relocating struct_ops maps
+    #[error("error relocating struct_ops map")]
+    StructOpsRelocationError(#[from] StructOpsRelocationError),
+
     /// No BTF parsed for object
     #[error("no BTF parsed for object")]
     NoBTF,
@@ -1120,6 +1351,58 @@ pub enum EbpfError {
 #[deprecated(since = "0.13.0", note = "use `EbpfError` instead")]
 pub type BpfError = EbpfError;
 
+/// Loads the BTF of the kernel modules defining any of `types` from
+/// `/sys/kernel/btf`.
+///
+/// Nothing is read when `types` is empty, that is when all the types are found
+/// in the kernel BTF.
+fn load_sys_fs_module_btfs(base: &Btf, types: &[(String, BtfKind)]) -> Result<Vec<Btf>, EbpfError> {
+    const SYS_FS_BTF: &str = "/sys/kernel/btf";
+
+    if types.is_empty() {
+        return Ok(Vec::new());
+    }
+    let file_error = |path: &Path, error| EbpfError::FileError {
+        path: path.to_owned(),
+        error,
+    };
+    let sys_fs_btf = Path::new(SYS_FS_BTF);
+    let mut module_btfs = Vec::new();
+    for entry in fs::read_dir(sys_fs_btf).map_err(|error| file_error(sys_fs_btf, error))? {
+        let path = entry.map_err(|error| file_error(sys_fs_btf, error))?.path();
+        if path.file_name().is_some_and(|name| name == "vmlinux") {
+            continue;
+        }
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            // The module was unloaded since the directory was read.
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                debug!("skipping the BTF of unloaded module {}", path.display());
+                continue;
+            }
+            Err(error) => return Err(file_error(&path, error)),
+        };
+        // The types of the module are checked before parsing its BTF, which
+        // copies the base.
+        let mut defines_type = false;
+        for (name, kind) in types {
+            match Btf::split_id_by_type_name_kind(base, &data, Endianness::default(), name, *kind) {
+                Ok(_) => {
+                    defines_type = true;
+                    break;
+                }
+                Err(BtfError::UnknownBtfTypeName { .. }) => {}
+                Err(err) => return Err(EbpfError::BtfError(err)),
+            }
+        }
+        if defines_type {
+            debug!("loading the BTF of module {}", path.display());
+            module_btfs.push(Btf::parse_split(base, &data, Endianness::default())?);
+        }
+    }
+    Ok(module_btfs)
+}
+
 fn load_btf(
     raw_btf: Vec<u8>,
     verifier_log_level: VerifierLogLevel,
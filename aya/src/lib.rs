@@ -81,7 +81,7 @@ pub use aya_obj::btf::{Btf, BtfError};
 pub use bpf::*;
 pub use object::Endianness;
 #[doc(hidden)]
-pub use sys::netlink_set_link_up;
+pub use sys::{netlink_add_netkit_pair, netlink_set_link_up};
 
 // See https://github.com/rust-lang/rust/pull/124210; this structure exists to avoid crashing the
 // process when we try to close a fake file descriptor.
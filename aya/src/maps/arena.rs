@@ -0,0 +1,138 @@
+//! An arena map, used to share memory between eBPF programs and userspace.
+
+use std::{
+    borrow::Borrow,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+    ptr::NonNull,
+};
+
+use libc::{MAP_SHARED, PROT_READ, PROT_WRITE};
+
+use crate::{
+    maps::{MapData, MapError},
+    util::{MMap, page_size},
+};
+
+/// A sparse region of memory shared between eBPF programs and userspace.
+///
+/// Pages are allocated by eBPF programs with the `bpf_arena_alloc_pages` kfunc, or by userspace
+/// on first access. A pointer into the arena has the same value in userspace and, once cast to
+/// the userspace address space, in eBPF programs, so pointer-rich data structures can be built by
+/// either side and traversed by the other.
+///
+/// The arena is mapped at the address set in the map's `map_extra`, or at an address picked by
+/// the kernel when it is zero. eBPF programs translate arena pointers using the address the arena
+/// was first mapped at, so unless `map_extra` is set the arena must be mapped, by constructing an
+/// [`Arena`], before loading the programs using it.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 6.9.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use aya::maps::Arena;
+///
+/// let arena = Arena::try_from(bpf.take_map("ARENA").unwrap())?;
+/// let program: &mut aya::programs::UProbe = bpf.program_mut("build_graph").unwrap().try_into()?;
+/// program.load()?;
+///
+/// let memory = arena.as_ptr();
+/// println!("arena mapped at {:p}, {} bytes", memory.cast::<u8>(), memory.len());
+/// # Ok::<(), aya::EbpfError>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_ARENA")]
+pub struct Arena<T> {
+    map: T,
+    mmap: MMap,
+}
+
+impl<T: Borrow<MapData>> Arena<T> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data: &MapData = map.borrow();
+        let len = usize::try_from(data.obj.max_entries()).unwrap() * page_size();
+        // The kernel only accepts the mapping at the arena's address, if it has one. It isn't
+        // mapped with MAP_FIXED so that existing mappings are never replaced.
+        let addr = data.obj.map_extra() as *mut _;
+        let mmap = MMap::new_at(
+            addr,
+            data.fd().as_fd(),
+            len,
+            PROT_READ | PROT_WRITE,
+            MAP_SHARED,
+            0,
+        )?;
+        Ok(Self { map, mmap })
+    }
+}
+
+impl<T> Arena<T> {
+    /// Returns a pointer to the memory of the arena.
+    ///
+    /// The memory is concurrently accessed by eBPF programs, and pages which haven't been
+    /// allocated yet are allocated on first access.
+    pub fn as_ptr(&self) -> NonNull<[u8]> {
+        let Self { map: _, mmap } = self;
+        NonNull::slice_from_raw_parts(mmap.ptr().cast(), mmap.len())
+    }
+}
+
+impl<T: Borrow<MapData>> AsFd for Arena<T> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        let Self { map, mmap: _ } = self;
+        map.borrow().fd().as_fd()
+    }
+}
+
+impl<T: Borrow<MapData>> AsRawFd for Arena<T> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use aya_obj::generated::bpf_map_type::{BPF_MAP_TYPE_ARENA, BPF_MAP_TYPE_ARRAY};
+
+    use super::*;
+    use crate::{
+        maps::{
+            Map,
+            test_utils::{self, new_map},
+        },
+        sys::TEST_MMAP_RET,
+    };
+
+    fn new_arena(buf: &mut [u8]) -> MapData {
+        TEST_MMAP_RET.with(|ret| *ret.borrow_mut() = buf.as_mut_ptr().cast());
+        new_map(test_utils::new_obj_map_with_max_entries::<u32>(
+            BPF_MAP_TYPE_ARENA,
+            4,
+        ))
+    }
+
+    #[test]
+    fn test_try_from_wrong_map() {
+        let map = new_map(test_utils::new_obj_map::<u32>(BPF_MAP_TYPE_ARRAY));
+        let map = Map::Array(map);
+
+        assert_matches!(
+            Arena::try_from(&map).map(|_| ()),
+            Err(MapError::InvalidMapType { .. })
+        );
+    }
+
+    #[test]
+    fn test_as_ptr() {
+        let mut buf = vec![0u8; 4 * page_size()];
+        let map = Map::Arena(new_arena(&mut buf));
+        let arena = Arena::try_from(&map).unwrap();
+
+        let memory = arena.as_ptr();
+        assert_eq!(memory.cast::<u8>().as_ptr(), buf.as_mut_ptr());
+        assert_eq!(memory.len(), 4 * page_size());
+    }
+}
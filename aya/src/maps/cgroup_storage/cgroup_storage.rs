@@ -0,0 +1,235 @@
+//! A cgroup storage map.
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    os::fd::AsFd as _,
+};
+
+use crate::{
+    Pod,
+    maps::{
+        IterableMap, MapData, MapError, MapIter, MapKeys, cgroup_storage::CgroupStorageKey,
+        check_kv_size,
+    },
+    sys::{SyscallError, bpf_map_lookup_elem, bpf_map_update_elem},
+};
+
+/// A map that holds a value for each cgroup and attach type of the programs using it.
+///
+/// eBPF programs access the value of the cgroup they are running for by calling
+/// `bpf_get_local_storage`.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.19.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use aya::maps::CgroupStorage;
+///
+/// let storage = CgroupStorage::<_, u64>::try_from(bpf.map("PACKETS").unwrap())?;
+/// for entry in storage.iter() {
+///     let (key, packets) = entry?;
+///     println!("cgroup {}: {} packets", key.cgroup_inode_id(), packets);
+/// }
+/// # Ok::<(), aya::EbpfError>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_CGROUP_STORAGE")]
+#[derive(Debug)]
+pub struct CgroupStorage<T, V> {
+    pub(crate) inner: T,
+    _v: PhantomData<V>,
+}
+
+impl<T: Borrow<MapData>, V: Pod> CgroupStorage<T, V> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data = map.borrow();
+        check_kv_size::<CgroupStorageKey, V>(data)?;
+
+        Ok(Self {
+            inner: map,
+            _v: PhantomData,
+        })
+    }
+
+    /// Returns a copy of the value associated with the key.
+    pub fn get(&self, key: &CgroupStorageKey, flags: u64) -> Result<V, MapError> {
+        let fd = self.inner.borrow().fd().as_fd();
+        let value = bpf_map_lookup_elem(fd, key, flags).map_err(|io_error| SyscallError {
+            call: "bpf_map_lookup_elem",
+            io_error,
+        })?;
+        value.ok_or(MapError::KeyNotFound)
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order. The
+    /// iterator item type is `Result<(CgroupStorageKey, V), MapError>`.
+    pub fn iter(&self) -> MapIter<'_, CgroupStorageKey, V, Self> {
+        MapIter::new(self)
+    }
+
+    /// An iterator visiting all keys in arbitrary order. The iterator element
+    /// type is `Result<CgroupStorageKey, MapError>`.
+    pub fn keys(&self) -> MapKeys<'_, CgroupStorageKey> {
+        MapKeys::new(self.inner.borrow())
+    }
+}
+
+impl<T: BorrowMut<MapData>, V: Pod> CgroupStorage<T, V> {
+    /// Sets the value associated with the key.
+    ///
+    /// The entry must already exist, which is the case while a program using the map is attached
+    /// to the cgroup with the attach type of the key.
+    pub fn set(
+        &mut self,
+        key: &CgroupStorageKey,
+        value: impl Borrow<V>,
+        flags: u64,
+    ) -> Result<(), MapError> {
+        let fd = self.inner.borrow_mut().fd().as_fd();
+        bpf_map_update_elem(fd, Some(key), value.borrow(), flags)
+            .map_err(|io_error| SyscallError {
+                call: "bpf_map_update_elem",
+                io_error,
+            })
+            .map_err(Into::into)
+    }
+}
+
+impl<T: Borrow<MapData>, V: Pod> IterableMap<CgroupStorageKey, V> for CgroupStorage<T, V> {
+    fn map(&self) -> &MapData {
+        self.inner.borrow()
+    }
+
+    fn get(&self, key: &CgroupStorageKey) -> Result<V, MapError> {
+        Self::get(self, key, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use assert_matches::assert_matches;
+    use aya_obj::generated::{
+        bpf_attr, bpf_cmd,
+        bpf_map_type::{BPF_MAP_TYPE_ARRAY, BPF_MAP_TYPE_CGROUP_STORAGE_DEPRECATED},
+    };
+    use libc::{EFAULT, ENOENT};
+
+    use super::*;
+    use crate::{
+        maps::{
+            Map,
+            test_utils::{self, new_map},
+        },
+        sys::{SysResult, Syscall, override_syscall},
+    };
+
+    fn new_obj_map() -> aya_obj::Map {
+        test_utils::new_obj_map::<CgroupStorageKey>(BPF_MAP_TYPE_CGROUP_STORAGE_DEPRECATED)
+    }
+
+    fn sys_error(value: i32) -> SysResult {
+        Err((-1, io::Error::from_raw_os_error(value)))
+    }
+
+    fn bpf_key(attr: &bpf_attr) -> Option<CgroupStorageKey> {
+        match unsafe { attr.__bindgen_anon_2.key } as *const CgroupStorageKey {
+            p if p.is_null() => None,
+            p => Some(unsafe { *p }),
+        }
+    }
+
+    #[test]
+    fn test_try_from_wrong_map() {
+        let map = Map::Array(new_map(test_utils::new_obj_map::<u32>(BPF_MAP_TYPE_ARRAY)));
+
+        assert_matches!(
+            CgroupStorage::<_, u32>::try_from(&map),
+            Err(MapError::InvalidMapType { .. })
+        );
+    }
+
+    #[test]
+    fn test_try_from_ok() {
+        let map = Map::CgroupStorage(new_map(new_obj_map()));
+        let _: CgroupStorage<_, u32> = map.try_into().unwrap();
+    }
+
+    #[test]
+    fn test_get_not_found() {
+        let map = new_map(new_obj_map());
+        let storage = CgroupStorage::<_, u32>::new(&map).unwrap();
+
+        override_syscall(|_| sys_error(ENOENT));
+
+        assert_matches!(
+            storage.get(&CgroupStorageKey::new(1, 0), 0),
+            Err(MapError::KeyNotFound)
+        );
+    }
+
+    #[test]
+    fn test_set_ok() {
+        let mut map = new_map(new_obj_map());
+        let mut storage = CgroupStorage::<_, u32>::new(&mut map).unwrap();
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_UPDATE_ELEM,
+                attr,
+            } => {
+                let key = bpf_key(attr).unwrap();
+                assert_eq!(key.cgroup_inode_id(), 42);
+                assert_eq!(key.attach_type(), 1);
+                Ok(0)
+            }
+            _ => sys_error(EFAULT),
+        });
+
+        assert_matches!(storage.set(&CgroupStorageKey::new(42, 1), 1, 0), Ok(()));
+    }
+
+    #[test]
+    fn test_iter() {
+        let map = new_map(new_obj_map());
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_GET_NEXT_KEY,
+                attr,
+            } => {
+                let next = match bpf_key(attr).map(|key| key.cgroup_inode_id()) {
+                    None => 10,
+                    Some(10) => 20,
+                    Some(_) => return sys_error(ENOENT),
+                };
+                let key = unsafe { attr.__bindgen_anon_2.__bindgen_anon_1.next_key }
+                    as *mut CgroupStorageKey;
+                unsafe { *key = CgroupStorageKey::new(next, 0) };
+                Ok(0)
+            }
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_LOOKUP_ELEM,
+                attr,
+            } => {
+                let key = bpf_key(attr).unwrap();
+                let value = unsafe { attr.__bindgen_anon_2.__bindgen_anon_1.value } as *mut u32;
+                unsafe { *value = key.cgroup_inode_id() as u32 * 10 };
+                Ok(0)
+            }
+            _ => sys_error(EFAULT),
+        });
+
+        let storage = CgroupStorage::<_, u32>::new(&map).unwrap();
+        let items = storage
+            .iter()
+            .map(|item| item.map(|(key, value)| (key.cgroup_inode_id(), value)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(&items, &[(10, 100), (20, 200)]);
+    }
+}
@@ -0,0 +1,64 @@
+//! Cgroup storage types.
+//!
+//! Cgroup storage maps hold a value for each cgroup and attach type that a program using the map
+//! is attached to. Entries are created by the kernel when the program is attached and freed when
+//! it is detached, so they can be read and updated from userspace but not inserted or removed.
+use aya_obj::generated::bpf_cgroup_storage_key;
+
+#[expect(clippy::module_inception)]
+mod cgroup_storage;
+mod per_cpu_cgroup_storage;
+
+pub use cgroup_storage::*;
+pub use per_cpu_cgroup_storage::*;
+
+use crate::Pod;
+
+/// The key of [`CgroupStorage`] and [`PerCpuCgroupStorage`] maps.
+///
+/// A storage entry is identified by the cgroup the program is attached to and by the attach type
+/// it was attached with.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct CgroupStorageKey(bpf_cgroup_storage_key);
+
+impl CgroupStorageKey {
+    /// Creates a new key.
+    ///
+    /// `cgroup_inode_id` is the inode number of the cgroup directory in the cgroup v2 hierarchy
+    /// and `attach_type` is the value of `bpf_attach_type` the program was attached with, such as
+    /// `BPF_CGROUP_INET_INGRESS`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::os::unix::fs::MetadataExt as _;
+    ///
+    /// use aya::maps::cgroup_storage::CgroupStorageKey;
+    ///
+    /// const BPF_CGROUP_INET_INGRESS: u32 = 0;
+    ///
+    /// let cgroup = std::fs::metadata("/sys/fs/cgroup/unified")?;
+    /// let key = CgroupStorageKey::new(cgroup.ino(), BPF_CGROUP_INET_INGRESS);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn new(cgroup_inode_id: u64, attach_type: u32) -> Self {
+        Self(bpf_cgroup_storage_key {
+            cgroup_inode_id,
+            attach_type,
+        })
+    }
+
+    /// Returns the inode number of the cgroup.
+    pub fn cgroup_inode_id(&self) -> u64 {
+        self.0.cgroup_inode_id
+    }
+
+    /// Returns the attach type of the program.
+    pub fn attach_type(&self) -> u32 {
+        self.0.attach_type
+    }
+}
+
+// A Pod impl is required as CgroupStorageKey is a key for a map.
+unsafe impl Pod for CgroupStorageKey {}
@@ -0,0 +1,259 @@
+//! A per-CPU cgroup storage map.
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    os::fd::AsFd as _,
+};
+
+use crate::{
+    Pod,
+    maps::{
+        IterableMap, MapData, MapError, MapIter, MapKeys, PerCpuValues,
+        cgroup_storage::CgroupStorageKey, check_kv_size,
+    },
+    sys::{SyscallError, bpf_map_lookup_elem_per_cpu, bpf_map_update_elem_per_cpu},
+};
+
+/// Similar to [`CgroupStorage`](crate::maps::CgroupStorage) but each CPU holds a separate value
+/// for a given key. Typically used to minimize lock contention in eBPF programs.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.20.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use aya::maps::PerCpuCgroupStorage;
+///
+/// let storage = PerCpuCgroupStorage::<_, u64>::try_from(bpf.map("PACKETS").unwrap())?;
+/// for entry in storage.iter() {
+///     let (key, packets) = entry?;
+///     let total: u64 = packets.iter().sum();
+///     println!("cgroup {}: {} packets", key.cgroup_inode_id(), total);
+/// }
+/// # Ok::<(), aya::EbpfError>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_PERCPU_CGROUP_STORAGE")]
+#[derive(Debug)]
+pub struct PerCpuCgroupStorage<T, V: Pod> {
+    pub(crate) inner: T,
+    _v: PhantomData<V>,
+}
+
+impl<T: Borrow<MapData>, V: Pod> PerCpuCgroupStorage<T, V> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data = map.borrow();
+        check_kv_size::<CgroupStorageKey, V>(data)?;
+
+        Ok(Self {
+            inner: map,
+            _v: PhantomData,
+        })
+    }
+
+    /// Returns a slice of values - one for each CPU - associated with the key.
+    pub fn get(&self, key: &CgroupStorageKey, flags: u64) -> Result<PerCpuValues<V>, MapError> {
+        let fd = self.inner.borrow().fd().as_fd();
+        let values =
+            bpf_map_lookup_elem_per_cpu(fd, key, flags).map_err(|io_error| SyscallError {
+                call: "bpf_map_lookup_elem",
+                io_error,
+            })?;
+        values.ok_or(MapError::KeyNotFound)
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order. The
+    /// iterator item type is `Result<(CgroupStorageKey, PerCpuValues<V>), MapError>`.
+    pub fn iter(&self) -> MapIter<'_, CgroupStorageKey, PerCpuValues<V>, Self> {
+        MapIter::new(self)
+    }
+
+    /// An iterator visiting all keys in arbitrary order. The iterator element
+    /// type is `Result<CgroupStorageKey, MapError>`.
+    pub fn keys(&self) -> MapKeys<'_, CgroupStorageKey> {
+        MapKeys::new(self.inner.borrow())
+    }
+}
+
+impl<T: BorrowMut<MapData>, V: Pod> PerCpuCgroupStorage<T, V> {
+    /// Sets a slice of values - one for each CPU - for the given key.
+    ///
+    /// The entry must already exist, which is the case while a program using the map is attached
+    /// to the cgroup with the attach type of the key.
+    pub fn set(
+        &mut self,
+        key: &CgroupStorageKey,
+        values: PerCpuValues<V>,
+        flags: u64,
+    ) -> Result<(), MapError> {
+        let fd = self.inner.borrow_mut().fd().as_fd();
+        bpf_map_update_elem_per_cpu(fd, key, &values, flags)
+            .map_err(|io_error| SyscallError {
+                call: "bpf_map_update_elem",
+                io_error,
+            })
+            .map_err(Into::into)
+    }
+}
+
+impl<T: Borrow<MapData>, V: Pod> IterableMap<CgroupStorageKey, PerCpuValues<V>>
+    for PerCpuCgroupStorage<T, V>
+{
+    fn map(&self) -> &MapData {
+        self.inner.borrow()
+    }
+
+    fn get(&self, key: &CgroupStorageKey) -> Result<PerCpuValues<V>, MapError> {
+        Self::get(self, key, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use assert_matches::assert_matches;
+    use aya_obj::generated::{
+        bpf_attr, bpf_cmd,
+        bpf_map_type::{BPF_MAP_TYPE_ARRAY, BPF_MAP_TYPE_PERCPU_CGROUP_STORAGE_DEPRECATED},
+    };
+    use libc::{EFAULT, ENOENT};
+
+    use super::*;
+    use crate::{
+        maps::{Map, test_utils},
+        sys::{SysResult, Syscall, override_syscall},
+        util::nr_cpus,
+    };
+
+    fn sys_error(value: i32) -> SysResult {
+        Err((-1, io::Error::from_raw_os_error(value)))
+    }
+
+    fn new_map() -> MapData {
+        test_utils::new_map(test_utils::new_obj_map::<CgroupStorageKey>(
+            BPF_MAP_TYPE_PERCPU_CGROUP_STORAGE_DEPRECATED,
+        ))
+    }
+
+    fn bpf_key(attr: &bpf_attr) -> Option<CgroupStorageKey> {
+        match unsafe { attr.__bindgen_anon_2.key } as *const CgroupStorageKey {
+            p if p.is_null() => None,
+            p => Some(unsafe { *p }),
+        }
+    }
+
+    #[test]
+    fn test_try_from_wrong_map() {
+        let map = Map::Array(test_utils::new_map(test_utils::new_obj_map::<u32>(
+            BPF_MAP_TYPE_ARRAY,
+        )));
+
+        assert_matches!(
+            PerCpuCgroupStorage::<_, u32>::try_from(&map),
+            Err(MapError::InvalidMapType { .. })
+        );
+    }
+
+    #[test]
+    fn test_try_from_ok() {
+        let map = Map::PerCpuCgroupStorage(new_map());
+        let _: PerCpuCgroupStorage<_, u32> = map.try_into().unwrap();
+    }
+
+    #[test]
+    fn test_get_not_found() {
+        let map = Map::PerCpuCgroupStorage(new_map());
+        let storage = PerCpuCgroupStorage::<_, u32>::try_from(&map).unwrap();
+
+        override_syscall(|_| sys_error(ENOENT));
+
+        assert_matches!(
+            storage.get(&CgroupStorageKey::new(1, 0), 0),
+            Err(MapError::KeyNotFound)
+        );
+    }
+
+    #[test]
+    fn test_set_ok() {
+        let mut map = new_map();
+        let mut storage = PerCpuCgroupStorage::<_, u32>::new(&mut map).unwrap();
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_UPDATE_ELEM,
+                attr,
+            } => {
+                let key = bpf_key(attr).unwrap();
+                assert_eq!(key.cgroup_inode_id(), 42);
+                assert_eq!(key.attach_type(), 1);
+                // Each value is padded to 8 bytes.
+                let values = unsafe { attr.__bindgen_anon_2.__bindgen_anon_1.value } as *const u8;
+                let values = unsafe { std::slice::from_raw_parts(values, nr_cpus().unwrap() * 8) };
+                for value in values.chunks_exact(8) {
+                    assert_eq!(value[..4], 1u32.to_ne_bytes());
+                }
+                Ok(0)
+            }
+            _ => sys_error(EFAULT),
+        });
+
+        let nr_cpus = nr_cpus().unwrap();
+        let values = PerCpuValues::try_from(vec![1u32; nr_cpus]).unwrap();
+        assert_matches!(
+            storage.set(&CgroupStorageKey::new(42, 1), values, 0),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let map = new_map();
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_GET_NEXT_KEY,
+                attr,
+            } => {
+                let next = match bpf_key(attr).map(|key| key.cgroup_inode_id()) {
+                    None => 10,
+                    Some(10) => 20,
+                    Some(_) => return sys_error(ENOENT),
+                };
+                let key = unsafe { attr.__bindgen_anon_2.__bindgen_anon_1.next_key }
+                    as *mut CgroupStorageKey;
+                unsafe { *key = CgroupStorageKey::new(next, 0) };
+                Ok(0)
+            }
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_LOOKUP_ELEM,
+                attr,
+            } => {
+                let key = bpf_key(attr).unwrap();
+                let value = key.cgroup_inode_id() as u32 * 10;
+                let values = unsafe { attr.__bindgen_anon_2.__bindgen_anon_1.value } as *mut u8;
+                let values =
+                    unsafe { std::slice::from_raw_parts_mut(values, nr_cpus().unwrap() * 8) };
+                for chunk in values.chunks_exact_mut(8) {
+                    chunk[..4].copy_from_slice(&value.to_ne_bytes());
+                }
+                Ok(0)
+            }
+            _ => sys_error(EFAULT),
+        });
+
+        let nr_cpus = nr_cpus().unwrap();
+        let storage = PerCpuCgroupStorage::<_, u32>::new(&map).unwrap();
+        let items = storage
+            .iter()
+            .map(|item| item.map(|(key, values)| (key.cgroup_inode_id(), values.to_vec())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            &items,
+            &[(10, vec![100; nr_cpus]), (20, vec![200; nr_cpus])]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    os::fd::AsFd,
+};
+
+use crate::{
+    Pod,
+    maps::{MapData, MapError, check_kv_size, local_storage},
+};
+
+/// Per-cgroup storage.
+///
+/// Each cgroup can be given a value of type `V`, which is freed together with
+/// the cgroup. eBPF programs access the value through the cgroup, userspace
+/// through a file descriptor of the cgroup directory.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 6.2.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::fs::File;
+/// use aya::maps::CgrpStorage;
+///
+/// let mut storage = CgrpStorage::<_, u64>::try_from(bpf.map_mut("CGRP_STORAGE").unwrap())?;
+/// let cgroup = File::open("/sys/fs/cgroup/unified")?;
+///
+/// storage.insert(&cgroup, 42, 0)?;
+/// assert_eq!(storage.get(&cgroup, 0)?, 42);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_CGRP_STORAGE")]
+#[derive(Debug)]
+pub struct CgrpStorage<T, V> {
+    pub(crate) inner: T,
+    _v: PhantomData<V>,
+}
+
+impl<T: Borrow<MapData>, V: Pod> CgrpStorage<T, V> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data = map.borrow();
+        check_kv_size::<i32, V>(data)?;
+
+        Ok(Self {
+            inner: map,
+            _v: PhantomData,
+        })
+    }
+
+    /// Returns the value stored for the given cgroup.
+    pub fn get(&self, cgroup: impl AsFd, flags: u64) -> Result<V, MapError> {
+        local_storage::get(self.inner.borrow(), cgroup, flags)
+    }
+}
+
+impl<T: BorrowMut<MapData>, V: Pod> CgrpStorage<T, V> {
+    /// Stores a value for the given cgroup.
+    pub fn insert(
+        &mut self,
+        cgroup: impl AsFd,
+        value: impl Borrow<V>,
+        flags: u64,
+    ) -> Result<(), MapError> {
+        local_storage::insert(self.inner.borrow_mut(), cgroup, value.borrow(), flags)
+    }
+
+    /// Removes the value stored for the given cgroup.
+    pub fn remove(&mut self, cgroup: impl AsFd) -> Result<(), MapError> {
+        local_storage::remove(self.inner.borrow_mut(), cgroup)
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    os::fd::AsFd,
+};
+
+use crate::{
+    Pod,
+    maps::{MapData, MapError, check_kv_size, local_storage},
+};
+
+/// Per-inode storage.
+///
+/// Each inode can be given a value of type `V`, which is freed together with
+/// the inode. eBPF programs access the value through the inode, userspace
+/// through a file descriptor of a file referring to the inode.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.10.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::fs::File;
+/// use aya::maps::InodeStorage;
+///
+/// let mut storage = InodeStorage::<_, u64>::try_from(bpf.map_mut("INODE_STORAGE").unwrap())?;
+/// let file = File::open("/etc/passwd")?;
+///
+/// storage.insert(&file, 42, 0)?;
+/// assert_eq!(storage.get(&file, 0)?, 42);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_INODE_STORAGE")]
+#[derive(Debug)]
+pub struct InodeStorage<T, V> {
+    pub(crate) inner: T,
+    _v: PhantomData<V>,
+}
+
+impl<T: Borrow<MapData>, V: Pod> InodeStorage<T, V> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data = map.borrow();
+        check_kv_size::<i32, V>(data)?;
+
+        Ok(Self {
+            inner: map,
+            _v: PhantomData,
+        })
+    }
+
+    /// Returns the value stored for the given inode.
+    pub fn get(&self, file: impl AsFd, flags: u64) -> Result<V, MapError> {
+        local_storage::get(self.inner.borrow(), file, flags)
+    }
+}
+
+impl<T: BorrowMut<MapData>, V: Pod> InodeStorage<T, V> {
+    /// Stores a value for the given inode.
+    pub fn insert(
+        &mut self,
+        file: impl AsFd,
+        value: impl Borrow<V>,
+        flags: u64,
+    ) -> Result<(), MapError> {
+        local_storage::insert(self.inner.borrow_mut(), file, value.borrow(), flags)
+    }
+
+    /// Removes the value stored for the given inode.
+    pub fn remove(&mut self, file: impl AsFd) -> Result<(), MapError> {
+        local_storage::remove(self.inner.borrow_mut(), file)
+    }
+}
@@ -0,0 +1,59 @@
+//! Local storage types.
+//!
+//! Local storage maps attach a value to a kernel object, such as a socket or a
+//! task. From userspace, the object is identified by a file descriptor
+//! referring to it.
+mod cgrp_storage;
+mod inode_storage;
+mod sk_storage;
+mod task_storage;
+
+use std::os::fd::{AsFd, AsRawFd as _};
+
+pub use cgrp_storage::CgrpStorage;
+pub use inode_storage::InodeStorage;
+pub use sk_storage::SkStorage;
+pub use task_storage::TaskStorage;
+
+use crate::{
+    Pod,
+    maps::{MapData, MapError},
+    sys::{SyscallError, bpf_map_delete_elem, bpf_map_lookup_elem, bpf_map_update_elem},
+};
+
+pub(crate) fn get<V: Pod>(map: &MapData, object: impl AsFd, flags: u64) -> Result<V, MapError> {
+    let fd = map.fd().as_fd();
+    let key = object.as_fd().as_raw_fd();
+    let value = bpf_map_lookup_elem(fd, &key, flags).map_err(|io_error| SyscallError {
+        call: "bpf_map_lookup_elem",
+        io_error,
+    })?;
+    value.ok_or(MapError::KeyNotFound)
+}
+
+pub(crate) fn insert<V: Pod>(
+    map: &MapData,
+    object: impl AsFd,
+    value: &V,
+    flags: u64,
+) -> Result<(), MapError> {
+    let fd = map.fd().as_fd();
+    let key = object.as_fd().as_raw_fd();
+    bpf_map_update_elem(fd, Some(&key), value, flags)
+        .map_err(|io_error| SyscallError {
+            call: "bpf_map_update_elem",
+            io_error,
+        })
+        .map_err(Into::into)
+}
+
+pub(crate) fn remove(map: &MapData, object: impl AsFd) -> Result<(), MapError> {
+    let fd = map.fd().as_fd();
+    let key = object.as_fd().as_raw_fd();
+    bpf_map_delete_elem(fd, &key)
+        .map_err(|io_error| SyscallError {
+            call: "bpf_map_delete_elem",
+            io_error,
+        })
+        .map_err(Into::into)
+}
@@ -0,0 +1,214 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    os::fd::AsFd,
+};
+
+use crate::{
+    Pod,
+    maps::{MapData, MapError, check_kv_size, local_storage},
+};
+
+/// Per-socket storage.
+///
+/// Each socket can be given a value of type `V`, which is freed together with
+/// the socket. eBPF programs access the value through the socket, userspace
+/// through a file descriptor of the socket.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.2.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::net::TcpStream;
+/// use aya::maps::SkStorage;
+///
+/// let mut storage = SkStorage::<_, u64>::try_from(bpf.map_mut("SK_STORAGE").unwrap())?;
+/// let socket = TcpStream::connect("127.0.0.1:1234")?;
+///
+/// storage.insert(&socket, 42, 0)?;
+/// assert_eq!(storage.get(&socket, 0)?, 42);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_SK_STORAGE")]
+#[derive(Debug)]
+pub struct SkStorage<T, V> {
+    pub(crate) inner: T,
+    _v: PhantomData<V>,
+}
+
+impl<T: Borrow<MapData>, V: Pod> SkStorage<T, V> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data = map.borrow();
+        check_kv_size::<i32, V>(data)?;
+
+        Ok(Self {
+            inner: map,
+            _v: PhantomData,
+        })
+    }
+
+    /// Returns the value stored for the given socket.
+    pub fn get(&self, socket: impl AsFd, flags: u64) -> Result<V, MapError> {
+        local_storage::get(self.inner.borrow(), socket, flags)
+    }
+}
+
+impl<T: BorrowMut<MapData>, V: Pod> SkStorage<T, V> {
+    /// Stores a value for the given socket.
+    pub fn insert(
+        &mut self,
+        socket: impl AsFd,
+        value: impl Borrow<V>,
+        flags: u64,
+    ) -> Result<(), MapError> {
+        local_storage::insert(self.inner.borrow_mut(), socket, value.borrow(), flags)
+    }
+
+    /// Removes the value stored for the given socket.
+    pub fn remove(&mut self, socket: impl AsFd) -> Result<(), MapError> {
+        local_storage::remove(self.inner.borrow_mut(), socket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, os::fd::BorrowedFd};
+
+    use assert_matches::assert_matches;
+    use aya_obj::generated::{
+        bpf_cmd,
+        bpf_map_type::{BPF_MAP_TYPE_ARRAY, BPF_MAP_TYPE_SK_STORAGE},
+    };
+    use libc::{EFAULT, ENOENT};
+
+    use super::*;
+    use crate::{
+        maps::{
+            Map,
+            test_utils::{self, new_map},
+        },
+        sys::{SysResult, Syscall, SyscallError, override_syscall},
+    };
+
+    fn new_sk_storage() -> MapData {
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_BTF_LOAD | bpf_cmd::BPF_MAP_CREATE,
+                ..
+            } => Ok(crate::MockableFd::mock_signed_fd().into()),
+            call => panic!("unexpected syscall {call:?}"),
+        });
+        MapData::create(
+            test_utils::new_obj_map::<i32>(BPF_MAP_TYPE_SK_STORAGE),
+            "foo",
+            None,
+        )
+        .unwrap()
+    }
+
+    fn sys_error(value: i32) -> SysResult {
+        Err((-1, io::Error::from_raw_os_error(value)))
+    }
+
+    fn socket() -> BorrowedFd<'static> {
+        unsafe { BorrowedFd::borrow_raw(42) }
+    }
+
+    #[test]
+    fn test_wrong_value_size() {
+        let map = new_sk_storage();
+        assert_matches!(
+            SkStorage::<_, u64>::new(&map),
+            Err(MapError::InvalidValueSize {
+                size: 8,
+                expected: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_from_wrong_map() {
+        let map = new_map(test_utils::new_obj_map::<u32>(BPF_MAP_TYPE_ARRAY));
+        let map = Map::Array(map);
+
+        assert_matches!(
+            SkStorage::<_, u32>::try_from(&map),
+            Err(MapError::InvalidMapType { .. })
+        );
+    }
+
+    #[test]
+    fn test_try_from_ok() {
+        let map = Map::SkStorage(new_sk_storage());
+        let _: SkStorage<_, u32> = map.try_into().unwrap();
+    }
+
+    #[test]
+    fn test_insert_ok() {
+        let mut map = new_sk_storage();
+        let mut storage = SkStorage::<_, u32>::new(&mut map).unwrap();
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_UPDATE_ELEM,
+                attr,
+            } => {
+                let key = unsafe { *(attr.__bindgen_anon_2.key as *const i32) };
+                assert_eq!(key, 42);
+                Ok(0)
+            }
+            _ => sys_error(EFAULT),
+        });
+
+        assert_matches!(storage.insert(socket(), 1, 0), Ok(()));
+    }
+
+    #[test]
+    fn test_get_syscall_error() {
+        let map = new_sk_storage();
+        let storage = SkStorage::<_, u32>::new(&map).unwrap();
+
+        override_syscall(|_| sys_error(EFAULT));
+
+        assert_matches!(
+            storage.get(socket(), 0),
+            Err(MapError::SyscallError(SyscallError { call: "bpf_map_lookup_elem", io_error })) if io_error.raw_os_error() == Some(EFAULT)
+        );
+    }
+
+    #[test]
+    fn test_get_not_found() {
+        let map = new_sk_storage();
+        let storage = SkStorage::<_, u32>::new(&map).unwrap();
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_LOOKUP_ELEM,
+                ..
+            } => sys_error(ENOENT),
+            _ => sys_error(EFAULT),
+        });
+
+        assert_matches!(storage.get(socket(), 0), Err(MapError::KeyNotFound));
+    }
+
+    #[test]
+    fn test_remove_ok() {
+        let mut map = new_sk_storage();
+        let mut storage = SkStorage::<_, u32>::new(&mut map).unwrap();
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_DELETE_ELEM,
+                ..
+            } => Ok(0),
+            _ => sys_error(EFAULT),
+        });
+
+        assert_matches!(storage.remove(socket()), Ok(()));
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    os::fd::AsFd,
+};
+
+use crate::{
+    Pod,
+    maps::{MapData, MapError, check_kv_size, local_storage},
+};
+
+/// Per-task storage.
+///
+/// Each task can be given a value of type `V`, which is freed together with
+/// the task. eBPF programs access the value through the task, userspace
+/// through a pidfd of the task.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.11.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::os::fd::{FromRawFd as _, OwnedFd};
+/// use aya::maps::TaskStorage;
+///
+/// let mut storage = TaskStorage::<_, u64>::try_from(bpf.map_mut("TASK_STORAGE").unwrap())?;
+/// let pidfd = unsafe { libc::syscall(libc::SYS_pidfd_open, std::process::id(), 0) };
+/// let pidfd = unsafe { OwnedFd::from_raw_fd(pidfd as i32) };
+///
+/// storage.insert(&pidfd, 42, 0)?;
+/// assert_eq!(storage.get(&pidfd, 0)?, 42);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_TASK_STORAGE")]
+#[derive(Debug)]
+pub struct TaskStorage<T, V> {
+    pub(crate) inner: T,
+    _v: PhantomData<V>,
+}
+
+impl<T: Borrow<MapData>, V: Pod> TaskStorage<T, V> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data = map.borrow();
+        check_kv_size::<i32, V>(data)?;
+
+        Ok(Self {
+            inner: map,
+            _v: PhantomData,
+        })
+    }
+
+    /// Returns the value stored for the given task.
+    pub fn get(&self, pidfd: impl AsFd, flags: u64) -> Result<V, MapError> {
+        local_storage::get(self.inner.borrow(), pidfd, flags)
+    }
+}
+
+impl<T: BorrowMut<MapData>, V: Pod> TaskStorage<T, V> {
+    /// Stores a value for the given task.
+    pub fn insert(
+        &mut self,
+        pidfd: impl AsFd,
+        value: impl Borrow<V>,
+        flags: u64,
+    ) -> Result<(), MapError> {
+        local_storage::insert(self.inner.borrow_mut(), pidfd, value.borrow(), flags)
+    }
+
+    /// Removes the value stored for the given task.
+    pub fn remove(&mut self, pidfd: impl AsFd) -> Result<(), MapError> {
+        local_storage::remove(self.inner.borrow_mut(), pidfd)
+    }
+}
@@ -74,30 +74,42 @@ use crate::{
     util::{KernelVersion, nr_cpus},
 };
 
+pub mod arena;
 pub mod array;
 pub mod bloom_filter;
+pub mod cgroup_storage;
 pub mod hash_map;
 mod info;
+pub mod local_storage;
 pub mod lpm_trie;
+pub mod of_maps;
 pub mod perf;
 pub mod queue;
 pub mod ring_buf;
 pub mod sock;
 pub mod stack;
 pub mod stack_trace;
+pub mod struct_ops;
+pub mod user_ring_buf;
 pub mod xdp;
 
+pub use arena::Arena;
 pub use array::{Array, PerCpuArray, ProgramArray};
 pub use bloom_filter::BloomFilter;
+pub use cgroup_storage::{CgroupStorage, PerCpuCgroupStorage};
 pub use hash_map::{HashMap, PerCpuHashMap};
 pub use info::{MapInfo, MapType, loaded_maps};
+pub use local_storage::{CgrpStorage, InodeStorage, SkStorage, TaskStorage};
 pub use lpm_trie::LpmTrie;
+pub use of_maps::{ArrayOfMaps, HashOfMaps};
 pub use perf::PerfEventArray;
 pub use queue::Queue;
 pub use ring_buf::RingBuf;
-pub use sock::{SockHash, SockMap};
+pub use sock::{ReusePortSockArray, SockHash, SockMap};
 pub use stack::Stack;
 pub use stack_trace::StackTraceMap;
+pub use struct_ops::{StructOpsLink, StructOpsMap};
+pub use user_ring_buf::UserRingBuf;
 pub use xdp::{CpuMap, DevMap, DevMapHash, XskMap};
 
 #[derive(Error, Debug)]
@@ -166,6 +178,13 @@ pub enum MapError {
     #[error("the program is not loaded")]
     ProgramNotLoaded,
 
+    /// Program not found
+    #[error("program `{name}` not found")]
+    ProgramNotFound {
+        /// The program name
+        name: String,
+    },
+
     /// An IO error occurred
     #[error(transparent)]
     IoError(#[from] io::Error),
@@ -188,6 +207,14 @@ pub enum MapError {
     #[error("program ids are not supported by the current kernel")]
     ProgIdNotSupported,
 
+    /// The map requires BTF for its key and value, but the object's BTF
+    /// couldn't be loaded
+    #[error("map `{name}` requires BTF, but the BTF of the object couldn't be loaded")]
+    MissingBtf {
+        /// Map name
+        name: String,
+    },
+
     /// Unsupported Map type
     #[error(
         "type of {name} ({map_type:?}) is unsupported; see `EbpfLoader::allow_unsupported_maps`"
@@ -218,7 +245,7 @@ impl MapFd {
         Self { fd }
     }
 
-    fn try_clone(&self) -> io::Result<Self> {
+    pub(crate) fn try_clone(&self) -> io::Result<Self> {
         let Self { fd } = self;
         let fd = fd.try_clone()?;
         Ok(Self { fd })
@@ -269,10 +296,18 @@ fn maybe_warn_rlimit() {
 /// eBPF map types.
 #[derive(Debug)]
 pub enum Map {
+    /// An [`Arena`] map.
+    Arena(MapData),
     /// An [`Array`] map.
     Array(MapData),
+    /// An [`ArrayOfMaps`] map.
+    ArrayOfMaps(MapData),
     /// A [`BloomFilter`] map.
     BloomFilter(MapData),
+    /// A [`CgroupStorage`] map.
+    CgroupStorage(MapData),
+    /// A [`CgrpStorage`] map.
+    CgrpStorage(MapData),
     /// A [`CpuMap`] map.
     CpuMap(MapData),
     /// A [`DevMap`] map.
@@ -281,12 +316,18 @@ pub enum Map {
     DevMapHash(MapData),
     /// A [`HashMap`] map.
     HashMap(MapData),
+    /// A [`HashOfMaps`] map.
+    HashOfMaps(MapData),
+    /// An [`InodeStorage`] map.
+    InodeStorage(MapData),
     /// A [`LpmTrie`] map.
     LpmTrie(MapData),
     /// A [`HashMap`] map that uses a LRU eviction policy.
     LruHashMap(MapData),
     /// A [`PerCpuArray`] map.
     PerCpuArray(MapData),
+    /// A [`PerCpuCgroupStorage`] map.
+    PerCpuCgroupStorage(MapData),
     /// A [`PerCpuHashMap`] map.
     PerCpuHashMap(MapData),
     /// A [`PerCpuHashMap`] map that uses a LRU eviction policy.
@@ -297,8 +338,12 @@ pub enum Map {
     ProgramArray(MapData),
     /// A [`Queue`] map.
     Queue(MapData),
+    /// A [`ReusePortSockArray`] map.
+    ReusePortSockArray(MapData),
     /// A [`RingBuf`] map.
     RingBuf(MapData),
+    /// A [`SkStorage`] map.
+    SkStorage(MapData),
     /// A [`SockHash`] map
     SockHash(MapData),
     /// A [`SockMap`] map.
@@ -307,8 +352,14 @@ pub enum Map {
     Stack(MapData),
     /// A [`StackTraceMap`] map.
     StackTraceMap(MapData),
+    /// A [`StructOpsMap`] map.
+    StructOpsMap(MapData),
+    /// A [`TaskStorage`] map.
+    TaskStorage(MapData),
     /// An unsupported map type.
     Unsupported(MapData),
+    /// A [`UserRingBuf`] map.
+    UserRingBuf(MapData),
     /// A [`XskMap`] map.
     XskMap(MapData),
 }
@@ -317,26 +368,38 @@ impl Map {
     /// Returns the low level map type.
     fn map_type(&self) -> u32 {
         match self {
+            Self::Arena(map) => map.obj.map_type(),
             Self::Array(map) => map.obj.map_type(),
+            Self::ArrayOfMaps(map) => map.obj.map_type(),
             Self::BloomFilter(map) => map.obj.map_type(),
+            Self::CgroupStorage(map) => map.obj.map_type(),
+            Self::CgrpStorage(map) => map.obj.map_type(),
             Self::CpuMap(map) => map.obj.map_type(),
             Self::DevMap(map) => map.obj.map_type(),
             Self::DevMapHash(map) => map.obj.map_type(),
             Self::HashMap(map) => map.obj.map_type(),
+            Self::HashOfMaps(map) => map.obj.map_type(),
+            Self::InodeStorage(map) => map.obj.map_type(),
             Self::LpmTrie(map) => map.obj.map_type(),
             Self::LruHashMap(map) => map.obj.map_type(),
             Self::PerCpuArray(map) => map.obj.map_type(),
+            Self::PerCpuCgroupStorage(map) => map.obj.map_type(),
             Self::PerCpuHashMap(map) => map.obj.map_type(),
             Self::PerCpuLruHashMap(map) => map.obj.map_type(),
             Self::PerfEventArray(map) => map.obj.map_type(),
             Self::ProgramArray(map) => map.obj.map_type(),
             Self::Queue(map) => map.obj.map_type(),
+            Self::ReusePortSockArray(map) => map.obj.map_type(),
             Self::RingBuf(map) => map.obj.map_type(),
+            Self::SkStorage(map) => map.obj.map_type(),
             Self::SockHash(map) => map.obj.map_type(),
             Self::SockMap(map) => map.obj.map_type(),
             Self::Stack(map) => map.obj.map_type(),
             Self::StackTraceMap(map) => map.obj.map_type(),
+            Self::StructOpsMap(map) => map.obj.map_type(),
+            Self::TaskStorage(map) => map.obj.map_type(),
             Self::Unsupported(map) => map.obj.map_type(),
+            Self::UserRingBuf(map) => map.obj.map_type(),
             Self::XskMap(map) => map.obj.map_type(),
         }
     }
@@ -347,26 +410,38 @@ impl Map {
     /// is deleted. All parent directories in the given `path` must already exist.
     pub fn pin<P: AsRef<Path>>(&self, path: P) -> Result<(), PinError> {
         match self {
+            Self::Arena(map) => map.pin(path),
             Self::Array(map) => map.pin(path),
+            Self::ArrayOfMaps(map) => map.pin(path),
             Self::BloomFilter(map) => map.pin(path),
+            Self::CgroupStorage(map) => map.pin(path),
+            Self::CgrpStorage(map) => map.pin(path),
             Self::CpuMap(map) => map.pin(path),
             Self::DevMap(map) => map.pin(path),
             Self::DevMapHash(map) => map.pin(path),
             Self::HashMap(map) => map.pin(path),
+            Self::HashOfMaps(map) => map.pin(path),
+            Self::InodeStorage(map) => map.pin(path),
             Self::LpmTrie(map) => map.pin(path),
             Self::LruHashMap(map) => map.pin(path),
             Self::PerCpuArray(map) => map.pin(path),
+            Self::PerCpuCgroupStorage(map) => map.pin(path),
             Self::PerCpuHashMap(map) => map.pin(path),
             Self::PerCpuLruHashMap(map) => map.pin(path),
             Self::PerfEventArray(map) => map.pin(path),
             Self::ProgramArray(map) => map.pin(path),
             Self::Queue(map) => map.pin(path),
+            Self::ReusePortSockArray(map) => map.pin(path),
             Self::RingBuf(map) => map.pin(path),
+            Self::SkStorage(map) => map.pin(path),
             Self::SockHash(map) => map.pin(path),
             Self::SockMap(map) => map.pin(path),
             Self::Stack(map) => map.pin(path),
             Self::StackTraceMap(map) => map.pin(path),
+            Self::StructOpsMap(map) => map.pin(path),
+            Self::TaskStorage(map) => map.pin(path),
             Self::Unsupported(map) => map.pin(path),
+            Self::UserRingBuf(map) => map.pin(path),
             Self::XskMap(map) => map.pin(path),
         }
     }
@@ -399,9 +474,12 @@ macro_rules! impl_map_pin {
 }
 
 impl_map_pin!(() {
+    ArrayOfMaps,
     ProgramArray,
+    ReusePortSockArray,
     SockMap,
     StackTraceMap,
+    StructOpsMap,
     CpuMap,
     DevMap,
     DevMapHash,
@@ -410,8 +488,15 @@ impl_map_pin!(() {
 
 impl_map_pin!((V) {
     Array,
+    CgroupStorage,
+    CgrpStorage,
+    InodeStorage,
+    SkStorage,
+    TaskStorage,
     PerCpuArray,
+    PerCpuCgroupStorage,
     SockHash,
+    HashOfMaps,
     BloomFilter,
     Queue,
     Stack,
@@ -475,24 +560,36 @@ macro_rules! impl_try_from_map {
 }
 
 impl_try_from_map!(() {
+    Arena,
+    ArrayOfMaps,
     CpuMap,
     DevMap,
     DevMapHash,
     PerfEventArray,
     ProgramArray,
+    ReusePortSockArray,
     RingBuf,
     SockMap,
     StackTraceMap,
+    StructOpsMap,
+    UserRingBuf,
     XskMap,
 });
 
 impl_try_from_map!((V) {
     Array,
     BloomFilter,
+    CgroupStorage,
+    CgrpStorage,
+    HashOfMaps,
+    InodeStorage,
     PerCpuArray,
+    PerCpuCgroupStorage,
     Queue,
+    SkStorage,
     SockHash,
     Stack,
+    TaskStorage,
 });
 
 impl_try_from_map!((K, V) {
@@ -570,7 +667,31 @@ impl MapData {
             }
         };
 
-        let fd = bpf_create_map(&c_name, &obj, btf_fd).map_err(|io_error| {
+        // The kernel needs the BTF of the key and value of local storage maps.
+        // Legacy definitions get placeholder BTF when the map is created, but
+        // BTF definitions refer to the BTF of the object.
+        if matches!(obj, aya_obj::Map::Btf(_))
+            && btf_fd.is_none()
+            && matches!(
+                obj.map_type().try_into(),
+                Ok(bpf_map_type::BPF_MAP_TYPE_SK_STORAGE
+                    | bpf_map_type::BPF_MAP_TYPE_INODE_STORAGE
+                    | bpf_map_type::BPF_MAP_TYPE_TASK_STORAGE
+                    | bpf_map_type::BPF_MAP_TYPE_CGRP_STORAGE)
+            )
+        {
+            return Err(MapError::MissingBtf { name: name.into() });
+        }
+
+        // Map-in-maps are created from a template of their inner maps. The template is only
+        // needed to create the outer map, so it is dropped right after.
+        let inner = obj
+            .inner()
+            .map(|inner| Self::create(inner, &format!("{name}.inner"), btf_fd))
+            .transpose()?;
+        let inner_map_fd = inner.as_ref().map(|inner| inner.fd().as_fd());
+
+        let fd = bpf_create_map(&c_name, &obj, btf_fd, inner_map_fd).map_err(|io_error| {
             if !KernelVersion::at_least(5, 11, 0) {
                 maybe_warn_rlimit();
             }
@@ -626,7 +747,9 @@ impl MapData {
 
     pub(crate) fn finalize(&mut self) -> Result<(), MapError> {
         let Self { obj, fd } = self;
-        if !obj.data().is_empty() {
+        // The value of struct_ops maps references programs, it's written when
+        // the map is attached.
+        if !obj.data().is_empty() && obj.section_kind() != EbpfSectionKind::StructOps {
             bpf_map_update_elem_ptr(fd.as_fd(), &0 as *const _, obj.data_mut().as_mut_ptr(), 0)
                 .map_err(|io_error| SyscallError {
                     call: "bpf_map_update_elem",
@@ -962,38 +1085,38 @@ mod test_utils {
     }
 
     pub(super) fn new_obj_map<K>(map_type: bpf_map_type) -> aya_obj::Map {
-        aya_obj::Map::Legacy(LegacyMap {
-            def: bpf_map_def {
+        aya_obj::Map::Legacy(LegacyMap::new(
+            bpf_map_def {
                 map_type: map_type as u32,
                 key_size: std::mem::size_of::<K>() as u32,
                 value_size: 4,
                 max_entries: 1024,
                 ..Default::default()
             },
-            section_index: 0,
-            section_kind: EbpfSectionKind::Maps,
-            data: Vec::new(),
-            symbol_index: None,
-        })
+            0,
+            EbpfSectionKind::Maps,
+            None,
+            Vec::new(),
+        ))
     }
 
     pub(super) fn new_obj_map_with_max_entries<K>(
         map_type: bpf_map_type,
         max_entries: u32,
     ) -> aya_obj::Map {
-        aya_obj::Map::Legacy(LegacyMap {
-            def: bpf_map_def {
+        aya_obj::Map::Legacy(LegacyMap::new(
+            bpf_map_def {
                 map_type: map_type as u32,
                 key_size: std::mem::size_of::<K>() as u32,
                 value_size: 4,
                 max_entries,
                 ..Default::default()
             },
-            section_index: 0,
-            section_kind: EbpfSectionKind::Maps,
-            data: Vec::new(),
-            symbol_index: None,
-        })
+            0,
+            EbpfSectionKind::Maps,
+            None,
+            Vec::new(),
+        ))
     }
 }
 
@@ -1066,6 +1189,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_map_of_maps() {
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_CREATE,
+                attr,
+            } => {
+                let u = unsafe { &attr.__bindgen_anon_1 };
+                if u.map_type == bpf_map_type::BPF_MAP_TYPE_ARRAY_OF_MAPS as u32 {
+                    assert_eq!(u.inner_map_fd, crate::MockableFd::mock_unsigned_fd());
+                } else {
+                    assert_eq!(u.map_type, bpf_map_type::BPF_MAP_TYPE_ARRAY as u32);
+                    assert_eq!(u.inner_map_fd, 0);
+                }
+                Ok(crate::MockableFd::mock_signed_fd().into())
+            }
+            _ => Err((-1, io::Error::from_raw_os_error(EFAULT))),
+        });
+
+        let mut obj = test_utils::new_obj_map::<u32>(bpf_map_type::BPF_MAP_TYPE_ARRAY_OF_MAPS);
+        let aya_obj::Map::Legacy(map) = &mut obj else {
+            unreachable!()
+        };
+        map.set_inner_def(Some(aya_obj::maps::bpf_map_def {
+            map_type: bpf_map_type::BPF_MAP_TYPE_ARRAY as u32,
+            key_size: 4,
+            value_size: 4,
+            max_entries: 1,
+            ..Default::default()
+        }));
+
+        assert_matches!(MapData::create(obj, "foo", None), Ok(_));
+    }
+
+    #[test]
+    fn test_create_local_storage() {
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_BTF_LOAD,
+                ..
+            } => Ok(crate::MockableFd::mock_signed_fd().into()),
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_MAP_CREATE,
+                attr,
+            } => {
+                let u = unsafe { &attr.__bindgen_anon_1 };
+                assert_eq!(u.btf_fd, crate::MockableFd::mock_unsigned_fd());
+                assert_ne!(u.btf_key_type_id, 0);
+                assert_ne!(u.btf_value_type_id, 0);
+                Ok(crate::MockableFd::mock_signed_fd().into())
+            }
+            _ => Err((-1, io::Error::from_raw_os_error(EFAULT))),
+        });
+
+        assert_matches!(
+            MapData::create(
+                test_utils::new_obj_map::<i32>(bpf_map_type::BPF_MAP_TYPE_SK_STORAGE),
+                "foo",
+                None
+            ),
+            Ok(_)
+        );
+    }
+
     #[test]
     fn test_create_perf_event_array() {
         override_syscall(|call| match call {
@@ -0,0 +1,6 @@
+//! Map-in-map types.
+mod array_of_maps;
+mod hash_of_maps;
+
+pub use array_of_maps::ArrayOfMaps;
+pub use hash_of_maps::HashOfMaps;
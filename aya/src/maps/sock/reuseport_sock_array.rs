@@ -0,0 +1,95 @@
+//! An array of sockets used to select the receiving socket of a reuseport group.
+
+use std::{
+    borrow::{Borrow, BorrowMut},
+    os::fd::{AsFd as _, AsRawFd, RawFd},
+};
+
+use crate::{
+    maps::{MapData, MapError, MapKeys, check_bounds, check_kv_size},
+    sys::{SyscallError, bpf_map_delete_elem, bpf_map_update_elem},
+};
+
+/// An array of sockets belonging to `SO_REUSEPORT` groups.
+///
+/// A `ReusePortSockArray` is used by [`SkReuseport`](crate::programs::SkReuseport)
+/// programs to select which socket of a reuseport group receives a packet or a
+/// connection. The sockets stored in the map must have `SO_REUSEPORT` set and be
+/// bound.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.19.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     IO(#[from] std::io::Error),
+/// #     #[error(transparent)]
+/// #     Map(#[from] aya::maps::MapError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// # fn reuseport_socket(port: u16) -> std::io::Result<std::net::UdpSocket> {
+/// #     unimplemented!()
+/// # }
+/// use aya::maps::ReusePortSockArray;
+///
+/// let mut sockets = ReusePortSockArray::try_from(bpf.map_mut("SOCKETS").unwrap())?;
+/// // `reuseport_socket` binds a socket with `SO_REUSEPORT` set, as shown in the
+/// // example of `SkReuseport`.
+/// let socket = reuseport_socket(1234)?;
+/// sockets.set(0, &socket, 0)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_REUSEPORT_SOCKARRAY")]
+pub struct ReusePortSockArray<T> {
+    pub(crate) inner: T,
+}
+
+impl<T: Borrow<MapData>> ReusePortSockArray<T> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data = map.borrow();
+        check_kv_size::<u32, RawFd>(data)?;
+
+        Ok(Self { inner: map })
+    }
+
+    /// An iterator over the indices of the array that point to a socket. The iterator item type
+    /// is `Result<u32, MapError>`.
+    pub fn indices(&self) -> MapKeys<'_, u32> {
+        MapKeys::new(self.inner.borrow())
+    }
+}
+
+impl<T: BorrowMut<MapData>> ReusePortSockArray<T> {
+    /// Stores a socket into the map.
+    pub fn set<I: AsRawFd>(&mut self, index: u32, socket: &I, flags: u64) -> Result<(), MapError> {
+        let data = self.inner.borrow_mut();
+        let fd = data.fd().as_fd();
+        check_bounds(data, index)?;
+        bpf_map_update_elem(fd, Some(&index), &socket.as_raw_fd(), flags)
+            .map_err(|io_error| SyscallError {
+                call: "bpf_map_update_elem",
+                io_error,
+            })
+            .map_err(Into::into)
+    }
+
+    /// Removes the socket stored at `index` from the map.
+    pub fn clear_index(&mut self, index: &u32) -> Result<(), MapError> {
+        let data = self.inner.borrow_mut();
+        let fd = data.fd().as_fd();
+        check_bounds(data, *index)?;
+        bpf_map_delete_elem(fd, index)
+            .map_err(|io_error| SyscallError {
+                call: "bpf_map_delete_elem",
+                io_error,
+            })
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,201 @@
+//! A map implementing the operations of a kernel struct.
+use std::{
+    borrow::Borrow,
+    os::fd::{AsFd as _, AsRawFd as _},
+};
+
+use aya_obj::generated::{BPF_F_LINK, bpf_attach_type::BPF_STRUCT_OPS};
+
+use crate::{
+    Ebpf,
+    maps::{MapData, MapError, MapFd},
+    programs::links::{FdLink, LinkError},
+    sys::{
+        LinkTarget, SyscallError, bpf_link_create, bpf_map_delete_elem, bpf_map_update_elem_ptr,
+    },
+};
+
+/// A map implementing the operations of a kernel struct.
+///
+/// A struct_ops map is declared in eBPF code as a static instance of a kernel
+/// struct, such as `tcp_congestion_ops`, placed in the `.struct_ops.link` or
+/// `.struct_ops` section. The function pointer members of the struct are
+/// implemented by [`StructOps`](crate::programs::StructOps) programs.
+///
+/// Once all the programs referenced by the map are loaded, calling
+/// [`StructOpsMap::attach`] registers the struct with the kernel. The struct
+/// is unregistered when the returned [`StructOpsLink`] is dropped.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.6, or 6.4 for
+/// maps in the `.struct_ops.link` section.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(thiserror::Error, Debug)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Map(#[from] aya::maps::MapError),
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError),
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use aya::{maps::StructOpsMap, programs::StructOps};
+///
+/// for (_, program) in bpf.programs_mut() {
+///     let program: &mut StructOps = program.try_into()?;
+///     program.load()?;
+/// }
+/// let ops = StructOpsMap::try_from(bpf.map("TCP_CC").unwrap())?;
+/// let link = ops.attach(&bpf)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_STRUCT_OPS")]
+pub struct StructOpsMap<T> {
+    pub(crate) inner: T,
+}
+
+impl<T: Borrow<MapData>> StructOpsMap<T> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data = map.borrow();
+        if !matches!(data.obj, aya_obj::Map::StructOps(_)) {
+            return Err(MapError::InvalidMapType {
+                map_type: data.obj.map_type(),
+            });
+        }
+
+        Ok(Self { inner: map })
+    }
+
+    /// Returns the name of the kernel struct implemented by the map.
+    pub fn type_name(&self) -> &str {
+        self.obj().type_name()
+    }
+
+    /// Registers the struct with the kernel.
+    ///
+    /// The programs implementing the members of the struct are looked up by
+    /// name in `ebpf` and must have been loaded. Maps in the `.struct_ops.link`
+    /// section are registered by creating a link, the others are registered
+    /// as soon as their value is set. In both cases the struct is unregistered
+    /// when the returned [`StructOpsLink`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MapError::ProgramNotFound`] if a program referenced by the
+    /// map can't be found, [`MapError::ProgramNotLoaded`] if it isn't loaded,
+    /// or [`MapError::SyscallError`] if registering the struct fails.
+    pub fn attach(&self, ebpf: &Ebpf) -> Result<StructOpsLink, MapError> {
+        let data = self.inner.borrow();
+        let obj = self.obj();
+        let mut value = data.obj.data().to_vec();
+        for program in obj.programs() {
+            let fd = ebpf
+                .program(&program.program_name)
+                .ok_or_else(|| MapError::ProgramNotFound {
+                    name: program.program_name.clone(),
+                })?
+                .fd()
+                .map_err(|_| MapError::ProgramNotLoaded)?;
+            let offset = program.offset as usize;
+            let fd = u64::from(fd.as_fd().as_raw_fd() as u32);
+            value[offset..offset + size_of::<u64>()].copy_from_slice(&fd.to_ne_bytes());
+        }
+
+        let fd = data.fd().as_fd();
+        bpf_map_update_elem_ptr(fd, &0u32, value.as_mut_ptr(), 0).map_err(|io_error| {
+            SyscallError {
+                call: "bpf_map_update_elem",
+                io_error,
+            }
+        })?;
+        if data.obj.map_flags() & BPF_F_LINK == 0 {
+            // The struct was registered by setting the value, it's unregistered by deleting it.
+            let map_fd = data.fd().try_clone().map_err(|io_error| SyscallError {
+                call: "dup",
+                io_error,
+            })?;
+            return Ok(StructOpsLink(Some(StructOpsLinkInner::Map(map_fd))));
+        }
+        // The kernel reads the map fd from the program fd field of the link
+        // create attributes.
+        let link_fd =
+            bpf_link_create(fd, LinkTarget::None, BPF_STRUCT_OPS, 0, None).map_err(|io_error| {
+                SyscallError {
+                    call: "bpf_link_create",
+                    io_error,
+                }
+            })?;
+        Ok(StructOpsLink(Some(StructOpsLinkInner::Fd(FdLink::new(
+            link_fd,
+        )))))
+    }
+
+    fn obj(&self) -> &aya_obj::maps::StructOpsMap {
+        match &self.inner.borrow().obj {
+            aya_obj::Map::StructOps(map) => map,
+            _ => unreachable!("checked in new"),
+        }
+    }
+}
+
+/// The registration of a [`StructOpsMap`] with the kernel.
+///
+/// The struct is unregistered when the link is dropped. Links of maps in the
+/// `.struct_ops.link` section can be converted to an [`FdLink`] with
+/// [`TryFrom`], for example to pin them.
+#[derive(Debug)]
+pub struct StructOpsLink(Option<StructOpsLinkInner>);
+
+#[derive(Debug)]
+enum StructOpsLinkInner {
+    Fd(FdLink),
+    Map(MapFd),
+}
+
+impl StructOpsLink {
+    /// Unregisters the struct.
+    pub fn detach(mut self) -> Result<(), MapError> {
+        detach(self.0.take().unwrap())
+    }
+}
+
+fn detach(inner: StructOpsLinkInner) -> Result<(), MapError> {
+    match inner {
+        // Closing the link unregisters the struct.
+        StructOpsLinkInner::Fd(link) => drop(link),
+        StructOpsLinkInner::Map(fd) => {
+            bpf_map_delete_elem(fd.as_fd(), &0u32).map_err(|io_error| SyscallError {
+                call: "bpf_map_delete_elem",
+                io_error,
+            })?
+        }
+    }
+    Ok(())
+}
+
+impl Drop for StructOpsLink {
+    fn drop(&mut self) {
+        if let Some(inner) = self.0.take() {
+            let _: Result<(), MapError> = detach(inner);
+        }
+    }
+}
+
+impl TryFrom<StructOpsLink> for FdLink {
+    type Error = LinkError;
+
+    fn try_from(mut value: StructOpsLink) -> Result<Self, Self::Error> {
+        match value.0.take().unwrap() {
+            StructOpsLinkInner::Fd(link) => Ok(link),
+            inner @ StructOpsLinkInner::Map(_) => {
+                value.0 = Some(inner);
+                Err(LinkError::InvalidLink)
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! A [user ring buffer map][ringbuf] that may be used to send samples from userspace to eBPF
+//! programs.
+//!
+//! [ringbuf]: https://www.kernel.org/doc/html/latest/bpf/ringbuf.html
+
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Formatter},
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+    slice,
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+};
+
+use aya_obj::generated::{BPF_RINGBUF_BUSY_BIT, BPF_RINGBUF_DISCARD_BIT, BPF_RINGBUF_HDR_SZ};
+use libc::{MAP_SHARED, PROT_READ, PROT_WRITE};
+
+use crate::{
+    maps::{MapData, MapError},
+    util::{MMap, page_size},
+};
+
+/// A map that can be used to send samples from userspace to eBPF programs.
+///
+/// This is the counterpart of [`crate::maps::RingBuf`]: userspace is the producer and eBPF
+/// programs consume samples by calling `bpf_user_ringbuf_drain`. Samples are written directly into
+/// the memory shared with the kernel, so no system call is needed to publish them.
+///
+/// To send samples you need to:
+/// * Construct [`UserRingBuf`] using [`UserRingBuf::try_from`].
+/// * Call [`UserRingBuf::reserve`] to reserve space for a sample.
+/// * Fill the returned [`UserRingBufEntry`], then call [`UserRingBufEntry::submit`] to make it
+///   visible to eBPF programs, or [`UserRingBufEntry::discard`] to drop it.
+///
+/// The kernel reports the file descriptor as writable when space has been freed by the consumer,
+/// so a producer that finds the ring buffer full may wait for it through `epoll` or other APIs.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 6.1.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use aya::maps::UserRingBuf;
+///
+/// let mut ring_buf = UserRingBuf::try_from(bpf.map_mut("COMMANDS").unwrap())?;
+/// if let Some(mut entry) = ring_buf.reserve(8) {
+///     entry.copy_from_slice(&42u64.to_ne_bytes());
+///     entry.submit();
+/// }
+/// # Ok::<(), aya::EbpfError>(())
+/// ```
+#[doc(alias = "BPF_MAP_TYPE_USER_RINGBUF")]
+pub struct UserRingBuf<T> {
+    map: T,
+    consumer: MMap,
+    producer: ProducerData,
+}
+
+impl<T: Borrow<MapData>> UserRingBuf<T> {
+    pub(crate) fn new(map: T) -> Result<Self, MapError> {
+        let data: &MapData = map.borrow();
+        let page_size = page_size();
+        let map_fd = data.fd().as_fd();
+        let byte_size = data.obj.max_entries();
+        // The consumer page is owned by the kernel and may only be mapped read-only.
+        let consumer = MMap::new(map_fd, page_size, PROT_READ, MAP_SHARED, 0)?;
+        let producer = ProducerData::new(map_fd, page_size, page_size, byte_size)?;
+        Ok(Self {
+            map,
+            consumer,
+            producer,
+        })
+    }
+}
+
+impl<T> UserRingBuf<T> {
+    /// Reserves `size` bytes in the ring buffer.
+    ///
+    /// Returns `None` if the ring buffer doesn't have enough free space, in which case the caller
+    /// may wait for the consumer to catch up by polling the map's file descriptor for writability.
+    /// Samples that could never fit in the ring buffer are never reserved. Only one
+    /// [`UserRingBufEntry`] may be outstanding at a time.
+    pub fn reserve(&mut self, size: usize) -> Option<UserRingBufEntry<'_>> {
+        let Self {
+            consumer, producer, ..
+        } = self;
+        // Pair the kernel's release store of the consumer position with an Acquire load. This
+        // ensures the kernel is done reading the samples we are about to overwrite.
+        let consumer_pos =
+            unsafe { consumer.ptr().cast::<AtomicUsize>().as_ref() }.load(Ordering::Acquire);
+        producer.reserve(consumer_pos, size)
+    }
+}
+
+impl<T: Borrow<MapData>> AsFd for UserRingBuf<T> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        let Self {
+            map,
+            consumer: _,
+            producer: _,
+        } = self;
+        map.borrow().fd().as_fd()
+    }
+}
+
+impl<T: Borrow<MapData>> AsRawFd for UserRingBuf<T> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+/// A sample reserved in a [`UserRingBuf`].
+///
+/// The sample is made visible to eBPF programs by [`submit`]. Entries which are dropped without
+/// being submitted are discarded.
+///
+/// [`submit`]: UserRingBufEntry::submit
+pub struct UserRingBufEntry<'a> {
+    header: &'a AtomicU32,
+    data: &'a mut [u8],
+}
+
+impl UserRingBufEntry<'_> {
+    /// Commits this entry, making it visible to eBPF programs.
+    pub fn submit(self) {
+        let entry = ManuallyDrop::new(self);
+        entry.commit(0)
+    }
+
+    /// Discards this entry. The entry will be skipped by eBPF programs.
+    pub fn discard(self) {
+        drop(self)
+    }
+
+    fn commit(&self, flags: u32) {
+        let Self { header, data } = self;
+        // The length was checked to fit the header when the entry was reserved.
+        let len = u32::try_from(data.len()).unwrap();
+        // Clearing the busy bit publishes the sample; the kernel reads the header with acquire
+        // semantics.
+        header.store(len | flags, Ordering::Release);
+    }
+}
+
+impl Deref for UserRingBufEntry<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        let Self { data, .. } = self;
+        data
+    }
+}
+
+impl DerefMut for UserRingBufEntry<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let Self { data, .. } = self;
+        data
+    }
+}
+
+impl Drop for UserRingBufEntry<'_> {
+    fn drop(&mut self) {
+        self.commit(BPF_RINGBUF_DISCARD_BIT)
+    }
+}
+
+impl Debug for UserRingBufEntry<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { header: _, data } = self;
+        f.debug_struct("UserRingBufEntry")
+            .field("len", &data.len())
+            .finish()
+    }
+}
+
+struct ProducerData {
+    mmap: MMap,
+
+    // Offset in the mmap where the data starts.
+    data_offset: usize,
+
+    // A bitmask which truncates u32 values to the domain of valid offsets in the ringbuf.
+    mask: u32,
+}
+
+impl ProducerData {
+    fn new(
+        fd: BorrowedFd<'_>,
+        offset: usize,
+        page_size: usize,
+        byte_size: u32,
+    ) -> Result<Self, MapError> {
+        // Unlike a kernel-produced ring buffer, the producer page and the data pages are writable
+        // by userspace. As with `RingBuf`, the data pages are mapped twice consecutively so that
+        // samples wrapping around the end of the ring buffer are contiguous.
+        let len = page_size + 2 * usize::try_from(byte_size).unwrap();
+        let mmap = MMap::new(
+            fd,
+            len,
+            PROT_READ | PROT_WRITE,
+            MAP_SHARED,
+            offset.try_into().unwrap(),
+        )?;
+
+        // byte_size is required to be a power of two multiple of page_size (which implicitly is a
+        // power of 2), so subtracting one will create a bitmask for values less than byte_size.
+        debug_assert!(byte_size.is_power_of_two());
+        let mask = byte_size - 1;
+        Ok(Self {
+            mmap,
+            data_offset: page_size,
+            mask,
+        })
+    }
+
+    fn reserve(&mut self, consumer_pos: usize, size: usize) -> Option<UserRingBufEntry<'_>> {
+        let Self {
+            mmap,
+            data_offset,
+            mask,
+        } = self;
+        let header_size = usize::try_from(BPF_RINGBUF_HDR_SZ).unwrap();
+        let capacity = usize::try_from(*mask).unwrap() + 1;
+        let total_size = (header_size + size).next_multiple_of(8);
+
+        // This position is only ever written by us, so there is nothing to synchronize with.
+        let pos = unsafe { mmap.ptr().cast::<AtomicUsize>().as_ref() };
+        let producer_pos = pos.load(Ordering::Relaxed);
+        let used = producer_pos.wrapping_sub(consumer_pos);
+        if total_size > capacity - used {
+            return None;
+        }
+        let len = u32::try_from(size).ok()?;
+
+        let offset = *data_offset + (producer_pos & usize::try_from(*mask).unwrap());
+        let base = mmap.ptr().as_ptr().cast::<u8>();
+        // SAFETY: the data pages are mapped twice, so the header and the sample are in bounds
+        // even when they wrap around the end of the ring buffer.
+        let header = unsafe { &*base.add(offset).cast::<AtomicU32>() };
+        let pad = unsafe { &*base.add(offset + 4).cast::<AtomicU32>() };
+        header.store(len | BPF_RINGBUF_BUSY_BIT, Ordering::Relaxed);
+        pad.store(0, Ordering::Relaxed);
+
+        // The kernel reads the producer position with acquire semantics; publishing it with
+        // Release ensures the busy header written above is visible first.
+        pos.store(producer_pos + total_size, Ordering::Release);
+
+        // SAFETY: the reserved region is exclusively ours until it is committed.
+        let data = unsafe { slice::from_raw_parts_mut(base.add(offset + header_size), size) };
+        Some(UserRingBufEntry { header, data })
+    }
+}
@@ -9,7 +9,8 @@ use aya_obj::generated::{
 use crate::{
     programs::{
         CgroupAttachMode, FdLink, Link, ProgAttachLink, ProgramData, ProgramError, ProgramFd,
-        ProgramType, bpf_prog_get_fd_by_id, define_link_wrapper, id_as_key, load_program, query,
+        ProgramType, bpf_prog_get_fd_by_id, define_link_wrapper, id_as_key, impl_update_program,
+        load_program, query,
     },
     sys::{LinkTarget, ProgQueryTarget, SyscallError, bpf_link_create},
     util::KernelVersion,
@@ -168,3 +169,5 @@ define_link_wrapper!(
     CgroupDeviceLinkIdInner,
     CgroupDevice,
 );
+
+impl_update_program!(CgroupDeviceLink, CgroupDeviceLinkInner);
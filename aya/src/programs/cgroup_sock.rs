@@ -9,7 +9,7 @@ use crate::{
     VerifierLogLevel,
     programs::{
         CgroupAttachMode, FdLink, Link, ProgAttachLink, ProgramData, ProgramError, ProgramType,
-        define_link_wrapper, id_as_key, impl_try_into_fdlink, load_program,
+        define_link_wrapper, id_as_key, impl_try_into_fdlink, impl_update_program, load_program,
     },
     sys::{LinkTarget, SyscallError, bpf_link_create},
     util::KernelVersion,
@@ -161,3 +161,4 @@ define_link_wrapper!(
 );
 
 impl_try_into_fdlink!(CgroupSockLink, CgroupSockLinkInner);
+impl_update_program!(CgroupSockLink, CgroupSockLinkInner);
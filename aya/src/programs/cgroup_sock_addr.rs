@@ -9,7 +9,7 @@ use crate::{
     VerifierLogLevel,
     programs::{
         CgroupAttachMode, FdLink, Link, ProgAttachLink, ProgramData, ProgramError, ProgramType,
-        define_link_wrapper, id_as_key, impl_try_into_fdlink, load_program,
+        define_link_wrapper, id_as_key, impl_try_into_fdlink, impl_update_program, load_program,
     },
     sys::{LinkTarget, SyscallError, bpf_link_create},
     util::KernelVersion,
@@ -162,3 +162,4 @@ define_link_wrapper!(
 );
 
 impl_try_into_fdlink!(CgroupSockAddrLink, CgroupSockAddrLinkInner);
+impl_update_program!(CgroupSockAddrLink, CgroupSockAddrLinkInner);
@@ -9,7 +9,7 @@ use crate::{
     VerifierLogLevel,
     programs::{
         CgroupAttachMode, FdLink, Link, ProgAttachLink, ProgramData, ProgramError, ProgramType,
-        define_link_wrapper, id_as_key, load_program,
+        define_link_wrapper, id_as_key, impl_update_program, load_program,
     },
     sys::{LinkTarget, SyscallError, bpf_link_create},
     util::KernelVersion,
@@ -159,3 +159,5 @@ define_link_wrapper!(
     CgroupSockoptLinkIdInner,
     CgroupSockopt,
 );
+
+impl_update_program!(CgroupSockoptLink, CgroupSockoptLinkInner);
@@ -1,21 +1,18 @@
 //! Extension programs.
 
-use std::os::fd::{AsFd as _, BorrowedFd};
+use std::os::fd::AsFd as _;
 
-use aya_obj::{
-    btf::BtfKind,
-    generated::{bpf_attach_type::BPF_CGROUP_INET_INGRESS, bpf_prog_type::BPF_PROG_TYPE_EXT},
+use aya_obj::generated::{
+    bpf_attach_type::BPF_CGROUP_INET_INGRESS, bpf_prog_type::BPF_PROG_TYPE_EXT,
 };
-use object::Endianness;
 use thiserror::Error;
 
 use crate::{
-    Btf,
     programs::{
         FdLink, FdLinkId, ProgramData, ProgramError, ProgramFd, ProgramType, define_link_wrapper,
-        load_program,
+        load_program, utils::get_prog_btf_info,
     },
-    sys::{self, BpfLinkCreateArgs, LinkTarget, SyscallError, bpf_link_create},
+    sys::{BpfLinkCreateArgs, LinkTarget, SyscallError, bpf_link_create},
 };
 
 /// The type returned when loading or attaching an [`Extension`] fails.
@@ -77,7 +74,8 @@ impl Extension {
     /// There are no restrictions on what functions may be replaced, so you could replace
     /// the main entry point of your program with an extension.
     pub fn load(&mut self, program: ProgramFd, func_name: &str) -> Result<(), ProgramError> {
-        let (btf_fd, btf_id) = get_btf_info(program.as_fd(), func_name)?;
+        let (btf_fd, btf_id) =
+            get_prog_btf_info(program.as_fd(), func_name)?.ok_or(ExtensionError::NoBTF)?;
 
         self.data.attach_btf_obj_fd = Some(btf_fd);
         self.data.attach_prog_fd = Some(program);
@@ -136,7 +134,7 @@ impl Extension {
         func_name: &str,
     ) -> Result<ExtensionLinkId, ProgramError> {
         let target_fd = program.as_fd();
-        let (_, btf_id) = get_btf_info(target_fd, func_name)?;
+        let (_, btf_id) = get_prog_btf_info(target_fd, func_name)?.ok_or(ExtensionError::NoBTF)?;
         let prog_fd = self.fd()?;
         let prog_fd = prog_fd.as_fd();
         // the attach type must be set as 0, which is bpf_attach_type::BPF_CGROUP_INET_INGRESS
@@ -157,44 +155,4 @@ impl Extension {
     }
 }
 
-/// Retrieves the FD of the BTF object for the provided `prog_fd` and the BTF ID of the function
-/// with the name `func_name` within that BTF object.
-fn get_btf_info(
-    prog_fd: BorrowedFd<'_>,
-    func_name: &str,
-) -> Result<(crate::MockableFd, u32), ProgramError> {
-    // retrieve program information
-    let info = sys::bpf_prog_get_info_by_fd(prog_fd, &mut [])?;
-
-    // btf_id refers to the ID of the program btf that was loaded with bpf(BPF_BTF_LOAD)
-    if info.btf_id == 0 {
-        return Err(ProgramError::ExtensionError(ExtensionError::NoBTF));
-    }
-
-    // the bpf fd of the BTF object
-    let btf_fd = sys::bpf_btf_get_fd_by_id(info.btf_id)?;
-
-    // we need to read the btf bytes into a buffer but we don't know the size ahead of time.
-    // assume 4kb. if this is too small we can resize based on the size obtained in the response.
-    let mut buf = vec![0u8; 4096];
-    loop {
-        let info = sys::btf_obj_get_info_by_fd(btf_fd.as_fd(), &mut buf)?;
-        let btf_size = info.btf_size as usize;
-        if btf_size > buf.len() {
-            buf.resize(btf_size, 0u8);
-            continue;
-        }
-        buf.truncate(btf_size);
-        break;
-    }
-
-    let btf = Btf::parse(&buf, Endianness::default()).map_err(ProgramError::Btf)?;
-
-    let btf_id = btf
-        .id_by_type_name_kind(func_name, BtfKind::Func)
-        .map_err(ProgramError::Btf)?;
-
-    Ok((btf_fd, btf_id))
-}
-
 define_link_wrapper!(ExtensionLink, ExtensionLinkId, FdLink, FdLinkId, Extension);
@@ -1,13 +1,16 @@
 //! Fexit programs.
 
+use std::os::fd::AsFd as _;
+
 use aya_obj::{
     btf::{Btf, BtfKind},
     generated::{bpf_attach_type::BPF_TRACE_FEXIT, bpf_prog_type::BPF_PROG_TYPE_TRACING},
 };
 
 use crate::programs::{
-    FdLink, FdLinkId, ProgramData, ProgramError, ProgramType, define_link_wrapper, load_program,
-    utils::attach_raw_tracepoint,
+    FdLink, FdLinkId, ProgramData, ProgramError, ProgramFd, ProgramType, define_link_wrapper,
+    load_program,
+    utils::{attach_raw_tracepoint, get_prog_btf_info, set_attach_btf_id},
 };
 
 /// A program that can be attached to the exit point of (almost) anny kernel
@@ -58,10 +61,33 @@ impl FExit {
     ///
     /// Loads the program so it's executed when the kernel function `fn_name`
     /// is exited. The `btf` argument must contain the BTF info for the running
-    /// kernel.
+    /// kernel. Functions which aren't part of the kernel itself are looked up
+    /// in the BTF of the loaded kernel modules.
     pub fn load(&mut self, fn_name: &str, btf: &Btf) -> Result<(), ProgramError> {
         self.data.expected_attach_type = Some(BPF_TRACE_FEXIT);
-        self.data.attach_btf_id = Some(btf.id_by_type_name_kind(fn_name, BtfKind::Func)?);
+        set_attach_btf_id(&mut self.data, btf, fn_name, BtfKind::Func)?;
+        load_program(BPF_PROG_TYPE_TRACING, &mut self.data)
+    }
+
+    /// Loads the program inside the kernel, to be attached to another eBPF
+    /// program.
+    ///
+    /// Loads the program so it's executed when the function `func_name` of the
+    /// eBPF program represented by the `program` file descriptor is exited.
+    /// This requires that `program` has had its BTF loaded into the kernel.
+    ///
+    /// The program is attached to `program` with [`Self::attach`].
+    pub fn load_for_program(
+        &mut self,
+        program: ProgramFd,
+        func_name: &str,
+    ) -> Result<(), ProgramError> {
+        let (_, btf_id) = get_prog_btf_info(program.as_fd(), func_name)?
+            .ok_or(ProgramError::TargetProgramNoBtf)?;
+        self.data.expected_attach_type = Some(BPF_TRACE_FEXIT);
+        self.data.attach_btf_obj_fd = None;
+        self.data.attach_prog_fd = Some(program);
+        self.data.attach_btf_id = Some(btf_id);
         load_program(BPF_PROG_TYPE_TRACING, &mut self.data)
     }
 
@@ -9,7 +9,7 @@ use aya_obj::generated::{
 use crate::{
     programs::{
         CgroupAttachMode, FdLink, Link, ProgAttachLink, ProgramData, ProgramError, ProgramType,
-        define_link_wrapper, id_as_key, load_program,
+        define_link_wrapper, id_as_key, impl_update_program, load_program,
     },
     sys::{LinkTarget, SyscallError, bpf_link_create},
     util::KernelVersion,
@@ -155,3 +155,5 @@ define_link_wrapper!(
     FlowDissectorLinkIdInner,
     FlowDissector,
 );
+
+impl_update_program!(FlowDissectorLink, FlowDissectorLinkInner);
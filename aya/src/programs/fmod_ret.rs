@@ -0,0 +1,78 @@
+//! Fmod_ret programs.
+
+use aya_obj::{
+    btf::{Btf, BtfKind},
+    generated::{bpf_attach_type::BPF_MODIFY_RETURN, bpf_prog_type::BPF_PROG_TYPE_TRACING},
+};
+
+use crate::programs::{
+    FdLink, FdLinkId, ProgramData, ProgramError, ProgramType, define_link_wrapper, load_program,
+    utils::{attach_raw_tracepoint, set_attach_btf_id},
+};
+
+/// A program that can override the return value of a kernel function.
+///
+/// [`FModRet`] programs run after [fentry](crate::programs::FEntry) programs
+/// and before the kernel function. When a program returns a non-zero value,
+/// the kernel function is skipped and its caller sees the returned value
+/// instead. This is an alternative to `bpf_override_return` in kprobes, and
+/// is only allowed on functions marked with `ALLOW_ERROR_INJECTION` and on
+/// security hooks.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.7.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(thiserror::Error, Debug)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     BtfError(#[from] aya::BtfError),
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError),
+/// # }
+/// # let mut bpf = Ebpf::load_file("ebpf_programs.o")?;
+/// use aya::{Ebpf, programs::FModRet, BtfError, Btf};
+///
+/// let btf = Btf::from_sys_fs()?;
+/// let program: &mut FModRet = bpf.program_mut("security_file_open").unwrap().try_into()?;
+/// program.load("security_file_open", &btf)?;
+/// program.attach()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_MODIFY_RETURN")]
+#[doc(alias = "BPF_PROG_TYPE_TRACING")]
+pub struct FModRet {
+    pub(crate) data: ProgramData<FModRetLink>,
+}
+
+impl FModRet {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::Tracing;
+
+    /// Loads the program inside the kernel.
+    ///
+    /// Loads the program so it can override the return value of the kernel
+    /// function `fn_name`. The `btf` argument must contain the BTF info for
+    /// the running kernel. Functions which aren't part of the kernel itself
+    /// are looked up in the BTF of the loaded kernel modules.
+    pub fn load(&mut self, fn_name: &str, btf: &Btf) -> Result<(), ProgramError> {
+        self.data.expected_attach_type = Some(BPF_MODIFY_RETURN);
+        set_attach_btf_id(&mut self.data, btf, fn_name, BtfKind::Func)?;
+        load_program(BPF_PROG_TYPE_TRACING, &mut self.data)
+    }
+
+    /// Attaches the program.
+    ///
+    /// The returned value can be used to detach, see [FModRet::detach].
+    pub fn attach(&mut self) -> Result<FModRetLinkId, ProgramError> {
+        attach_raw_tracepoint(&mut self.data, None)
+    }
+}
+
+define_link_wrapper!(FModRetLink, FModRetLinkId, FdLink, FdLinkId, FModRet);
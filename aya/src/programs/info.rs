@@ -355,17 +355,20 @@ pub enum ProgramType {
     /// Introduced in kernel v4.10.
     #[doc(alias = "BPF_PROG_TYPE_CGROUP_SOCK")]
     CgroupSock = bpf_prog_type::BPF_PROG_TYPE_CGROUP_SOCK as isize,
-    /// A Lightweight Tunnel (LWT) Input program type.
+    /// A Lightweight Tunnel (LWT) Input program type. See [`LwtIn`](super::lwt::LwtIn) for the
+    /// program implementation.
     ///
     /// Introduced in kernel v4.10.
     #[doc(alias = "BPF_PROG_TYPE_LWT_IN")]
     LwtInput = bpf_prog_type::BPF_PROG_TYPE_LWT_IN as isize,
-    /// A Lightweight Tunnel (LWT) Output program type.
+    /// A Lightweight Tunnel (LWT) Output program type. See [`LwtOut`](super::lwt::LwtOut) for the
+    /// program implementation.
     ///
     /// Introduced in kernel v4.10.
     #[doc(alias = "BPF_PROG_TYPE_LWT_OUT")]
     LwtOutput = bpf_prog_type::BPF_PROG_TYPE_LWT_OUT as isize,
-    /// A Lightweight Tunnel (LWT) Transmit program type.
+    /// A Lightweight Tunnel (LWT) Transmit program type. See [`LwtXmit`](super::lwt::LwtXmit)
+    /// for the program implementation.
     ///
     /// Introduced in kernel v4.10.
     #[doc(alias = "BPF_PROG_TYPE_LWT_XMIT")]
@@ -406,7 +409,8 @@ pub enum ProgramType {
     /// Introduced in kernel v4.17.
     #[doc(alias = "BPF_PROG_TYPE_CGROUP_SOCK_ADDR")]
     CgroupSockAddr = bpf_prog_type::BPF_PROG_TYPE_CGROUP_SOCK_ADDR as isize,
-    /// A Lightweight Tunnel (LWT) Seg6local program type.
+    /// A Lightweight Tunnel (LWT) Seg6local program type. See
+    /// [`LwtSeg6Local`](super::lwt::LwtSeg6Local) for the program implementation.
     ///
     /// Introduced in kernel v4.18.
     #[doc(alias = "BPF_PROG_TYPE_LWT_SEG6LOCAL")]
@@ -417,7 +421,8 @@ pub enum ProgramType {
     /// Introduced in kernel v4.18.
     #[doc(alias = "BPF_PROG_TYPE_LIRC_MODE2")]
     LircMode2 = bpf_prog_type::BPF_PROG_TYPE_LIRC_MODE2 as isize,
-    /// A Socket Reuseport program type.
+    /// A Socket Reuseport program type. See [`SkReuseport`](super::sk_reuseport::SkReuseport)
+    /// for the program implementation.
     ///
     /// Introduced in kernel v4.19.
     #[doc(alias = "BPF_PROG_TYPE_SK_REUSEPORT")]
@@ -433,7 +438,8 @@ pub enum ProgramType {
     /// Introduced in kernel v5.2.
     #[doc(alias = "BPF_PROG_TYPE_CGROUP_SYSCTL")]
     CgroupSysctl = bpf_prog_type::BPF_PROG_TYPE_CGROUP_SYSCTL as isize,
-    /// A Writable Raw Tracepoint program type.
+    /// A Writable Raw Tracepoint program type. See
+    /// [`RawTracePoint`](super::raw_trace_point::RawTracePoint) for the program implementation.
     ///
     /// Introduced in kernel v5.2.
     #[doc(alias = "BPF_PROG_TYPE_RAW_TRACEPOINT_WRITABLE")]
@@ -445,7 +451,8 @@ pub enum ProgramType {
     #[doc(alias = "BPF_PROG_TYPE_CGROUP_SOCKOPT")]
     CgroupSockopt = bpf_prog_type::BPF_PROG_TYPE_CGROUP_SOCKOPT as isize,
     /// A Tracing program type. See [`FEntry`](super::fentry::FEntry), [`FExit`](super::fexit::FExit),
-    /// and [`BtfTracePoint`](super::tp_btf::BtfTracePoint) for the program implementations.
+    /// [`FModRet`](super::fmod_ret::FModRet) and [`BtfTracePoint`](super::tp_btf::BtfTracePoint)
+    /// for the program implementations.
     ///
     /// Introduced in kernel v5.5.
     #[doc(alias = "BPF_PROG_TYPE_TRACING")]
@@ -461,8 +468,8 @@ pub enum ProgramType {
     /// Introduced in kernel v5.6.
     #[doc(alias = "BPF_PROG_TYPE_EXT")]
     Extension = bpf_prog_type::BPF_PROG_TYPE_EXT as isize,
-    /// A Linux Security Module (LSM) program type. See [`Lsm`](super::lsm::Lsm) for the program
-    /// implementation.
+    /// A Linux Security Module (LSM) program type. See [`Lsm`](super::lsm::Lsm) and
+    /// [`LsmCgroup`](super::lsm_cgroup::LsmCgroup) for the program implementations.
     ///
     /// Introduced in kernel v5.7.
     #[doc(alias = "BPF_PROG_TYPE_LSM")]
@@ -473,12 +480,14 @@ pub enum ProgramType {
     /// Introduced in kernel v5.9.
     #[doc(alias = "BPF_PROG_TYPE_SK_LOOKUP")]
     SkLookup = bpf_prog_type::BPF_PROG_TYPE_SK_LOOKUP as isize,
-    /// A Syscall program type.
+    /// A Syscall program type. See [`Syscall`](super::syscall::Syscall) for the program
+    /// implementation.
     ///
     /// Introduced in kernel v5.14.
     #[doc(alias = "BPF_PROG_TYPE_SYSCALL")]
     Syscall = bpf_prog_type::BPF_PROG_TYPE_SYSCALL as isize,
-    /// A Netfilter program type.
+    /// A Netfilter program type. See [`Netfilter`](super::netfilter::Netfilter) for the program
+    /// implementation.
     ///
     /// Introduced in kernel v6.4.
     #[doc(alias = "BPF_PROG_TYPE_NETFILTER")]
@@ -1,23 +1,29 @@
 //! Iterators.
 use std::{
     fs::File,
-    os::fd::{AsFd, BorrowedFd},
+    os::fd::{AsFd, AsRawFd as _, BorrowedFd},
 };
 
 use aya_obj::{
     btf::{Btf, BtfKind},
     generated::{
-        bpf_attach_type::BPF_TRACE_ITER, bpf_link_type::BPF_LINK_TYPE_ITER,
+        bpf_attach_type::BPF_TRACE_ITER, bpf_cgroup_iter_order, bpf_iter_link_info,
+        bpf_iter_link_info__bindgen_ty_1, bpf_iter_link_info__bindgen_ty_2,
+        bpf_iter_link_info__bindgen_ty_3, bpf_link_type::BPF_LINK_TYPE_ITER,
         bpf_prog_type::BPF_PROG_TYPE_TRACING,
     },
 };
 
 use crate::{
+    maps::MapFd,
     programs::{
         FdLink, LinkError, PerfLinkIdInner, PerfLinkInner, ProgramData, ProgramError, ProgramType,
-        define_link_wrapper, impl_try_into_fdlink, load_program,
+        define_link_wrapper, impl_try_into_fdlink, impl_update_program, load_program,
+    },
+    sys::{
+        BpfLinkCreateArgs, LinkTarget, SyscallError, bpf_create_iter, bpf_link_create,
+        bpf_link_get_info_by_fd,
     },
-    sys::{LinkTarget, SyscallError, bpf_create_iter, bpf_link_create, bpf_link_get_info_by_fd},
 };
 
 /// A BPF iterator which allows to dump data from the kernel-space into the
@@ -76,14 +82,54 @@ impl Iter {
     ///
     /// The returned value can be used to detach, see [`Self::detach`].
     pub fn attach(&mut self) -> Result<IterLinkId, ProgramError> {
+        self.attach_inner(None)
+    }
+
+    /// Attaches the program, restricting the objects it iterates over.
+    ///
+    /// This is required for iterators over the elements of a map, such as
+    /// `bpf_map_elem` iterators, and can be used to scope `cgroup` and `task`
+    /// iterators.
+    ///
+    /// The returned value can be used to detach, see [`Self::detach`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aya::{maps::MapData, programs::{Iter, IterLinkOptions}, Btf, Ebpf};
+    /// # let mut ebpf = Ebpf::load_file("ebpf_programs.o")?;
+    ///
+    /// let btf = Btf::from_sys_fs()?;
+    /// let map = MapData::from_pin("/sys/fs/bpf/counters")?;
+    /// let program: &mut Iter = ebpf.program_mut("dump_counters").unwrap().try_into()?;
+    /// program.load("bpf_map_elem", &btf)?;
+    /// program.attach_with_options(IterLinkOptions::Map(map.fd()))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn attach_with_options(
+        &mut self,
+        options: IterLinkOptions<'_>,
+    ) -> Result<IterLinkId, ProgramError> {
+        self.attach_inner(Some(options.link_info()))
+    }
+
+    fn attach_inner(
+        &mut self,
+        link_info: Option<bpf_iter_link_info>,
+    ) -> Result<IterLinkId, ProgramError> {
         let prog_fd = self.fd()?;
         let prog_fd = prog_fd.as_fd();
-        let link_fd = bpf_link_create(prog_fd, LinkTarget::Iter, BPF_TRACE_ITER, 0, None).map_err(
-            |io_error| SyscallError {
-                call: "bpf_link_create",
-                io_error,
-            },
-        )?;
+        let link_fd = bpf_link_create(
+            prog_fd,
+            LinkTarget::Iter,
+            BPF_TRACE_ITER,
+            0,
+            link_info.as_ref().map(BpfLinkCreateArgs::IterInfo),
+        )
+        .map_err(|io_error| SyscallError {
+            call: "bpf_link_create",
+            io_error,
+        })?;
 
         self.data
             .links
@@ -91,6 +137,120 @@ impl Iter {
     }
 }
 
+/// Options restricting the objects an [`Iter`] program iterates over.
+///
+/// See [`Iter::attach_with_options`].
+#[derive(Debug)]
+pub enum IterLinkOptions<'a> {
+    /// Iterates over the elements of the given map.
+    ///
+    /// Used by `bpf_map_elem`, `bpf_sk_storage_map` and `sockmap` iterators.
+    ///
+    /// # Minimum kernel version
+    ///
+    /// The minimum kernel version required to use this feature is 5.9.
+    Map(&'a MapFd),
+    /// Iterates over the cgroups related to the given cgroup.
+    ///
+    /// # Minimum kernel version
+    ///
+    /// The minimum kernel version required to use this feature is 6.1.
+    Cgroup {
+        /// The cgroup the iteration starts from.
+        cgroup: CgroupIterTarget<'a>,
+        /// The order the cgroups are visited in.
+        order: CgroupIterOrder,
+    },
+    /// Iterates over the tasks of the given thread or process.
+    ///
+    /// # Minimum kernel version
+    ///
+    /// The minimum kernel version required to use this feature is 6.1.
+    Task(TaskIterTarget<'a>),
+}
+
+impl IterLinkOptions<'_> {
+    fn link_info(&self) -> bpf_iter_link_info {
+        // The unused bytes of the union must be zero.
+        let mut info = unsafe { std::mem::zeroed::<bpf_iter_link_info>() };
+        match self {
+            Self::Map(map_fd) => {
+                info.map = bpf_iter_link_info__bindgen_ty_1 {
+                    map_fd: map_fd.as_fd().as_raw_fd() as u32,
+                };
+            }
+            Self::Cgroup { cgroup, order } => {
+                let (cgroup_fd, cgroup_id) = match cgroup {
+                    CgroupIterTarget::Fd(fd) => (fd.as_raw_fd() as u32, 0),
+                    CgroupIterTarget::Id(id) => (0, *id),
+                };
+                info.cgroup = bpf_iter_link_info__bindgen_ty_2 {
+                    order: (*order).into(),
+                    cgroup_fd,
+                    cgroup_id,
+                };
+            }
+            Self::Task(task) => {
+                let (tid, pid, pid_fd) = match task {
+                    TaskIterTarget::Tid(tid) => (*tid, 0, 0),
+                    TaskIterTarget::Pid(pid) => (0, *pid, 0),
+                    TaskIterTarget::PidFd(fd) => (0, 0, fd.as_raw_fd() as u32),
+                };
+                info.task = bpf_iter_link_info__bindgen_ty_3 { tid, pid, pid_fd };
+            }
+        }
+        info
+    }
+}
+
+/// The cgroup a `cgroup` iterator starts from.
+#[derive(Debug)]
+pub enum CgroupIterTarget<'a> {
+    /// A file descriptor of the cgroup directory.
+    Fd(BorrowedFd<'a>),
+    /// The id of the cgroup.
+    Id(u64),
+}
+
+/// The order `cgroup` iterators visit cgroups in.
+#[derive(Clone, Copy, Debug)]
+pub enum CgroupIterOrder {
+    /// Visits only the given cgroup.
+    #[doc(alias = "BPF_CGROUP_ITER_SELF_ONLY")]
+    SelfOnly,
+    /// Visits the descendants of the given cgroup, parents before children.
+    #[doc(alias = "BPF_CGROUP_ITER_DESCENDANTS_PRE")]
+    DescendantsPre,
+    /// Visits the descendants of the given cgroup, children before parents.
+    #[doc(alias = "BPF_CGROUP_ITER_DESCENDANTS_POST")]
+    DescendantsPost,
+    /// Visits the ancestors of the given cgroup, up to the root.
+    #[doc(alias = "BPF_CGROUP_ITER_ANCESTORS_UP")]
+    AncestorsUp,
+}
+
+impl From<CgroupIterOrder> for bpf_cgroup_iter_order {
+    fn from(order: CgroupIterOrder) -> Self {
+        match order {
+            CgroupIterOrder::SelfOnly => Self::BPF_CGROUP_ITER_SELF_ONLY,
+            CgroupIterOrder::DescendantsPre => Self::BPF_CGROUP_ITER_DESCENDANTS_PRE,
+            CgroupIterOrder::DescendantsPost => Self::BPF_CGROUP_ITER_DESCENDANTS_POST,
+            CgroupIterOrder::AncestorsUp => Self::BPF_CGROUP_ITER_ANCESTORS_UP,
+        }
+    }
+}
+
+/// The tasks a `task`, `task_file` or `task_vma` iterator visits.
+#[derive(Debug)]
+pub enum TaskIterTarget<'a> {
+    /// Visits only the thread with the given id.
+    Tid(u32),
+    /// Visits the threads of the process with the given id.
+    Pid(u32),
+    /// Visits the threads of the process referred to by the given pidfd.
+    PidFd(BorrowedFd<'a>),
+}
+
 /// An iterator descriptor.
 #[derive(Debug)]
 pub struct IterFd {
@@ -117,6 +277,7 @@ impl TryFrom<FdLink> for IterLink {
 }
 
 impl_try_into_fdlink!(IterLink, PerfLinkInner);
+impl_update_program!(IterLink, PerfLinkInner);
 
 define_link_wrapper!(IterLink, IterLinkId, PerfLinkInner, PerfLinkIdInner, Iter);
 
@@ -13,11 +13,12 @@ use crate::{
     VerifierLogLevel,
     programs::{
         FdLink, LinkError, ProgramData, ProgramError, ProgramType, define_link_wrapper,
-        impl_try_into_fdlink, load_program,
+        impl_try_into_fdlink, impl_update_program, load_program,
         perf_attach::{PerfLinkIdInner, PerfLinkInner},
         probe::{ProbeKind, attach},
     },
     sys::bpf_link_get_info_by_fd,
+    util::kernel_symbols,
 };
 
 /// A kernel probe.
@@ -91,6 +92,34 @@ impl KProbe {
         )
     }
 
+    /// Attaches the program to the given syscall.
+    ///
+    /// `syscall` is the name of the syscall without any prefix, such as
+    /// `openat`. The probe is attached to the wrapper implementing the syscall
+    /// on the running kernel, such as `__x64_sys_openat` on x86_64 or
+    /// `__arm64_sys_openat` on aarch64, which is found in `/proc/kallsyms`.
+    /// The wrapper takes a pointer to the registers saved on syscall entry,
+    /// from which the arguments can be read in eBPF with `KSyscallContext`.
+    /// On kernels without syscall wrappers, the probe is attached to
+    /// `sys_openat` instead.
+    ///
+    /// Syscalls made by 32-bit tasks on 64-bit kernels go through separate
+    /// compat wrappers, which this doesn't attach to.
+    ///
+    /// The returned value can be used to detach from the syscall, see [KProbe::detach].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KProbeError::FileError`] if `/proc/kallsyms` can't be read.
+    pub fn attach_syscall(&mut self, syscall: &str) -> Result<KProbeLinkId, ProgramError> {
+        let symbols = kernel_symbols().map_err(|io_error| KProbeError::FileError {
+            filename: "/proc/kallsyms".into(),
+            io_error,
+        })?;
+        let fn_name = syscall_fn_name(symbols.values(), syscall);
+        self.attach(fn_name, 0)
+    }
+
     /// Creates a program from a pinned entry on a bpffs.
     ///
     /// Existing links will not be populated. To work with existing links you should use [`crate::programs::links::PinnedLink`].
@@ -125,7 +154,36 @@ pub enum KProbeError {
     },
 }
 
+/// The prefixes of the native syscall wrappers of the architectures that have them.
+///
+/// Compat wrappers such as `__ia32_compat_sys_` are left out on purpose, they only handle the
+/// syscalls made by 32-bit tasks.
+const SYSCALL_WRAPPER_PREFIXES: &[&str] = &[
+    "__x64_sys_",
+    "__arm64_sys_",
+    "__riscv_sys_",
+    "__s390x_sys_",
+    "__powerpc_sys_",
+];
+
+/// Returns the name of the function implementing `syscall` among the kernel `symbols`.
+///
+/// Kernels without syscall wrappers implement syscalls in `sys_<name>`.
+fn syscall_fn_name<S: AsRef<str>>(symbols: impl IntoIterator<Item = S>, syscall: &str) -> String {
+    symbols
+        .into_iter()
+        .find_map(|symbol| {
+            let symbol = symbol.as_ref();
+            let name = SYSCALL_WRAPPER_PREFIXES
+                .iter()
+                .find_map(|prefix| symbol.strip_prefix(prefix))?;
+            (name == syscall).then(|| symbol.to_owned())
+        })
+        .unwrap_or_else(|| format!("sys_{syscall}"))
+}
+
 impl_try_into_fdlink!(KProbeLink, PerfLinkInner);
+impl_update_program!(KProbeLink, PerfLinkInner);
 
 impl TryFrom<FdLink> for KProbeLink {
     type Error = LinkError;
@@ -138,3 +196,29 @@ impl TryFrom<FdLink> for KProbeLink {
         Err(LinkError::InvalidLink)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syscall_fn_name() {
+        let symbols = [
+            "__ia32_compat_sys_openat",
+            "__x64_sys_openat2",
+            "__x64_sys_openat",
+            "sys_openat",
+        ];
+        assert_eq!(syscall_fn_name(symbols, "openat"), "__x64_sys_openat");
+        assert_eq!(
+            syscall_fn_name(["__arm64_sys_bpf"], "bpf"),
+            "__arm64_sys_bpf"
+        );
+        // Without wrappers, syscalls are implemented in `sys_<name>`.
+        assert_eq!(syscall_fn_name(["sys_openat"], "openat"), "sys_openat");
+        assert_eq!(
+            syscall_fn_name(["__ia32_compat_sys_openat"], "openat"),
+            "sys_openat"
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Kernel space probes attached to many functions through a single link.
+use std::{
+    collections::HashSet,
+    ffi::{CString, NulError, OsStr, OsString},
+    fs::File,
+    io::{self, BufRead, BufReader},
+    os::{fd::AsFd as _, unix::ffi::OsStrExt as _},
+    path::{Path, PathBuf},
+};
+
+use aya_obj::generated::{
+    BPF_F_KPROBE_MULTI_RETURN, bpf_attach_type::BPF_TRACE_KPROBE_MULTI,
+    bpf_prog_type::BPF_PROG_TYPE_KPROBE,
+};
+use thiserror::Error;
+
+use crate::{
+    VerifierLogLevel,
+    programs::{
+        FdLink, FdLinkId, ProgramData, ProgramError, ProgramType, define_link_wrapper,
+        load_program, probe::ProbeKind, utils::find_tracefs_path,
+    },
+    sys::{BpfLinkCreateArgs, LinkTarget, SyscallError, bpf_link_create},
+};
+
+/// A kernel probe attached to many kernel functions at once.
+///
+/// Unlike [`KProbe`](super::KProbe), which creates one perf event per traced
+/// function, a `KProbeMulti` program is attached to all of its target
+/// functions through a single `BPF_LINK_TYPE_KPROBE_MULTI` link. This makes
+/// tracing hundreds of functions considerably cheaper to set up.
+///
+/// The targets can either be given as an explicit list of symbols, optionally
+/// with one attach cookie per symbol, or as a glob pattern matched against the functions listed in
+/// `available_filter_functions`.
+///
+/// The program is a `kprobe` if it was declared in a `kprobe.multi` section and
+/// a `kretprobe` if it was declared in a `kretprobe.multi` section.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.18.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = Ebpf::load_file("ebpf_programs.o")?;
+/// use aya::{Ebpf, programs::KProbeMulti};
+///
+/// let program: &mut KProbeMulti = bpf.program_mut("tcp_calls").unwrap().try_into()?;
+/// program.load()?;
+/// program.attach_pattern("tcp_v4_*")?;
+/// # Ok::<(), aya::EbpfError>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_TRACE_KPROBE_MULTI")]
+pub struct KProbeMulti {
+    pub(crate) data: ProgramData<KProbeMultiLink>,
+    pub(crate) kind: ProbeKind,
+}
+
+impl KProbeMulti {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::KProbe;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        self.data.expected_attach_type = Some(BPF_TRACE_KPROBE_MULTI);
+        load_program(BPF_PROG_TYPE_KPROBE, &mut self.data)
+    }
+
+    /// Returns `KProbe` if the program is a `kprobe.multi`, or `KRetProbe` if
+    /// the program is a `kretprobe.multi`.
+    pub fn kind(&self) -> ProbeKind {
+        self.kind
+    }
+
+    /// Attaches the program to the given kernel functions.
+    ///
+    /// If `cookies` is provided it must contain exactly one value per symbol;
+    /// the value at index `i` can be retrieved with `bpf_get_attach_cookie`
+    /// when the program runs for `symbols[i]`.
+    ///
+    /// The returned value can be used to detach, see [`KProbeMulti::detach`].
+    pub fn attach<T: AsRef<OsStr>>(
+        &mut self,
+        symbols: &[T],
+        cookies: Option<&[u64]>,
+    ) -> Result<KProbeMultiLinkId, ProgramError> {
+        if let Some(cookies) = cookies {
+            if cookies.len() != symbols.len() {
+                return Err(KProbeMultiError::CookieCountMismatch {
+                    symbols: symbols.len(),
+                    cookies: cookies.len(),
+                }
+                .into());
+            }
+        }
+        let symbols = symbols
+            .iter()
+            .map(|symbol| {
+                let symbol = symbol.as_ref();
+                CString::new(symbol.as_bytes()).map_err(|error| KProbeMultiError::InvalidSymbol {
+                    symbol: symbol.to_os_string(),
+                    error,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.attach_symbols(&symbols, cookies)
+    }
+
+    /// Attaches the program to all the kernel functions matching `pattern`.
+    ///
+    /// `pattern` is a glob where `*` matches any sequence of characters and
+    /// `?` matches any single character. It is matched against the functions
+    /// listed in `available_filter_functions` in tracefs.
+    ///
+    /// The returned value can be used to detach, see [`KProbeMulti::detach`].
+    pub fn attach_pattern(&mut self, pattern: &str) -> Result<KProbeMultiLinkId, ProgramError> {
+        let path = find_tracefs_path()?.join("available_filter_functions");
+        let file = File::open(&path).map_err(|io_error| KProbeMultiError::FileError {
+            filename: path.clone(),
+            io_error,
+        })?;
+        let symbols = matching_symbols(BufReader::new(file), pattern).map_err(|io_error| {
+            KProbeMultiError::FileError {
+                filename: path,
+                io_error,
+            }
+        })?;
+        if symbols.is_empty() {
+            return Err(KProbeMultiError::NoMatchingSymbols {
+                pattern: pattern.to_owned(),
+            }
+            .into());
+        }
+        self.attach_symbols(&symbols, None)
+    }
+
+    fn attach_symbols(
+        &mut self,
+        symbols: &[CString],
+        cookies: Option<&[u64]>,
+    ) -> Result<KProbeMultiLinkId, ProgramError> {
+        let prog_fd = self.fd()?;
+        let prog_fd = prog_fd.as_fd();
+        let syms: Vec<_> = symbols.iter().map(|symbol| symbol.as_ptr()).collect();
+        let flags = match self.kind {
+            ProbeKind::KRetProbe => BPF_F_KPROBE_MULTI_RETURN,
+            ProbeKind::KProbe | ProbeKind::UProbe | ProbeKind::URetProbe => 0,
+        };
+        let link_fd = bpf_link_create(
+            prog_fd,
+            LinkTarget::None,
+            BPF_TRACE_KPROBE_MULTI,
+            0,
+            Some(BpfLinkCreateArgs::KProbeMulti {
+                syms: &syms,
+                cookies,
+                flags,
+            }),
+        )
+        .map_err(|io_error| SyscallError {
+            call: "bpf_link_create",
+            io_error,
+        })?;
+
+        self.data
+            .links
+            .insert(KProbeMultiLink::new(FdLink::new(link_fd)))
+    }
+
+    /// Creates a program from a pinned entry on a bpffs.
+    ///
+    /// Existing links will not be populated. To work with existing links you should use [`crate::programs::links::PinnedLink`].
+    ///
+    /// On drop, any managed links are detached and the program is unloaded. This will not result in
+    /// the program being unloaded from the kernel if it is still pinned.
+    pub fn from_pin<P: AsRef<Path>>(path: P, kind: ProbeKind) -> Result<Self, ProgramError> {
+        let data = ProgramData::from_pinned_path(path, VerifierLogLevel::default())?;
+        Ok(Self { data, kind })
+    }
+}
+
+/// Returns the deduplicated list of traceable functions matching `pattern`.
+fn matching_symbols(reader: impl BufRead, pattern: &str) -> io::Result<Vec<CString>> {
+    let mut seen = HashSet::new();
+    let mut symbols = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        // Lines look like `tcp_v4_connect` or `nf_nat_setup_info [nf_nat]`.
+        let Some(name) = line.split_whitespace().next() else {
+            continue;
+        };
+        // Placeholders for functions whose address couldn't be resolved.
+        if name.starts_with("__ftrace_invalid_address__") {
+            continue;
+        }
+        if glob_match(pattern, name) && seen.insert(name.to_owned()) {
+            // `name` comes from a line so it can't contain a nul byte.
+            symbols.push(CString::new(name).unwrap());
+        }
+    }
+    Ok(symbols)
+}
+
+/// Matches `s` against `pattern`, where `*` matches any sequence of bytes and
+/// `?` matches any single byte.
+fn glob_match(pattern: &str, s: &str) -> bool {
+    let (pattern, s) = (pattern.as_bytes(), s.as_bytes());
+    let (mut p, mut i) = (0, 0);
+    // The position of the last `*` seen in the pattern and the position in `s`
+    // it is currently matched up to, used to backtrack.
+    let mut star = None;
+    while i < s.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, i));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == s[i] => {
+                p += 1;
+                i += 1;
+            }
+            _ => match star {
+                Some((star_p, star_i)) => {
+                    p = star_p + 1;
+                    i = star_i + 1;
+                    star = Some((star_p, star_i + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+define_link_wrapper!(
+    KProbeMultiLink,
+    KProbeMultiLinkId,
+    FdLink,
+    FdLinkId,
+    KProbeMulti,
+);
+
+/// The type returned when attaching a [`KProbeMulti`] fails.
+#[derive(Debug, Error)]
+pub enum KProbeMultiError {
+    /// Error reading the list of traceable functions.
+    #[error("`{filename}`")]
+    FileError {
+        /// The file name
+        filename: PathBuf,
+        /// The [`io::Error`] returned from the file operation
+        #[source]
+        io_error: io::Error,
+    },
+
+    /// No traceable kernel function matches the pattern.
+    #[error("no kernel function matches `{pattern}`")]
+    NoMatchingSymbols {
+        /// The glob pattern
+        pattern: String,
+    },
+
+    /// A symbol name contains a nul byte.
+    #[error("invalid symbol name {symbol:?}")]
+    InvalidSymbol {
+        /// The symbol name
+        symbol: OsString,
+        /// The [`NulError`] returned when converting the symbol name
+        #[source]
+        error: NulError,
+    },
+
+    /// The number of cookies doesn't match the number of symbols.
+    #[error("got {cookies} cookies for {symbols} symbols")]
+    CookieCountMismatch {
+        /// The number of symbols
+        symbols: usize,
+        /// The number of cookies
+        cookies: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("tcp_*", "tcp_v4_connect"));
+        assert!(glob_match("*_connect", "tcp_v4_connect"));
+        assert!(glob_match("tcp_v?_*", "tcp_v6_connect"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("tcp_v4_connect", "tcp_v4_connect"));
+        assert!(glob_match("t*p*c*t", "tcp_v4_connect"));
+        assert!(!glob_match("tcp_*", "udp_sendmsg"));
+        assert!(!glob_match("tcp_v?", "tcp_v4_connect"));
+        assert!(!glob_match("", "tcp"));
+    }
+
+    #[test]
+    fn test_matching_symbols() {
+        let data = b"\
+tcp_v4_connect
+__ftrace_invalid_address___64
+udp_sendmsg
+tcp_close
+nf_nat_setup_info [nf_nat]
+tcp_close
+";
+        let symbols = matching_symbols(&data[..], "tcp_*").unwrap();
+        assert_eq!(
+            symbols,
+            [
+                CString::new("tcp_v4_connect").unwrap(),
+                CString::new("tcp_close").unwrap()
+            ]
+        );
+
+        let symbols = matching_symbols(&data[..], "nf_*").unwrap();
+        assert_eq!(symbols, [CString::new("nf_nat_setup_info").unwrap()]);
+    }
+}
@@ -20,8 +20,8 @@ use crate::{
     pin::PinError,
     programs::{MultiProgLink, MultiProgram, ProgramError, ProgramFd, ProgramId},
     sys::{
-        SyscallError, bpf_get_object, bpf_link_get_info_by_fd, bpf_pin_object, bpf_prog_attach,
-        bpf_prog_detach,
+        SyscallError, bpf_get_object, bpf_link_detach, bpf_link_get_fd_by_id,
+        bpf_link_get_info_by_fd, bpf_link_update, bpf_pin_object, bpf_prog_attach, bpf_prog_detach,
     },
 };
 
@@ -37,6 +37,26 @@ pub trait Link: std::fmt::Debug + Eq + std::hash::Hash + 'static {
     fn detach(self) -> Result<(), ProgramError>;
 }
 
+/// The link type of programs which aren't attached through aya, such as
+/// struct_ops programs, which are registered through their map, and syscall
+/// programs, which are only run with `BPF_PROG_RUN`.
+///
+/// It has no values, so no link can be created for these programs.
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub(crate) enum NoLink {}
+
+impl Link for NoLink {
+    type Id = Self;
+
+    fn id(&self) -> Self::Id {
+        match *self {}
+    }
+
+    fn detach(self) -> Result<(), ProgramError> {
+        match self {}
+    }
+}
+
 /// Program attachment mode.
 #[derive(Clone, Copy, Debug, Default)]
 pub enum CgroupAttachMode {
@@ -252,6 +272,53 @@ impl FdLink {
         Self { fd }
     }
 
+    /// Creates a link from the id of a link loaded in the kernel.
+    ///
+    /// Combined with [`loaded_links`](crate::programs::loaded_links), this
+    /// gives access to links created by other processes, for instance to
+    /// [`force_detach`](Self::force_detach) them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use aya::programs::{links::FdLink, loaded_links};
+    ///
+    /// // Detaches all the links of the program with id 42.
+    /// for info in loaded_links() {
+    ///     let info = info?;
+    ///     if info.program_id() == 42 {
+    ///         FdLink::from_id(info.id())?.force_detach()?;
+    ///     }
+    /// }
+    /// # Ok::<(), aya::programs::links::LinkError>(())
+    /// ```
+    pub fn from_id(id: u32) -> Result<Self, LinkError> {
+        let fd = bpf_link_get_fd_by_id(id)?;
+        Ok(Self::new(fd))
+    }
+
+    /// Forcibly detaches the link.
+    ///
+    /// Unlike dropping the link, which only closes this file descriptor, this
+    /// detaches the link in the kernel even if it has other owners, such as a
+    /// pin on a BPF file system or another process. The link stays alive in a
+    /// defunct state until all of its file descriptors are closed and pins are
+    /// removed.
+    ///
+    /// Not all link types support being detached, in which case the call
+    /// fails with `EOPNOTSUPP`.
+    ///
+    /// # Minimum kernel version
+    ///
+    /// The minimum kernel version required to use this feature is 5.9.
+    pub fn force_detach(&self) -> Result<(), LinkError> {
+        bpf_link_detach(self.fd.as_fd()).map_err(|io_error| SyscallError {
+            call: "bpf_link_detach",
+            io_error,
+        })?;
+        Ok(())
+    }
+
     /// Pins the link to a BPF file system.
     ///
     /// When a link is pinned it will remain attached even after the link instance is dropped,
@@ -302,6 +369,68 @@ impl FdLink {
     pub fn info(&self) -> Result<LinkInfo, LinkError> {
         LinkInfo::new_from_fd(self.fd.as_fd())
     }
+
+    /// Atomically replaces the program attached by the link with `program`.
+    ///
+    /// Unlike detaching the link and attaching `program`, there's no window
+    /// during which neither program is attached. `program` must have the same
+    /// type and attach type as the program it replaces.
+    ///
+    /// If `expected_program` is set, the program is only replaced if it's the
+    /// program currently attached by the link, otherwise the call fails with
+    /// `EPERM`.
+    ///
+    /// The link must outlive the program it was created from, so take it with
+    /// `take_link` or pin it first: dropping the original program detaches the
+    /// links it still owns.
+    ///
+    /// # Minimum kernel version
+    ///
+    /// The minimum kernel version required to use this feature is 5.7.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[derive(thiserror::Error, Debug)]
+    /// # enum Error {
+    /// #     #[error(transparent)]
+    /// #     Ebpf(#[from] aya::EbpfError),
+    /// #     #[error(transparent)]
+    /// #     Link(#[from] aya::programs::links::LinkError),
+    /// #     #[error(transparent)]
+    /// #     Program(#[from] aya::programs::ProgramError)
+    /// # }
+    /// # let mut bpf = aya::Ebpf::load(&[])?;
+    /// # let mut new_bpf = aya::Ebpf::load(&[])?;
+    /// use aya::programs::{links::FdLink, Xdp, XdpFlags};
+    ///
+    /// let old: &mut Xdp = bpf.program_mut("filter").unwrap().try_into()?;
+    /// old.load()?;
+    /// let link_id = old.attach("eth0", XdpFlags::default())?;
+    /// let link: FdLink = old.take_link(link_id)?.try_into()?;
+    ///
+    /// let new: &mut Xdp = new_bpf.program_mut("filter").unwrap().try_into()?;
+    /// new.load()?;
+    /// link.update_program(new.fd()?, Some(old.fd()?))?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn update_program(
+        &self,
+        program: &ProgramFd,
+        expected_program: Option<&ProgramFd>,
+    ) -> Result<(), LinkError> {
+        bpf_link_update(
+            self.fd.as_fd(),
+            program.as_fd(),
+            expected_program.map(|fd| fd.as_fd().as_raw_fd()),
+            0,
+        )
+        .map_err(|io_error| SyscallError {
+            call: "bpf_link_update",
+            io_error,
+        })?;
+        Ok(())
+    }
 }
 
 impl Link for FdLink {
@@ -365,6 +494,24 @@ impl PinnedLink {
         std::fs::remove_file(self.path)?;
         Ok(self.inner)
     }
+
+    /// Forcibly detaches the link.
+    ///
+    /// The link is detached even though it stays pinned, see [`FdLink::force_detach`].
+    pub fn force_detach(&self) -> Result<(), LinkError> {
+        self.inner.force_detach()
+    }
+
+    /// Atomically replaces the program attached by the link with `program`.
+    ///
+    /// See [`FdLink::update_program`].
+    pub fn update_program(
+        &self,
+        program: &ProgramFd,
+        expected_program: Option<&ProgramFd>,
+    ) -> Result<(), LinkError> {
+        self.inner.update_program(program, expected_program)
+    }
 }
 
 /// The identifier of a `ProgAttachLink`.
@@ -473,7 +620,30 @@ macro_rules! id_as_key {
 pub(crate) use id_as_key;
 
 macro_rules! define_link_wrapper {
+    ($wrapper:ident, $wrapper_id:ident, FdLink, FdLinkId, $program:ident $(,)?) => {
+        $crate::programs::links::define_link_wrapper!(
+            @inner $wrapper, $wrapper_id, FdLink, FdLinkId, $program
+        );
+
+        impl $wrapper {
+            /// Atomically replaces the program attached by the link with `program`.
+            ///
+            /// See [`FdLink::update_program`](crate::programs::links::FdLink::update_program).
+            pub fn update_program(
+                &self,
+                program: &$crate::programs::ProgramFd,
+                expected_program: Option<&$crate::programs::ProgramFd>,
+            ) -> Result<(), $crate::programs::links::LinkError> {
+                self.inner().update_program(program, expected_program)
+            }
+        }
+    };
     ($wrapper:ident, $wrapper_id:ident, $base:ident, $base_id:ident, $program:ident $(,)?) => {
+        $crate::programs::links::define_link_wrapper!(
+            @inner $wrapper, $wrapper_id, $base, $base_id, $program
+        );
+    };
+    (@inner $wrapper:ident, $wrapper_id:ident, $base:ident, $base_id:ident, $program:ident) => {
         /// The type returned by
         #[doc = concat!("[`", stringify!($program), "::attach`]")]
         /// . Can be passed to
@@ -582,6 +752,30 @@ macro_rules! impl_try_into_fdlink {
 
 pub(crate) use impl_try_into_fdlink;
 
+macro_rules! impl_update_program {
+    ($wrapper:ident, $inner:ident) => {
+        impl $wrapper {
+            /// Atomically replaces the program attached by the link with `program`.
+            ///
+            /// See [`FdLink::update_program`](crate::programs::links::FdLink::update_program).
+            /// Returns [`LinkError::InvalidLink`](crate::programs::links::LinkError::InvalidLink)
+            /// if the program wasn't attached with a file descriptor based link.
+            pub fn update_program(
+                &self,
+                program: &$crate::programs::ProgramFd,
+                expected_program: Option<&$crate::programs::ProgramFd>,
+            ) -> Result<(), $crate::programs::links::LinkError> {
+                match self.inner() {
+                    $inner::Fd(link) => link.update_program(program, expected_program),
+                    _ => Err($crate::programs::links::LinkError::InvalidLink),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use impl_update_program;
+
 #[derive(Error, Debug)]
 /// Errors from operations on links.
 pub enum LinkError {
@@ -723,13 +917,13 @@ mod tests {
     use std::{cell::RefCell, fs::File, rc::Rc};
 
     use assert_matches::assert_matches;
-    use aya_obj::generated::{BPF_F_ALLOW_MULTI, BPF_F_ALLOW_OVERRIDE};
+    use aya_obj::generated::{BPF_F_ALLOW_MULTI, BPF_F_ALLOW_OVERRIDE, BPF_F_REPLACE, bpf_cmd};
     use tempfile::tempdir;
 
     use super::{FdLink, Link, Links};
     use crate::{
-        programs::{CgroupAttachMode, ProgramError},
-        sys::override_syscall,
+        programs::{CgroupAttachMode, ProgramError, ProgramFd},
+        sys::{Syscall, override_syscall},
     };
 
     #[derive(Debug, Hash, Eq, PartialEq)]
@@ -879,6 +1073,63 @@ mod tests {
         assert!(!pin.exists());
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore = "`mkdir` not available when isolation is enabled")]
+    fn test_update_program() {
+        let dir = tempdir().unwrap();
+        let file = |name| File::create(dir.path().join(name)).unwrap();
+        let link = FdLink::new(file("link").into());
+        let new_prog = ProgramFd(file("new").into());
+        let old_prog = ProgramFd(file("old").into());
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_LINK_UPDATE,
+                attr,
+            } => {
+                let attr = unsafe { &attr.link_update };
+                assert_eq!(attr.flags, 0);
+                assert_eq!(unsafe { attr.__bindgen_anon_2.old_prog_fd }, 0);
+                Ok(0)
+            }
+            call => panic!("unexpected syscall {call:?}"),
+        });
+        link.update_program(&new_prog, None).unwrap();
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_LINK_UPDATE,
+                attr,
+            } => {
+                let attr = unsafe { &attr.link_update };
+                assert_eq!(attr.flags, BPF_F_REPLACE);
+                assert_ne!(unsafe { attr.__bindgen_anon_2.old_prog_fd }, 0);
+                Ok(0)
+            }
+            call => panic!("unexpected syscall {call:?}"),
+        });
+        link.update_program(&new_prog, Some(&old_prog)).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "`mkdir` not available when isolation is enabled")]
+    fn test_force_detach() {
+        let dir = tempdir().unwrap();
+        let link = FdLink::new(File::create(dir.path().join("link")).unwrap().into());
+
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_LINK_DETACH,
+                attr,
+            } => {
+                assert_ne!(unsafe { attr.link_detach.link_fd }, 0);
+                Ok(0)
+            }
+            call => panic!("unexpected syscall {call:?}"),
+        });
+        link.force_detach().unwrap();
+    }
+
     #[test]
     fn test_cgroup_attach_flag() {
         assert_eq!(u32::from(CgroupAttachMode::Single), 0);
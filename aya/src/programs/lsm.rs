@@ -7,7 +7,7 @@ use aya_obj::{
 
 use crate::programs::{
     FdLink, FdLinkId, ProgramData, ProgramError, ProgramType, define_link_wrapper, load_program,
-    utils::attach_raw_tracepoint,
+    utils::{attach_raw_tracepoint, set_attach_btf_id},
 };
 
 /// A program that attaches to Linux LSM hooks. Used to implement security policy and
@@ -66,8 +66,7 @@ impl Lsm {
     pub fn load(&mut self, lsm_hook_name: &str, btf: &Btf) -> Result<(), ProgramError> {
         self.data.expected_attach_type = Some(BPF_LSM_MAC);
         let type_name = format!("bpf_lsm_{lsm_hook_name}");
-        self.data.attach_btf_id =
-            Some(btf.id_by_type_name_kind(type_name.as_str(), BtfKind::Func)?);
+        set_attach_btf_id(&mut self.data, btf, &type_name, BtfKind::Func)?;
         load_program(BPF_PROG_TYPE_LSM, &mut self.data)
     }
 
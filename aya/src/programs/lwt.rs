@@ -0,0 +1,567 @@
+//! Lightweight tunnel programs.
+use std::{ffi::CString, net::IpAddr, os::fd::AsFd as _};
+
+use aya_obj::generated::{
+    BPF_OBJ_NAME_LEN,
+    bpf_prog_type::{
+        BPF_PROG_TYPE_LWT_IN, BPF_PROG_TYPE_LWT_OUT, BPF_PROG_TYPE_LWT_SEG6LOCAL,
+        BPF_PROG_TYPE_LWT_XMIT,
+    },
+};
+use libc::{RT_SCOPE_HOST, RT_SCOPE_LINK, RT_SCOPE_UNIVERSE, RT_TABLE_MAIN};
+use thiserror::Error;
+
+use crate::{
+    programs::{
+        Link, ProgramData, ProgramError, ProgramType, define_link_wrapper, id_as_key, load_program,
+    },
+    sys::{
+        Route, netlink_route_add_lwt, netlink_route_del, netlink_route_lwt_prog_name,
+        netlink_route_replace,
+    },
+    util::ifindex_from_ifname,
+};
+
+/// An error that occurred while working with a lightweight tunnel program.
+#[derive(Debug, Error)]
+pub enum LwtError {
+    /// Seg6local programs can only be attached to IPv6 routes.
+    #[error("seg6local programs can't be attached to the IPv4 route to {dst}")]
+    Ipv4Route {
+        /// The destination of the route.
+        dst: IpAddr,
+    },
+
+    /// The route doesn't run the program anymore, so it isn't removed.
+    #[error("the route to {dst}/{prefix_len} doesn't run the program anymore")]
+    RouteChanged {
+        /// The destination of the route.
+        dst: IpAddr,
+        /// The prefix length of the destination.
+        prefix_len: u8,
+    },
+}
+
+/// The scope of a route.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum RouteScope {
+    /// The destination is anywhere, usually through a gateway.
+    Universe,
+    /// The destination is on a directly attached link.
+    Link,
+    /// The destination is on the local host.
+    Host,
+}
+
+impl From<RouteScope> for u8 {
+    fn from(scope: RouteScope) -> Self {
+        match scope {
+            RouteScope::Universe => RT_SCOPE_UNIVERSE,
+            RouteScope::Link => RT_SCOPE_LINK,
+            RouteScope::Host => RT_SCOPE_HOST,
+        }
+    }
+}
+
+/// Options for attaching a lightweight tunnel program to a route.
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct LwtRouteOptions {
+    /// The routing table of the route. Defaults to the main table.
+    pub table: Option<u32>,
+    /// The gateway of the route. Without a gateway, the destination is
+    /// directly reachable through the interface.
+    pub gateway: Option<IpAddr>,
+    /// The scope of the route. Defaults to [`RouteScope::Link`] without a
+    /// gateway and to [`RouteScope::Universe`] with one.
+    pub scope: Option<RouteScope>,
+    /// Whether to attach the program to an existing route instead of creating
+    /// a new one.
+    ///
+    /// The existing route is replaced by one running the program. When the
+    /// program is detached, it is replaced again by a route which doesn't run
+    /// any program, instead of being deleted. Attributes of the existing route
+    /// which aren't set by these options, such as its metrics, are lost.
+    pub replace: bool,
+}
+
+/// The point of the route a lightweight tunnel program runs at.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub(crate) enum LwtHook {
+    In,
+    Out,
+    Xmit { headroom: u32 },
+    Seg6Local,
+}
+
+/// A program that runs on packets received by a route.
+///
+/// [`LwtIn`] programs are attached to routes and run on the packets being
+/// received by them, after the routing lookup. The program can inspect and
+/// drop packets but can't modify them.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.10.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::net::Ipv4Addr;
+/// use aya::programs::LwtIn;
+///
+/// let prog: &mut LwtIn = bpf.program_mut("route_in").unwrap().try_into()?;
+/// prog.load()?;
+/// prog.attach("eth0", Ipv4Addr::new(10, 0, 0, 0).into(), 24)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_PROG_TYPE_LWT_IN")]
+pub struct LwtIn {
+    pub(crate) data: ProgramData<LwtInLink>,
+}
+
+impl LwtIn {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::LwtInput;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        load_program(BPF_PROG_TYPE_LWT_IN, &mut self.data)
+    }
+
+    /// Attaches the program to a new route to `dst`/`prefix_len` through the
+    /// given `interface`.
+    ///
+    /// The route is deleted when the program is detached. The returned value
+    /// can be used to detach, see [LwtIn::detach].
+    ///
+    /// For more control over the route, see [`LwtIn::attach_with_options`].
+    pub fn attach(
+        &mut self,
+        interface: &str,
+        dst: IpAddr,
+        prefix_len: u8,
+    ) -> Result<LwtInLinkId, ProgramError> {
+        self.attach_with_options(interface, dst, prefix_len, &LwtRouteOptions::default())
+    }
+
+    /// Attaches the program to the route to `dst`/`prefix_len` through the
+    /// given `interface`, as described by `options`.
+    ///
+    /// The returned value can be used to detach, see [LwtIn::detach].
+    pub fn attach_with_options(
+        &mut self,
+        interface: &str,
+        dst: IpAddr,
+        prefix_len: u8,
+        options: &LwtRouteOptions,
+    ) -> Result<LwtInLinkId, ProgramError> {
+        let link = attach_route(&self.data, interface, dst, prefix_len, options, LwtHook::In)?;
+        self.data.links.insert(LwtInLink::new(link))
+    }
+}
+
+/// A program that runs on packets sent through a route.
+///
+/// [`LwtOut`] programs are attached to routes and run on the packets being
+/// sent through them, before the packets are transmitted. The program can
+/// inspect and drop packets but can't modify them.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.10.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::net::Ipv4Addr;
+/// use aya::programs::LwtOut;
+///
+/// let prog: &mut LwtOut = bpf.program_mut("route_out").unwrap().try_into()?;
+/// prog.load()?;
+/// prog.attach("eth0", Ipv4Addr::new(10, 0, 0, 0).into(), 24)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_PROG_TYPE_LWT_OUT")]
+pub struct LwtOut {
+    pub(crate) data: ProgramData<LwtOutLink>,
+}
+
+impl LwtOut {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::LwtOutput;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        load_program(BPF_PROG_TYPE_LWT_OUT, &mut self.data)
+    }
+
+    /// Attaches the program to a new route to `dst`/`prefix_len` through the
+    /// given `interface`.
+    ///
+    /// The route is deleted when the program is detached. The returned value
+    /// can be used to detach, see [LwtOut::detach].
+    ///
+    /// For more control over the route, see [`LwtOut::attach_with_options`].
+    pub fn attach(
+        &mut self,
+        interface: &str,
+        dst: IpAddr,
+        prefix_len: u8,
+    ) -> Result<LwtOutLinkId, ProgramError> {
+        self.attach_with_options(interface, dst, prefix_len, &LwtRouteOptions::default())
+    }
+
+    /// Attaches the program to the route to `dst`/`prefix_len` through the
+    /// given `interface`, as described by `options`.
+    ///
+    /// The returned value can be used to detach, see [LwtOut::detach].
+    pub fn attach_with_options(
+        &mut self,
+        interface: &str,
+        dst: IpAddr,
+        prefix_len: u8,
+        options: &LwtRouteOptions,
+    ) -> Result<LwtOutLinkId, ProgramError> {
+        let link = attach_route(
+            &self.data,
+            interface,
+            dst,
+            prefix_len,
+            options,
+            LwtHook::Out,
+        )?;
+        self.data.links.insert(LwtOutLink::new(link))
+    }
+}
+
+/// A program that runs on packets transmitted through a route.
+///
+/// [`LwtXmit`] programs are attached to routes and run on the packets being
+/// transmitted through them. Unlike [`LwtIn`] and [`LwtOut`] programs, they
+/// can modify packets and push encapsulation headers with
+/// `bpf_lwt_push_encap`.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.10.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::net::Ipv4Addr;
+/// use aya::programs::LwtXmit;
+///
+/// let prog: &mut LwtXmit = bpf.program_mut("encap").unwrap().try_into()?;
+/// prog.load()?;
+/// prog.attach("eth0", Ipv4Addr::new(10, 0, 0, 0).into(), 24, 0)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_PROG_TYPE_LWT_XMIT")]
+pub struct LwtXmit {
+    pub(crate) data: ProgramData<LwtXmitLink>,
+}
+
+impl LwtXmit {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::LwtXmit;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        load_program(BPF_PROG_TYPE_LWT_XMIT, &mut self.data)
+    }
+
+    /// Attaches the program to a new route to `dst`/`prefix_len` through the
+    /// given `interface`.
+    ///
+    /// `headroom` is the number of bytes the kernel reserves in front of the
+    /// packets for the headers pushed by the program.
+    ///
+    /// The route is deleted when the program is detached. The returned value
+    /// can be used to detach, see [LwtXmit::detach].
+    ///
+    /// For more control over the route, see [`LwtXmit::attach_with_options`].
+    pub fn attach(
+        &mut self,
+        interface: &str,
+        dst: IpAddr,
+        prefix_len: u8,
+        headroom: u32,
+    ) -> Result<LwtXmitLinkId, ProgramError> {
+        self.attach_with_options(
+            interface,
+            dst,
+            prefix_len,
+            headroom,
+            &LwtRouteOptions::default(),
+        )
+    }
+
+    /// Attaches the program to the route to `dst`/`prefix_len` through the
+    /// given `interface`, as described by `options`.
+    ///
+    /// `headroom` is the number of bytes the kernel reserves in front of the
+    /// packets for the headers pushed by the program.
+    ///
+    /// The returned value can be used to detach, see [LwtXmit::detach].
+    pub fn attach_with_options(
+        &mut self,
+        interface: &str,
+        dst: IpAddr,
+        prefix_len: u8,
+        headroom: u32,
+        options: &LwtRouteOptions,
+    ) -> Result<LwtXmitLinkId, ProgramError> {
+        let link = attach_route(
+            &self.data,
+            interface,
+            dst,
+            prefix_len,
+            options,
+            LwtHook::Xmit { headroom },
+        )?;
+        self.data.links.insert(LwtXmitLink::new(link))
+    }
+}
+
+/// A program implementing an SRv6 local segment.
+///
+/// [`LwtSeg6Local`] programs are attached to IPv6 routes as the `End.BPF`
+/// action of a segment routing local segment, and run on the packets whose
+/// active segment is the route destination. They can modify the segment
+/// routing header with the `bpf_lwt_seg6_*` helpers.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.18.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::net::Ipv6Addr;
+/// use aya::programs::LwtSeg6Local;
+///
+/// let prog: &mut LwtSeg6Local = bpf.program_mut("end_bpf").unwrap().try_into()?;
+/// prog.load()?;
+/// prog.attach("eth0", Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 1).into(), 128)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_PROG_TYPE_LWT_SEG6LOCAL")]
+pub struct LwtSeg6Local {
+    pub(crate) data: ProgramData<LwtSeg6LocalLink>,
+}
+
+impl LwtSeg6Local {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::LwtSeg6local;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        load_program(BPF_PROG_TYPE_LWT_SEG6LOCAL, &mut self.data)
+    }
+
+    /// Attaches the program to a new IPv6 route to `dst`/`prefix_len` through
+    /// the given `interface`.
+    ///
+    /// The route is deleted when the program is detached. The returned value
+    /// can be used to detach, see [LwtSeg6Local::detach].
+    ///
+    /// For more control over the route, see
+    /// [`LwtSeg6Local::attach_with_options`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LwtError::Ipv4Route`] if `dst` is an IPv4 address.
+    pub fn attach(
+        &mut self,
+        interface: &str,
+        dst: IpAddr,
+        prefix_len: u8,
+    ) -> Result<LwtSeg6LocalLinkId, ProgramError> {
+        self.attach_with_options(interface, dst, prefix_len, &LwtRouteOptions::default())
+    }
+
+    /// Attaches the program to the IPv6 route to `dst`/`prefix_len` through
+    /// the given `interface`, as described by `options`.
+    ///
+    /// The returned value can be used to detach, see [LwtSeg6Local::detach].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LwtError::Ipv4Route`] if `dst` is an IPv4 address.
+    pub fn attach_with_options(
+        &mut self,
+        interface: &str,
+        dst: IpAddr,
+        prefix_len: u8,
+        options: &LwtRouteOptions,
+    ) -> Result<LwtSeg6LocalLinkId, ProgramError> {
+        if dst.is_ipv4() {
+            return Err(LwtError::Ipv4Route { dst }.into());
+        }
+        let link = attach_route(
+            &self.data,
+            interface,
+            dst,
+            prefix_len,
+            options,
+            LwtHook::Seg6Local,
+        )?;
+        self.data.links.insert(LwtSeg6LocalLink::new(link))
+    }
+}
+
+fn attach_route<T: Link>(
+    data: &ProgramData<T>,
+    interface: &str,
+    dst: IpAddr,
+    prefix_len: u8,
+    options: &LwtRouteOptions,
+    hook: LwtHook,
+) -> Result<RouteLink, ProgramError> {
+    let prog_fd = data.fd()?;
+    let prog_fd = prog_fd.as_fd();
+    let if_index = ifindex_from_ifname(interface).map_err(|_: std::io::Error| {
+        ProgramError::UnknownInterface {
+            name: interface.to_string(),
+        }
+    })?;
+    let name = data.name.as_deref().unwrap_or_default();
+    // The kernel only uses the name to identify the program when dumping the
+    // route, so it is truncated like the names of programs.
+    let name = &name.as_bytes()[..name.len().min(BPF_OBJ_NAME_LEN as usize - 1)];
+    let name = CString::new(name).map_err(|_: std::ffi::NulError| ProgramError::InvalidName {
+        name: String::from_utf8_lossy(name).into_owned(),
+    })?;
+    let LwtRouteOptions {
+        table,
+        gateway,
+        scope,
+        replace,
+    } = *options;
+    let scope = scope.unwrap_or(match gateway {
+        // Routes without a gateway are directly reachable through the interface.
+        None => RouteScope::Link,
+        Some(_) => RouteScope::Universe,
+    });
+    let route = Route {
+        if_index: if_index as i32,
+        dst,
+        prefix_len,
+        table: table.unwrap_or(RT_TABLE_MAIN.into()),
+        gateway,
+        scope: scope.into(),
+    };
+    unsafe { netlink_route_add_lwt(&route, replace, &hook, prog_fd, &name) }
+        .map_err(ProgramError::NetlinkError)?;
+
+    Ok(RouteLink {
+        route,
+        hook,
+        name,
+        replace,
+    })
+}
+
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub(crate) struct RouteLinkId(u32, IpAddr, u8, u32);
+
+/// A route running a lightweight tunnel program.
+#[derive(Debug)]
+pub(crate) struct RouteLink {
+    route: Route,
+    hook: LwtHook,
+    name: CString,
+    /// Whether the program was attached by replacing an existing route.
+    replace: bool,
+}
+
+impl Link for RouteLink {
+    type Id = RouteLinkId;
+
+    fn id(&self) -> Self::Id {
+        let Route {
+            if_index,
+            dst,
+            prefix_len,
+            table,
+            ..
+        } = self.route;
+        RouteLinkId(if_index as u32, dst, prefix_len, table)
+    }
+
+    fn detach(self) -> Result<(), ProgramError> {
+        let Self {
+            route,
+            hook,
+            name,
+            replace,
+        } = self;
+        // The route may have been replaced since the program was attached, in
+        // which case it isn't ours to remove.
+        let prog_name = unsafe { netlink_route_lwt_prog_name(&route, &hook) }
+            .map_err(ProgramError::NetlinkError)?;
+        if prog_name.as_deref() != Some(name.as_c_str()) {
+            return Err(LwtError::RouteChanged {
+                dst: route.dst,
+                prefix_len: route.prefix_len,
+            }
+            .into());
+        }
+        if replace {
+            unsafe { netlink_route_replace(&route) }
+        } else {
+            unsafe { netlink_route_del(&route) }
+        }
+        .map_err(ProgramError::NetlinkError)?;
+        Ok(())
+    }
+}
+
+id_as_key!(RouteLink, RouteLinkId);
+
+define_link_wrapper!(LwtInLink, LwtInLinkId, RouteLink, RouteLinkId, LwtIn);
+define_link_wrapper!(LwtOutLink, LwtOutLinkId, RouteLink, RouteLinkId, LwtOut);
+define_link_wrapper!(LwtXmitLink, LwtXmitLinkId, RouteLink, RouteLinkId, LwtXmit);
+define_link_wrapper!(
+    LwtSeg6LocalLink,
+    LwtSeg6LocalLinkId,
+    RouteLink,
+    RouteLinkId,
+    LwtSeg6Local
+);
@@ -52,23 +52,34 @@ pub mod extension;
 pub mod fentry;
 pub mod fexit;
 pub mod flow_dissector;
+pub mod fmod_ret;
 pub mod iter;
 pub mod kprobe;
+pub mod kprobe_multi;
 pub mod links;
 pub mod lirc_mode2;
 pub mod lsm;
+pub mod lsm_cgroup;
+pub mod lwt;
+pub mod netfilter;
 pub mod perf_attach;
 pub mod perf_event;
 pub mod raw_trace_point;
 pub mod sk_lookup;
 pub mod sk_msg;
+pub mod sk_reuseport;
 pub mod sk_skb;
 pub mod sock_ops;
 pub mod socket_filter;
+pub mod struct_ops;
+pub mod syscall;
 pub mod tc;
+pub mod test_run;
 pub mod tp_btf;
 pub mod trace_point;
 pub mod uprobe;
+pub mod uprobe_multi;
+pub mod usdt;
 pub mod xdp;
 
 use std::{
@@ -104,23 +115,36 @@ pub use crate::programs::{
     fentry::FEntry,
     fexit::FExit,
     flow_dissector::FlowDissector,
-    iter::Iter,
+    fmod_ret::FModRet,
+    iter::{CgroupIterOrder, CgroupIterTarget, Iter, IterLinkOptions, TaskIterTarget},
     kprobe::{KProbe, KProbeError},
+    kprobe_multi::{KProbeMulti, KProbeMultiError},
     links::{CgroupAttachMode, Link, LinkOrder},
     lirc_mode2::LircMode2,
     lsm::Lsm,
+    lsm_cgroup::LsmCgroup,
+    lwt::{LwtError, LwtIn, LwtOut, LwtRouteOptions, LwtSeg6Local, LwtXmit, RouteScope},
+    netfilter::{
+        Netfilter, NetfilterError, NetfilterFlags, NetfilterHook, NetfilterProtocolFamily,
+    },
     perf_event::{PerfEvent, PerfEventScope, PerfTypeId, SamplePolicy},
     probe::ProbeKind,
     raw_trace_point::RawTracePoint,
     sk_lookup::SkLookup,
     sk_msg::SkMsg,
+    sk_reuseport::{SkReuseport, SkReuseportError},
     sk_skb::{SkSkb, SkSkbKind},
     sock_ops::SockOps,
     socket_filter::{SocketFilter, SocketFilterError},
-    tc::{SchedClassifier, TcAttachType, TcError},
+    struct_ops::{StructOps, StructOpsError},
+    syscall::Syscall,
+    tc::{NetkitAttachType, SchedClassifier, TcAttachType, TcError},
+    test_run::{TestRunOptions, TestRunOutput},
     tp_btf::BtfTracePoint,
     trace_point::{TracePoint, TracePointError},
     uprobe::{UProbe, UProbeError},
+    uprobe_multi::{UProbeMulti, UProbeMultiError},
+    usdt::{Usdt, UsdtError},
     xdp::{Xdp, XdpError, XdpFlags},
 };
 use crate::{
@@ -188,10 +212,22 @@ pub enum ProgramError {
     #[error(transparent)]
     KProbeError(#[from] KProbeError),
 
+    /// An error occurred while working with a [`KProbeMulti`].
+    #[error(transparent)]
+    KProbeMultiError(#[from] KProbeMultiError),
+
     /// An error occurred while working with an [`UProbe`].
     #[error(transparent)]
     UProbeError(#[from] UProbeError),
 
+    /// An error occurred while working with an [`UProbeMulti`].
+    #[error(transparent)]
+    UProbeMultiError(#[from] UProbeMultiError),
+
+    /// An error occurred while working with a [`Usdt`].
+    #[error(transparent)]
+    UsdtError(#[from] UsdtError),
+
     /// An error occurred while working with a [`TracePoint`].
     #[error(transparent)]
     TracePointError(#[from] TracePointError),
@@ -200,6 +236,10 @@ pub enum ProgramError {
     #[error(transparent)]
     SocketFilterError(#[from] SocketFilterError),
 
+    /// An error occurred while working with an [`SkReuseport`] program.
+    #[error(transparent)]
+    SkReuseportError(#[from] SkReuseportError),
+
     /// An error occurred while working with an [`Xdp`] program.
     #[error(transparent)]
     XdpError(#[from] XdpError),
@@ -208,10 +248,22 @@ pub enum ProgramError {
     #[error(transparent)]
     TcError(#[from] TcError),
 
+    /// An error occurred while working with a lightweight tunnel program.
+    #[error(transparent)]
+    LwtError(#[from] LwtError),
+
+    /// An error occurred while working with a [`Netfilter`] program.
+    #[error(transparent)]
+    NetfilterError(#[from] NetfilterError),
+
     /// An error occurred while working with an [`Extension`] program.
     #[error(transparent)]
     ExtensionError(#[from] ExtensionError),
 
+    /// An error occurred while working with a [`StructOps`] program.
+    #[error(transparent)]
+    StructOpsError(#[from] StructOpsError),
+
     /// An error occurred while working with BTF.
     #[error(transparent)]
     Btf(#[from] BtfError),
@@ -231,6 +283,10 @@ pub enum ProgramError {
     #[error("providing an attach cookie is not supported")]
     AttachCookieNotSupported,
 
+    /// The target eBPF program doesn't have BTF loaded to the kernel.
+    #[error("the target BPF program does not have BTF loaded to the kernel")]
+    TargetProgramNoBtf,
+
     /// An error occurred while working with Netlink.
     #[error(transparent)]
     NetlinkError(#[from] NetlinkError),
@@ -325,6 +381,32 @@ pub enum Program {
     CgroupDevice(CgroupDevice),
     /// An [`Iter`] program
     Iter(Iter),
+    /// An [`LwtIn`] program
+    LwtIn(LwtIn),
+    /// An [`LwtOut`] program
+    LwtOut(LwtOut),
+    /// An [`LwtXmit`] program
+    LwtXmit(LwtXmit),
+    /// An [`LwtSeg6Local`] program
+    LwtSeg6Local(LwtSeg6Local),
+    /// A [`Netfilter`] program
+    Netfilter(Netfilter),
+    /// A [`Syscall`] program
+    Syscall(Syscall),
+    /// An [`LsmCgroup`] program
+    LsmCgroup(LsmCgroup),
+    /// An [`SkReuseport`] program
+    SkReuseport(SkReuseport),
+    /// An [`FModRet`] program
+    FModRet(FModRet),
+    /// A [`StructOps`] program
+    StructOps(StructOps),
+    /// An [`UProbeMulti`] program
+    UProbeMulti(UProbeMulti),
+    /// A [`Usdt`] program
+    Usdt(Usdt),
+    /// A [`KProbeMulti`] program
+    KProbeMulti(KProbeMulti),
 }
 
 impl Program {
@@ -345,7 +427,13 @@ impl Program {
             Self::CgroupSockopt(_) => CgroupSockopt::PROGRAM_TYPE,
             Self::LircMode2(_) => LircMode2::PROGRAM_TYPE,
             Self::PerfEvent(_) => PerfEvent::PROGRAM_TYPE,
-            Self::RawTracePoint(_) => RawTracePoint::PROGRAM_TYPE,
+            Self::RawTracePoint(p) => {
+                if p.writable {
+                    ProgramType::RawTracePointWritable
+                } else {
+                    RawTracePoint::PROGRAM_TYPE
+                }
+            }
             Self::Lsm(_) => Lsm::PROGRAM_TYPE,
             Self::BtfTracePoint(_) => BtfTracePoint::PROGRAM_TYPE,
             Self::FEntry(_) => FEntry::PROGRAM_TYPE,
@@ -356,6 +444,19 @@ impl Program {
             Self::CgroupSock(_) => CgroupSock::PROGRAM_TYPE,
             Self::CgroupDevice(_) => CgroupDevice::PROGRAM_TYPE,
             Self::Iter(_) => Iter::PROGRAM_TYPE,
+            Self::LwtIn(_) => LwtIn::PROGRAM_TYPE,
+            Self::LwtOut(_) => LwtOut::PROGRAM_TYPE,
+            Self::LwtXmit(_) => LwtXmit::PROGRAM_TYPE,
+            Self::LwtSeg6Local(_) => LwtSeg6Local::PROGRAM_TYPE,
+            Self::Netfilter(_) => Netfilter::PROGRAM_TYPE,
+            Self::Syscall(_) => Syscall::PROGRAM_TYPE,
+            Self::LsmCgroup(_) => LsmCgroup::PROGRAM_TYPE,
+            Self::SkReuseport(_) => SkReuseport::PROGRAM_TYPE,
+            Self::FModRet(_) => FModRet::PROGRAM_TYPE,
+            Self::StructOps(_) => StructOps::PROGRAM_TYPE,
+            Self::UProbeMulti(_) => UProbeMulti::PROGRAM_TYPE,
+            Self::Usdt(_) => Usdt::PROGRAM_TYPE,
+            Self::KProbeMulti(_) => KProbeMulti::PROGRAM_TYPE,
             Self::FlowDissector(_) => FlowDissector::PROGRAM_TYPE,
         }
     }
@@ -389,6 +490,19 @@ impl Program {
             Self::CgroupSock(p) => p.pin(path),
             Self::CgroupDevice(p) => p.pin(path),
             Self::Iter(p) => p.pin(path),
+            Self::LwtIn(p) => p.pin(path),
+            Self::LwtOut(p) => p.pin(path),
+            Self::LwtXmit(p) => p.pin(path),
+            Self::LwtSeg6Local(p) => p.pin(path),
+            Self::Netfilter(p) => p.pin(path),
+            Self::Syscall(p) => p.pin(path),
+            Self::LsmCgroup(p) => p.pin(path),
+            Self::SkReuseport(p) => p.pin(path),
+            Self::FModRet(p) => p.pin(path),
+            Self::StructOps(p) => p.pin(path),
+            Self::UProbeMulti(p) => p.pin(path),
+            Self::Usdt(p) => p.pin(path),
+            Self::KProbeMulti(p) => p.pin(path),
         }
     }
 
@@ -421,6 +535,19 @@ impl Program {
             Self::CgroupSock(mut p) => p.unload(),
             Self::CgroupDevice(mut p) => p.unload(),
             Self::Iter(mut p) => p.unload(),
+            Self::LwtIn(mut p) => p.unload(),
+            Self::LwtOut(mut p) => p.unload(),
+            Self::LwtXmit(mut p) => p.unload(),
+            Self::LwtSeg6Local(mut p) => p.unload(),
+            Self::Netfilter(mut p) => p.unload(),
+            Self::Syscall(mut p) => p.unload(),
+            Self::LsmCgroup(mut p) => p.unload(),
+            Self::SkReuseport(mut p) => p.unload(),
+            Self::FModRet(mut p) => p.unload(),
+            Self::StructOps(mut p) => p.unload(),
+            Self::UProbeMulti(mut p) => p.unload(),
+            Self::Usdt(mut p) => p.unload(),
+            Self::KProbeMulti(mut p) => p.unload(),
         }
     }
 
@@ -455,6 +582,19 @@ impl Program {
             Self::CgroupSock(p) => p.fd(),
             Self::CgroupDevice(p) => p.fd(),
             Self::Iter(p) => p.fd(),
+            Self::LwtIn(p) => p.fd(),
+            Self::LwtOut(p) => p.fd(),
+            Self::LwtXmit(p) => p.fd(),
+            Self::LwtSeg6Local(p) => p.fd(),
+            Self::Netfilter(p) => p.fd(),
+            Self::Syscall(p) => p.fd(),
+            Self::LsmCgroup(p) => p.fd(),
+            Self::SkReuseport(p) => p.fd(),
+            Self::FModRet(p) => p.fd(),
+            Self::StructOps(p) => p.fd(),
+            Self::UProbeMulti(p) => p.fd(),
+            Self::Usdt(p) => p.fd(),
+            Self::KProbeMulti(p) => p.fd(),
         }
     }
 
@@ -490,6 +630,19 @@ impl Program {
             Self::CgroupSock(p) => p.info(),
             Self::CgroupDevice(p) => p.info(),
             Self::Iter(p) => p.info(),
+            Self::LwtIn(p) => p.info(),
+            Self::LwtOut(p) => p.info(),
+            Self::LwtXmit(p) => p.info(),
+            Self::LwtSeg6Local(p) => p.info(),
+            Self::Netfilter(p) => p.info(),
+            Self::Syscall(p) => p.info(),
+            Self::LsmCgroup(p) => p.info(),
+            Self::SkReuseport(p) => p.info(),
+            Self::FModRet(p) => p.info(),
+            Self::StructOps(p) => p.info(),
+            Self::UProbeMulti(p) => p.info(),
+            Self::Usdt(p) => p.info(),
+            Self::KProbeMulti(p) => p.info(),
         }
     }
 }
@@ -624,13 +777,27 @@ fn pin_program<T: Link, P: AsRef<Path>>(data: &ProgramData<T>, path: P) -> Resul
 fn load_program<T: Link>(
     prog_type: bpf_prog_type,
     data: &mut ProgramData<T>,
+) -> Result<(), ProgramError> {
+    let expected_attach_type = data.expected_attach_type.map(|t| t as u32);
+    load_program_with_attach_type(prog_type, expected_attach_type, data)
+}
+
+/// Loads a program with a raw `expected_attach_type`.
+///
+/// This is needed by program types which overload `expected_attach_type` with
+/// values which aren't a valid [`bpf_attach_type`], such as struct_ops
+/// programs which use it to pass the index of the member they implement.
+fn load_program_with_attach_type<T: Link>(
+    prog_type: bpf_prog_type,
+    expected_attach_type: Option<u32>,
+    data: &mut ProgramData<T>,
 ) -> Result<(), ProgramError> {
     let ProgramData {
         name,
         obj,
         fd,
         links: _,
-        expected_attach_type,
+        expected_attach_type: _,
         attach_btf_obj_fd,
         attach_btf_id,
         attach_prog_fd,
@@ -686,7 +853,7 @@ fn load_program<T: Link>(
         insns: instructions,
         license,
         kernel_version: target_kernel_version,
-        expected_attach_type: *expected_attach_type,
+        expected_attach_type,
         prog_btf_fd: btf_fd.as_ref().map(|f| f.as_fd()),
         attach_btf_obj_fd: attach_btf_obj_fd.as_ref().map(|fd| fd.as_fd()),
         attach_btf_id: *attach_btf_id,
@@ -806,6 +973,19 @@ impl_program_unload!(
     CgroupSock,
     CgroupDevice,
     Iter,
+    KProbeMulti,
+    UProbeMulti,
+    Usdt,
+    StructOps,
+    FModRet,
+    SkReuseport,
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
+    Netfilter,
+    Syscall,
+    LsmCgroup,
 );
 
 macro_rules! impl_fd {
@@ -848,6 +1028,51 @@ impl_fd!(
     CgroupSock,
     CgroupDevice,
     Iter,
+    KProbeMulti,
+    UProbeMulti,
+    Usdt,
+    StructOps,
+    FModRet,
+    SkReuseport,
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
+    Netfilter,
+    Syscall,
+    LsmCgroup,
+);
+
+macro_rules! impl_test_run {
+    ($($struct_name:ident),+ $(,)?) => {
+        $(
+            impl $struct_name {
+                /// Runs the program in the kernel with the given options, without attaching it.
+                ///
+                /// See [`TestRunOptions`] for details.
+                pub fn test_run(
+                    &self,
+                    options: TestRunOptions<'_>,
+                ) -> Result<TestRunOutput, ProgramError> {
+                    options.run(self.fd()?.as_fd())
+                }
+            }
+        )+
+    }
+}
+
+impl_test_run!(
+    SocketFilter,
+    Xdp,
+    SchedClassifier,
+    CgroupSkb,
+    RawTracePoint,
+    FlowDissector,
+    SkLookup,
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
 );
 
 /// Trait implemented by the [`Program`] types which support the kernel's
@@ -955,6 +1180,19 @@ impl_program_pin!(
     CgroupSock,
     CgroupDevice,
     Iter,
+    KProbeMulti,
+    UProbeMulti,
+    Usdt,
+    StructOps,
+    FModRet,
+    SkReuseport,
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
+    Netfilter,
+    Syscall,
+    LsmCgroup,
 );
 
 macro_rules! impl_from_pin {
@@ -985,7 +1223,6 @@ impl_from_pin!(
     LircMode2,
     PerfEvent,
     Lsm,
-    RawTracePoint,
     BtfTracePoint,
     FEntry,
     FExit,
@@ -995,6 +1232,15 @@ impl_from_pin!(
     SockOps,
     CgroupDevice,
     Iter,
+    FModRet,
+    SkReuseport,
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
+    Netfilter,
+    Syscall,
+    LsmCgroup,
 );
 
 macro_rules! impl_from_prog_info {
@@ -1026,7 +1272,7 @@ macro_rules! impl_from_prog_info {
                 if info.program_type()? != Self::PROGRAM_TYPE {
                     return Err(ProgramError::UnexpectedProgramType {});
                 }
-                let ProgramInfo(bpf_progam_info) = info;
+                let ProgramInfo(bpf_prog_info) = info;
                 let fd = info.fd()?;
                 let fd = fd.as_fd().try_clone_to_owned()?;
 
@@ -1035,7 +1281,7 @@ macro_rules! impl_from_prog_info {
                         Some(name),
                         crate::MockableFd::from_fd(fd),
                         Path::new(""),
-                        bpf_progam_info,
+                        bpf_prog_info,
                         VerifierLogLevel::default(),
                     )?,
                     $($var,)?
@@ -1095,7 +1341,6 @@ impl_from_prog_info!(
     LircMode2,
     PerfEvent,
     Lsm,
-    RawTracePoint,
     unsafe BtfTracePoint,
     unsafe FEntry,
     unsafe FExit,
@@ -1103,6 +1348,17 @@ impl_from_prog_info!(
     SkLookup,
     CgroupDevice,
     Iter,
+    unsafe KProbeMulti kind : ProbeKind,
+    unsafe UProbeMulti kind : ProbeKind,
+    unsafe FModRet,
+    SkReuseport,
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
+    Netfilter,
+    Syscall,
+    LsmCgroup,
 );
 
 macro_rules! impl_try_from_program {
@@ -1160,6 +1416,19 @@ impl_try_from_program!(
     CgroupSock,
     CgroupDevice,
     Iter,
+    KProbeMulti,
+    UProbeMulti,
+    Usdt,
+    StructOps,
+    FModRet,
+    SkReuseport,
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
+    Netfilter,
+    Syscall,
+    LsmCgroup,
 );
 
 impl_info!(
@@ -1189,6 +1458,19 @@ impl_info!(
     CgroupSock,
     CgroupDevice,
     Iter,
+    KProbeMulti,
+    UProbeMulti,
+    Usdt,
+    StructOps,
+    FModRet,
+    SkReuseport,
+    LwtIn,
+    LwtOut,
+    LwtXmit,
+    LwtSeg6Local,
+    Netfilter,
+    Syscall,
+    LsmCgroup,
 );
 
 /// Returns an iterator over all loaded links.
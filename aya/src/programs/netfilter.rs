@@ -0,0 +1,175 @@
+//! Netfilter programs.
+use std::os::fd::AsFd as _;
+
+use aya_obj::generated::{
+    BPF_F_NETFILTER_IP_DEFRAG, NFPROTO_IPV4, NFPROTO_IPV6, bpf_attach_type::BPF_NETFILTER,
+    bpf_prog_type::BPF_PROG_TYPE_NETFILTER, nf_inet_hooks,
+};
+use thiserror::Error;
+
+use crate::{
+    programs::{
+        FdLink, FdLinkId, ProgramData, ProgramError, ProgramType, define_link_wrapper, load_program,
+    },
+    sys::{BpfLinkCreateArgs, LinkTarget, SyscallError, bpf_link_create},
+};
+
+/// The type returned when attaching a [`Netfilter`] fails.
+#[derive(Debug, Error)]
+pub enum NetfilterError {
+    /// The priority is reserved by the kernel.
+    #[error("invalid netfilter priority {priority}: `i32::MIN` and `i32::MAX` are reserved")]
+    InvalidPriority {
+        /// The rejected priority.
+        priority: i32,
+    },
+}
+
+/// The protocol family of the packets a [`Netfilter`] program runs on.
+#[derive(Clone, Copy, Debug)]
+pub enum NetfilterProtocolFamily {
+    /// IPv4 packets.
+    Ipv4,
+    /// IPv6 packets.
+    Ipv6,
+}
+
+impl From<NetfilterProtocolFamily> for u32 {
+    fn from(pf: NetfilterProtocolFamily) -> Self {
+        match pf {
+            NetfilterProtocolFamily::Ipv4 => NFPROTO_IPV4,
+            NetfilterProtocolFamily::Ipv6 => NFPROTO_IPV6,
+        }
+    }
+}
+
+/// The netfilter hook a [`Netfilter`] program runs at.
+#[derive(Clone, Copy, Debug)]
+pub enum NetfilterHook {
+    /// Packets entering the host, before the routing decision.
+    PreRouting,
+    /// Packets destined to the local host.
+    LocalIn,
+    /// Packets routed through the host.
+    Forward,
+    /// Packets sent by the local host.
+    LocalOut,
+    /// Packets leaving the host, after the routing decision.
+    PostRouting,
+}
+
+impl From<NetfilterHook> for u32 {
+    fn from(hook: NetfilterHook) -> Self {
+        let hook = match hook {
+            NetfilterHook::PreRouting => nf_inet_hooks::NF_INET_PRE_ROUTING,
+            NetfilterHook::LocalIn => nf_inet_hooks::NF_INET_LOCAL_IN,
+            NetfilterHook::Forward => nf_inet_hooks::NF_INET_FORWARD,
+            NetfilterHook::LocalOut => nf_inet_hooks::NF_INET_LOCAL_OUT,
+            NetfilterHook::PostRouting => nf_inet_hooks::NF_INET_POST_ROUTING,
+        };
+        hook as Self
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags passed to [`Netfilter::attach()`].
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct NetfilterFlags: u32 {
+        /// Run the program on defragmented packets.
+        const IP_DEFRAG = BPF_F_NETFILTER_IP_DEFRAG;
+    }
+}
+
+/// A program that runs at a netfilter hook.
+///
+/// [`Netfilter`] programs are attached to a netfilter hook of a protocol
+/// family, like iptables rules, and decide whether the packets traversing the
+/// hook are accepted or dropped.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 6.4.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use aya::programs::{Netfilter, NetfilterFlags, NetfilterHook, NetfilterProtocolFamily};
+///
+/// let program: &mut Netfilter = bpf.program_mut("filter").unwrap().try_into()?;
+/// program.load()?;
+/// program.attach(
+///     NetfilterProtocolFamily::Ipv4,
+///     NetfilterHook::LocalIn,
+///     -128,
+///     NetfilterFlags::default(),
+/// )?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_PROG_TYPE_NETFILTER")]
+pub struct Netfilter {
+    pub(crate) data: ProgramData<NetfilterLink>,
+}
+
+impl Netfilter {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::Netfilter;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        self.data.expected_attach_type = Some(BPF_NETFILTER);
+        load_program(BPF_PROG_TYPE_NETFILTER, &mut self.data)
+    }
+
+    /// Attaches the program to a netfilter hook.
+    ///
+    /// Programs attached to the same hook run in ascending order of
+    /// `priority`. `i32::MIN` and `i32::MAX` are reserved by the kernel and
+    /// are rejected with [`NetfilterError::InvalidPriority`].
+    ///
+    /// The returned value can be used to detach, see [Netfilter::detach].
+    pub fn attach(
+        &mut self,
+        pf: NetfilterProtocolFamily,
+        hook: NetfilterHook,
+        priority: i32,
+        flags: NetfilterFlags,
+    ) -> Result<NetfilterLinkId, ProgramError> {
+        if priority == i32::MIN || priority == i32::MAX {
+            return Err(NetfilterError::InvalidPriority { priority }.into());
+        }
+
+        let prog_fd = self.fd()?;
+        let prog_fd = prog_fd.as_fd();
+
+        let link_fd = bpf_link_create(
+            prog_fd,
+            LinkTarget::None,
+            BPF_NETFILTER,
+            0,
+            Some(BpfLinkCreateArgs::Netfilter {
+                pf: pf.into(),
+                hooknum: hook.into(),
+                priority,
+                flags: flags.bits(),
+            }),
+        )
+        .map_err(|io_error| SyscallError {
+            call: "bpf_link_create",
+            io_error,
+        })?;
+        self.data
+            .links
+            .insert(NetfilterLink::new(FdLink::new(link_fd)))
+    }
+}
+
+define_link_wrapper!(NetfilterLink, NetfilterLinkId, FdLink, FdLinkId, Netfilter);
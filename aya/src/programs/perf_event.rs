@@ -17,6 +17,7 @@ pub use aya_obj::generated::{
 use crate::{
     programs::{
         FdLink, LinkError, ProgramData, ProgramError, ProgramType, impl_try_into_fdlink,
+        impl_update_program,
         links::define_link_wrapper,
         load_program, perf_attach,
         perf_attach::{PerfLinkIdInner, PerfLinkInner},
@@ -189,6 +190,7 @@ impl PerfEvent {
 }
 
 impl_try_into_fdlink!(PerfEventLink, PerfLinkInner);
+impl_update_program!(PerfEventLink, PerfLinkInner);
 
 impl TryFrom<FdLink> for PerfEventLink {
     type Error = LinkError;
@@ -125,7 +125,7 @@ pub(crate) fn attach<T: Link + From<PerfLinkInner>>(
         let (fd, event_alias) = create_as_trace_point(kind, fn_name, offset, pid)?;
         perf_attach_debugfs(prog_fd, fd, ProbeEvent { kind, event_alias })
     } else {
-        let fd = create_as_probe(kind, fn_name, offset, pid)?;
+        let fd = create_as_probe(kind, fn_name, offset, None, pid)?;
         perf_attach(prog_fd, fd, cookie)
     }?;
     program_data.links.insert(T::from(link))
@@ -149,10 +149,15 @@ pub(crate) fn detach_debug_fs(event: ProbeEvent) -> Result<(), ProgramError> {
     })
 }
 
-fn create_as_probe(
+/// Opens a kprobe or uprobe perf event.
+///
+/// `ref_ctr_offset` is the file offset of a uprobe's reference counter (eg: a USDT semaphore),
+/// which the kernel increments while the probe is active.
+pub(crate) fn create_as_probe(
     kind: ProbeKind,
     fn_name: &OsStr,
     offset: u64,
+    ref_ctr_offset: Option<u64>,
     pid: Option<pid_t>,
 ) -> Result<crate::MockableFd, ProgramError> {
     use ProbeKind::*;
@@ -176,7 +181,7 @@ fn create_as_probe(
         _ => None,
     };
 
-    perf_event_open_probe(perf_ty, ret_bit, fn_name, offset, pid)
+    perf_event_open_probe(perf_ty, ret_bit, fn_name, offset, ref_ctr_offset, pid)
         .map_err(|io_error| SyscallError {
             call: "perf_event_open",
             io_error,
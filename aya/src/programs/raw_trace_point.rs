@@ -1,11 +1,16 @@
 //! Raw tracepoints.
-use std::ffi::CString;
+use std::{borrow::Cow, ffi::CString, os::fd::AsFd as _, path::Path};
 
-use aya_obj::generated::bpf_prog_type::BPF_PROG_TYPE_RAW_TRACEPOINT;
+use aya_obj::generated::bpf_prog_type::{
+    BPF_PROG_TYPE_RAW_TRACEPOINT, BPF_PROG_TYPE_RAW_TRACEPOINT_WRITABLE,
+};
 
-use crate::programs::{
-    FdLink, FdLinkId, ProgramData, ProgramError, ProgramType, define_link_wrapper, load_program,
-    utils::attach_raw_tracepoint,
+use crate::{
+    VerifierLogLevel,
+    programs::{
+        FdLink, FdLinkId, ProgramData, ProgramError, ProgramInfo, ProgramType, define_link_wrapper,
+        load_program, utils::attach_raw_tracepoint,
+    },
 };
 
 /// A program that can be attached at a pre-defined kernel trace point.
@@ -17,9 +22,17 @@ use crate::programs::{
 /// be attached to. See`/sys/kernel/debug/tracing/events` for a list of which
 /// events can be traced.
 ///
+/// Programs in `raw_tp.w` or `raw_tracepoint.w` sections are loaded as writable
+/// raw tracepoints, which can write to the buffer that some tracepoints, like
+/// the `nbd` ones, pass as their first argument. The kernel verifier records
+/// the largest context offset and buffer offset accessed by the program while
+/// loading it, and attaching fails if the tracepoint has fewer arguments or a
+/// smaller writable buffer.
+///
 /// # Minimum kernel version
 ///
-/// The minimum kernel version required to use this feature is 4.17.
+/// The minimum kernel version required to use this feature is 4.17, or 5.2
+/// for writable raw tracepoints.
 ///
 /// # Examples
 ///
@@ -34,17 +47,33 @@ use crate::programs::{
 /// ```
 #[derive(Debug)]
 #[doc(alias = "BPF_PROG_TYPE_RAW_TRACEPOINT")]
+#[doc(alias = "BPF_PROG_TYPE_RAW_TRACEPOINT_WRITABLE")]
 pub struct RawTracePoint {
     pub(crate) data: ProgramData<RawTracePointLink>,
+    pub(crate) writable: bool,
 }
 
 impl RawTracePoint {
     /// The type of the program according to the kernel.
+    ///
+    /// Writable raw tracepoints have the
+    /// [`ProgramType::RawTracePointWritable`] type instead, see
+    /// [`RawTracePoint::writable`].
     pub const PROGRAM_TYPE: ProgramType = ProgramType::RawTracePoint;
 
+    /// Returns whether the program is a writable raw tracepoint.
+    pub fn writable(&self) -> bool {
+        self.writable
+    }
+
     /// Loads the program inside the kernel.
     pub fn load(&mut self) -> Result<(), ProgramError> {
-        load_program(BPF_PROG_TYPE_RAW_TRACEPOINT, &mut self.data)
+        let prog_type = if self.writable {
+            BPF_PROG_TYPE_RAW_TRACEPOINT_WRITABLE
+        } else {
+            BPF_PROG_TYPE_RAW_TRACEPOINT
+        };
+        load_program(prog_type, &mut self.data)
     }
 
     /// Attaches the program to the given tracepoint.
@@ -54,6 +83,55 @@ impl RawTracePoint {
         let tp_name_c = CString::new(tp_name).unwrap();
         attach_raw_tracepoint(&mut self.data, Some(&tp_name_c))
     }
+
+    /// Creates a program from a pinned entry on a bpffs.
+    ///
+    /// Existing links will not be populated. To work with existing links you should use [`crate::programs::links::PinnedLink`].
+    ///
+    /// On drop, any managed links are detached and the program is unloaded. This will not result in
+    /// the program being unloaded from the kernel if it is still pinned.
+    pub fn from_pin<P: AsRef<Path>>(path: P) -> Result<Self, ProgramError> {
+        let data = ProgramData::from_pinned_path(path, VerifierLogLevel::default())?;
+        let info = ProgramInfo::new_from_fd(data.fd()?.as_fd())?;
+        let writable = info.program_type()? == ProgramType::RawTracePointWritable;
+        Ok(Self { data, writable })
+    }
+
+    /// Constructs an instance of a [`Self`] from a [`ProgramInfo`].
+    ///
+    /// This allows the caller to get a handle to an already loaded
+    /// program from the kernel without having to load it again.
+    ///
+    /// # Errors
+    ///
+    /// - If the program type reported by the kernel is neither
+    ///   [`ProgramType::RawTracePoint`] nor
+    ///   [`ProgramType::RawTracePointWritable`].
+    /// - If the file descriptor of the program cannot be cloned.
+    pub fn from_program_info(
+        info: ProgramInfo,
+        name: Cow<'static, str>,
+    ) -> Result<Self, ProgramError> {
+        let writable = match info.program_type()? {
+            ProgramType::RawTracePoint => false,
+            ProgramType::RawTracePointWritable => true,
+            _ => return Err(ProgramError::UnexpectedProgramType {}),
+        };
+        let ProgramInfo(bpf_prog_info) = info;
+        let fd = info.fd()?;
+        let fd = fd.as_fd().try_clone_to_owned()?;
+
+        Ok(Self {
+            data: ProgramData::from_bpf_prog_info(
+                Some(name),
+                crate::MockableFd::from_fd(fd),
+                Path::new(""),
+                bpf_prog_info,
+                VerifierLogLevel::default(),
+            )?,
+            writable,
+        })
+    }
 }
 
 define_link_wrapper!(
@@ -0,0 +1,220 @@
+//! Programs selecting a socket among a `SO_REUSEPORT` group.
+use std::{
+    io, mem,
+    os::fd::{AsFd, AsRawFd as _, RawFd},
+};
+
+use aya_obj::generated::bpf_prog_type::BPF_PROG_TYPE_SK_REUSEPORT;
+use libc::{SO_ATTACH_REUSEPORT_EBPF, SO_DETACH_REUSEPORT_BPF, SOL_SOCKET, setsockopt};
+use thiserror::Error;
+
+use crate::{
+    programs::{Link, ProgramData, ProgramError, ProgramType, id_as_key, load_program},
+    sys::SyscallError,
+};
+
+/// The type returned when attaching a [`SkReuseport`] fails.
+#[derive(Debug, Error)]
+pub enum SkReuseportError {
+    /// Setting the `SO_ATTACH_REUSEPORT_EBPF` socket option failed.
+    #[error("setsockopt SO_ATTACH_REUSEPORT_EBPF failed")]
+    SoAttachReuseportEbpfError {
+        /// original [`io::Error`]
+        #[source]
+        io_error: io::Error,
+    },
+}
+
+/// A program used to select the socket receiving a packet or connection among
+/// a group of sockets bound with `SO_REUSEPORT`.
+///
+/// [`SkReuseport`] programs are attached to one socket of a reuseport group
+/// and apply to the whole group. They select the receiving socket from a
+/// [`ReusePortSockArray`](crate::maps::ReusePortSockArray) map. Programs
+/// loaded from the `sk_reuseport/migrate` section can also select the socket
+/// which requests are migrated to when a listener is closed.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.19.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     IO(#[from] std::io::Error),
+/// #     #[error(transparent)]
+/// #     Map(#[from] aya::maps::MapError),
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use std::{
+///     io,
+///     net::{Ipv4Addr, UdpSocket},
+///     os::fd::{AsRawFd as _, FromRawFd as _, OwnedFd},
+/// };
+///
+/// use aya::{maps::ReusePortSockArray, programs::SkReuseport};
+///
+/// // Creates a UDP socket bound to 127.0.0.1:`port`. `SO_REUSEPORT` must be
+/// // set before the socket is bound, which `UdpSocket::bind` doesn't allow.
+/// fn reuseport_socket(port: u16) -> io::Result<UdpSocket> {
+///     let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+///     if fd < 0 {
+///         return Err(io::Error::last_os_error());
+///     }
+///     let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+///     let one: libc::c_int = 1;
+///     let ret = unsafe {
+///         libc::setsockopt(
+///             socket.as_raw_fd(),
+///             libc::SOL_SOCKET,
+///             libc::SO_REUSEPORT,
+///             (&raw const one).cast(),
+///             size_of_val(&one) as libc::socklen_t,
+///         )
+///     };
+///     if ret < 0 {
+///         return Err(io::Error::last_os_error());
+///     }
+///     let addr = libc::sockaddr_in {
+///         sin_family: libc::AF_INET as libc::sa_family_t,
+///         sin_port: port.to_be(),
+///         sin_addr: libc::in_addr {
+///             s_addr: u32::from(Ipv4Addr::LOCALHOST).to_be(),
+///         },
+///         sin_zero: [0; 8],
+///     };
+///     let ret = unsafe {
+///         libc::bind(
+///             socket.as_raw_fd(),
+///             (&raw const addr).cast(),
+///             size_of_val(&addr) as libc::socklen_t,
+///         )
+///     };
+///     if ret < 0 {
+///         return Err(io::Error::last_os_error());
+///     }
+///     Ok(socket.into())
+/// }
+///
+/// let group = [reuseport_socket(1234)?, reuseport_socket(1234)?];
+/// let mut sockets = ReusePortSockArray::try_from(bpf.map_mut("SOCKETS").unwrap())?;
+/// for (index, socket) in group.iter().enumerate() {
+///     sockets.set(index as u32, socket, 0)?;
+/// }
+///
+/// let prog: &mut SkReuseport = bpf.program_mut("select_socket").unwrap().try_into()?;
+/// prog.load()?;
+/// // The program applies to the whole group.
+/// prog.attach(&group[0])?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_PROG_TYPE_SK_REUSEPORT")]
+pub struct SkReuseport {
+    pub(crate) data: ProgramData<SkReuseportLink>,
+}
+
+impl SkReuseport {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::SkReuseport;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        load_program(BPF_PROG_TYPE_SK_REUSEPORT, &mut self.data)
+    }
+
+    /// Attaches the program to the reuseport group of the given socket.
+    ///
+    /// The returned value can be used to detach from the socket, see [SkReuseport::detach].
+    pub fn attach<T: AsFd>(&mut self, socket: T) -> Result<SkReuseportLinkId, ProgramError> {
+        let prog_fd = self.fd()?;
+        let prog_fd = prog_fd.as_fd();
+        let prog_fd = prog_fd.as_raw_fd();
+        let socket = socket.as_fd();
+        let socket = socket.as_raw_fd();
+
+        let ret = unsafe {
+            setsockopt(
+                socket,
+                SOL_SOCKET,
+                SO_ATTACH_REUSEPORT_EBPF,
+                &prog_fd as *const _ as *const _,
+                mem::size_of::<RawFd>() as u32,
+            )
+        };
+        if ret < 0 {
+            return Err(SkReuseportError::SoAttachReuseportEbpfError {
+                io_error: io::Error::last_os_error(),
+            }
+            .into());
+        }
+
+        self.data.links.insert(SkReuseportLink { socket, prog_fd })
+    }
+
+    /// Detaches the program.
+    ///
+    /// See [`Self::attach`].
+    pub fn detach(&mut self, link_id: SkReuseportLinkId) -> Result<(), ProgramError> {
+        self.data.links.remove(link_id)
+    }
+
+    /// Takes ownership of the link referenced by the provided `link_id`.
+    ///
+    /// The caller takes the responsibility of managing the lifetime of the link. When the returned
+    /// [`SkReuseportLink`] is dropped, the link is detached.
+    pub fn take_link(
+        &mut self,
+        link_id: SkReuseportLinkId,
+    ) -> Result<SkReuseportLink, ProgramError> {
+        self.data.links.forget(link_id)
+    }
+}
+
+/// The type returned by [`SkReuseport::attach`]. Can be passed to [`SkReuseport::detach`].
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub struct SkReuseportLinkId(RawFd, RawFd);
+
+/// A SkReuseport Link
+#[derive(Debug)]
+pub struct SkReuseportLink {
+    socket: RawFd,
+    prog_fd: RawFd,
+}
+
+impl Link for SkReuseportLink {
+    type Id = SkReuseportLinkId;
+
+    fn id(&self) -> Self::Id {
+        SkReuseportLinkId(self.socket, self.prog_fd)
+    }
+
+    fn detach(self) -> Result<(), ProgramError> {
+        let ret = unsafe {
+            setsockopt(
+                self.socket,
+                SOL_SOCKET,
+                SO_DETACH_REUSEPORT_BPF,
+                &self.prog_fd as *const _ as *const _,
+                mem::size_of::<RawFd>() as u32,
+            )
+        };
+        if ret < 0 {
+            return Err(SyscallError {
+                call: "setsockopt",
+                io_error: io::Error::last_os_error(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+id_as_key!(SkReuseportLink, SkReuseportLinkId);
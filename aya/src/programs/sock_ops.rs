@@ -8,7 +8,7 @@ use aya_obj::generated::{
 use crate::{
     programs::{
         CgroupAttachMode, FdLink, Link, ProgAttachLink, ProgramData, ProgramError, ProgramType,
-        define_link_wrapper, id_as_key, impl_try_into_fdlink, load_program,
+        define_link_wrapper, id_as_key, impl_try_into_fdlink, impl_update_program, load_program,
     },
     sys::{LinkTarget, SyscallError, bpf_link_create},
     util::KernelVersion,
@@ -140,3 +140,4 @@ define_link_wrapper!(
 );
 
 impl_try_into_fdlink!(SockOpsLink, SockOpsLinkInner);
+impl_update_program!(SockOpsLink, SockOpsLinkInner);
@@ -0,0 +1,95 @@
+//! struct_ops programs.
+
+use std::path::Path;
+
+use aya_obj::generated::bpf_prog_type::BPF_PROG_TYPE_STRUCT_OPS;
+use thiserror::Error;
+
+use crate::{
+    VerifierLogLevel,
+    programs::{
+        ProgramData, ProgramError, ProgramType, links::NoLink, load_program_with_attach_type,
+    },
+};
+
+/// The type returned when loading a [`StructOps`] program fails.
+#[derive(Debug, Error)]
+pub enum StructOpsError {
+    /// The program doesn't implement a member of any struct_ops map.
+    #[error("the program doesn't implement a member of any struct_ops map")]
+    MapNotFound,
+}
+
+/// A program implementing a function pointer member of a kernel struct.
+///
+/// struct_ops programs implement the operations of kernel structs such as
+/// `tcp_congestion_ops`, which are declared in eBPF code as
+/// [`StructOpsMap`](crate::maps::StructOpsMap) maps. The programs aren't
+/// attached individually: once all the programs referenced by a map are
+/// loaded, the map is registered with the kernel by calling
+/// [`StructOpsMap::attach`](crate::maps::StructOpsMap::attach).
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 6.4.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(thiserror::Error, Debug)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Map(#[from] aya::maps::MapError),
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError),
+/// # }
+/// # let mut bpf = aya::Ebpf::load_file("ebpf_programs.o")?;
+/// use aya::{maps::StructOpsMap, programs::StructOps};
+///
+/// for name in ["cc_init", "cc_ssthresh", "cc_cong_avoid", "cc_undo_cwnd"] {
+///     let program: &mut StructOps = bpf.program_mut(name).unwrap().try_into()?;
+///     program.load()?;
+/// }
+/// let ops: StructOpsMap<_> = bpf.map("TCP_CC").unwrap().try_into()?;
+/// let link = ops.attach(&bpf)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_PROG_TYPE_STRUCT_OPS")]
+pub struct StructOps {
+    pub(crate) data: ProgramData<NoLink>,
+    pub(crate) member_index: Option<u32>,
+}
+
+impl StructOps {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::StructOps;
+
+    /// Loads the program inside the kernel.
+    ///
+    /// The kernel struct and member implemented by the program are found when
+    /// the object file is loaded, by looking for the struct_ops map which
+    /// references the program.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        // The kernel expects the index of the implemented member to be passed
+        // as the expected attach type.
+        let member_index = self.member_index.ok_or(StructOpsError::MapNotFound)?;
+        load_program_with_attach_type(BPF_PROG_TYPE_STRUCT_OPS, Some(member_index), &mut self.data)
+    }
+
+    /// Creates a program from a pinned entry on a bpffs.
+    ///
+    /// Existing links will not be populated. To work with existing links you should use [`crate::programs::links::PinnedLink`].
+    ///
+    /// On drop, any managed links are detached and the program is unloaded. This will not result in
+    /// the program being unloaded from the kernel if it is still pinned.
+    pub fn from_pin<P: AsRef<Path>>(path: P) -> Result<Self, ProgramError> {
+        let data = ProgramData::from_pinned_path(path, VerifierLogLevel::default())?;
+        Ok(Self {
+            data,
+            member_index: None,
+        })
+    }
+}
@@ -0,0 +1,86 @@
+//! Syscall programs.
+use std::os::fd::AsFd as _;
+
+use aya_obj::generated::bpf_prog_type::BPF_PROG_TYPE_SYSCALL;
+
+use crate::{
+    programs::{ProgramData, ProgramError, ProgramType, links::NoLink, load_program},
+    sys::{ProgTestRunArgs, SyscallError, bpf_prog_test_run},
+};
+
+/// A program that can call the `bpf` syscall.
+///
+/// [`Syscall`] programs aren't attached to any kernel event: they are run on
+/// demand from userspace with [`Syscall::run`], and can use the `bpf_sys_bpf`
+/// helper to update maps or load other programs from BPF. They are always
+/// loaded as sleepable programs.
+///
+/// The context of the program is a user-defined struct, passed in and read
+/// back as bytes when the program is run.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.14.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use aya::programs::Syscall;
+///
+/// let program: &mut Syscall = bpf.program_mut("setup").unwrap().try_into()?;
+/// program.load()?;
+///
+/// let mut ctx = [0u8; 8];
+/// let ret = program.run(&mut ctx)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_PROG_TYPE_SYSCALL")]
+pub struct Syscall {
+    pub(crate) data: ProgramData<NoLink>,
+}
+
+impl Syscall {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::Syscall;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        load_program(BPF_PROG_TYPE_SYSCALL, &mut self.data)
+    }
+
+    /// Runs the program with the given context and returns its return value.
+    ///
+    /// `ctx` holds the bytes of the context struct of the program. The kernel
+    /// copies the context back into `ctx` after the program has run, so any
+    /// changes made by the program are visible to the caller.
+    pub fn run(&self, ctx: &mut [u8]) -> Result<u32, ProgramError> {
+        let prog_fd = self.fd()?;
+        let prog_fd = prog_fd.as_fd();
+        let output = bpf_prog_test_run(
+            prog_fd,
+            ProgTestRunArgs {
+                data_in: &[],
+                data_out: &mut [],
+                ctx_in: ctx,
+                ctx_out: &mut [],
+                repeat: 0,
+                cpu: 0,
+                flags: 0,
+            },
+        )
+        .map_err(|io_error| SyscallError {
+            call: "bpf_prog_test_run",
+            io_error,
+        })?;
+        Ok(output.retval)
+    }
+}
@@ -8,7 +8,7 @@ use std::{
 
 use aya_obj::generated::{
     TC_H_CLSACT, TC_H_MIN_EGRESS, TC_H_MIN_INGRESS,
-    bpf_attach_type::{self, BPF_TCX_EGRESS, BPF_TCX_INGRESS},
+    bpf_attach_type::{self, BPF_NETKIT_PEER, BPF_NETKIT_PRIMARY, BPF_TCX_EGRESS, BPF_TCX_INGRESS},
     bpf_link_type,
     bpf_prog_type::BPF_PROG_TYPE_SCHED_CLS,
 };
@@ -19,7 +19,7 @@ use crate::{
     VerifierLogLevel,
     programs::{
         Link, LinkError, LinkOrder, ProgramData, ProgramError, ProgramType, define_link_wrapper,
-        id_as_key, impl_try_into_fdlink, load_program, query,
+        id_as_key, impl_try_into_fdlink, impl_update_program, load_program, query,
     },
     sys::{
         BpfLinkCreateArgs, LinkTarget, NetlinkError, ProgQueryTarget, SyscallError,
@@ -41,6 +41,28 @@ pub enum TcAttachType {
     Custom(u32),
 }
 
+/// Netkit attach type.
+///
+/// A netkit device is created as a pair of a primary and a peer device, like a
+/// veth pair. Programs attached to either side run on the packets transmitted
+/// by that device.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum NetkitAttachType {
+    /// Attach to the primary device.
+    Primary,
+    /// Attach to the peer device.
+    Peer,
+}
+
+impl NetkitAttachType {
+    pub(crate) fn netkit_attach_type(&self) -> bpf_attach_type {
+        match self {
+            Self::Primary => BPF_NETKIT_PRIMARY,
+            Self::Peer => BPF_NETKIT_PEER,
+        }
+    }
+}
+
 /// A network traffic control classifier.
 ///
 /// [`SchedClassifier`] programs can be used to inspect, filter or redirect
@@ -221,6 +243,52 @@ impl SchedClassifier {
         self.do_attach(if_index, attach_type, options, true)
     }
 
+    /// Attaches the program to the netkit device `interface`.
+    ///
+    /// `interface` must be the primary device of a netkit pair. The program
+    /// runs on the packets transmitted by the primary or the peer device,
+    /// depending on `attach_type`, and is ordered relative to the other programs
+    /// attached to the same side as defined by `order`.
+    ///
+    /// The returned value can be used to detach, see [SchedClassifier::detach].
+    ///
+    /// # Minimum kernel version
+    ///
+    /// The minimum kernel version required to use this feature is 6.7.
+    ///
+    /// # Errors
+    ///
+    /// [`ProgramError::SyscallError`] is returned if attaching fails, for
+    /// example because `interface` isn't a netkit device.
+    pub fn attach_to_netkit(
+        &mut self,
+        interface: &str,
+        attach_type: NetkitAttachType,
+        order: LinkOrder,
+    ) -> Result<SchedClassifierLinkId, ProgramError> {
+        let if_index = ifindex_from_ifname(interface).map_err(TcError::IoError)?;
+        let prog_fd = self.fd()?;
+        let prog_fd = prog_fd.as_fd();
+
+        let link_fd = bpf_link_create(
+            prog_fd,
+            LinkTarget::IfIndex(if_index),
+            attach_type.netkit_attach_type(),
+            order.flags.bits(),
+            Some(BpfLinkCreateArgs::Netkit(&order.link_ref)),
+        )
+        .map_err(|io_error| SyscallError {
+            call: "bpf_mprog_attach",
+            io_error,
+        })?;
+
+        self.data
+            .links
+            .insert(SchedClassifierLink::new(TcLinkInner::Fd(FdLink::new(
+                link_fd,
+            ))))
+    }
+
     /// Atomically replaces the program referenced by the provided link.
     ///
     /// Ownership of the link will transfer to this program.
@@ -368,6 +436,45 @@ impl SchedClassifier {
 
         Ok((revision, prog_infos))
     }
+
+    /// Queries a given netkit device for attached programs.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use aya::programs::tc::{NetkitAttachType, SchedClassifier};
+    /// # #[derive(Debug, thiserror::Error)]
+    /// # enum Error {
+    /// #     #[error(transparent)]
+    /// #     Program(#[from] aya::programs::ProgramError),
+    /// # }
+    /// let (revision, programs) = SchedClassifier::query_netkit("nk0", NetkitAttachType::Primary)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn query_netkit(
+        interface: &str,
+        attach_type: NetkitAttachType,
+    ) -> Result<(u64, Vec<ProgramInfo>), ProgramError> {
+        let if_index = ifindex_from_ifname(interface).map_err(TcError::IoError)?;
+
+        let (revision, prog_ids) = query(
+            ProgQueryTarget::IfIndex(if_index),
+            attach_type.netkit_attach_type(),
+            0,
+            &mut None,
+        )?;
+
+        let prog_infos = prog_ids
+            .into_iter()
+            .map(|prog_id| {
+                let prog_fd = bpf_prog_get_fd_by_id(prog_id)?;
+                let prog_info = ProgramInfo::new_from_fd(prog_fd.as_fd())?;
+                Ok::<ProgramInfo, ProgramError>(prog_info)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((revision, prog_infos))
+    }
 }
 
 #[derive(Debug, Hash, Eq, PartialEq)]
@@ -449,13 +556,16 @@ impl<'a> TryFrom<&'a SchedClassifierLink> for &'a FdLink {
 }
 
 impl_try_into_fdlink!(SchedClassifierLink, TcLinkInner);
+impl_update_program!(SchedClassifierLink, TcLinkInner);
 
 impl TryFrom<FdLink> for SchedClassifierLink {
     type Error = LinkError;
 
     fn try_from(fd_link: FdLink) -> Result<Self, Self::Error> {
         let info = bpf_link_get_info_by_fd(fd_link.fd.as_fd())?;
-        if info.type_ == (bpf_link_type::BPF_LINK_TYPE_TCX as u32) {
+        if info.type_ == (bpf_link_type::BPF_LINK_TYPE_TCX as u32)
+            || info.type_ == (bpf_link_type::BPF_LINK_TYPE_NETKIT as u32)
+        {
             return Ok(Self::new(TcLinkInner::Fd(fd_link)));
         }
         Err(LinkError::InvalidLink)
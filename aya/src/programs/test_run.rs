@@ -0,0 +1,237 @@
+//! Running programs with `BPF_PROG_TEST_RUN`.
+use std::{os::fd::BorrowedFd, time::Duration};
+
+use crate::{
+    programs::ProgramError,
+    sys::{ProgTestRunArgs, SyscallError, bpf_prog_test_run},
+};
+
+/// Options for running a program with `BPF_PROG_TEST_RUN`.
+///
+/// Running a program executes it once, or [`repeat`](Self::repeat) times, in
+/// the kernel on the given input packet and context, without attaching it.
+/// This allows programs to be tested without setting up the network topology
+/// or the events they normally run on.
+///
+/// The input and output buffers a program uses depend on its type: packet
+/// based programs such as [`Xdp`](super::Xdp) and
+/// [`SchedClassifier`](super::SchedClassifier) run on
+/// [`data_in`](Self::data_in) and may take a context such as `xdp_md` or
+/// `__sk_buff` with [`ctx_in`](Self::ctx_in), while
+/// [`RawTracePoint`](super::RawTracePoint) programs only take the arguments
+/// of the tracepoint as their context.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 4.12. Support
+/// for contexts, repeat counts, CPUs and flags varies by program type.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[derive(Debug, thiserror::Error)]
+/// # enum Error {
+/// #     #[error(transparent)]
+/// #     Program(#[from] aya::programs::ProgramError),
+/// #     #[error(transparent)]
+/// #     Ebpf(#[from] aya::EbpfError)
+/// # }
+/// # let mut bpf = aya::Ebpf::load(&[])?;
+/// use aya::programs::{Xdp, test_run::TestRunOptions};
+///
+/// let program: &mut Xdp = bpf.program_mut("xdp_pass").unwrap().try_into()?;
+/// program.load()?;
+///
+/// let packet = [0u8; 64];
+/// let output = program.test_run(
+///     TestRunOptions::new()
+///         .data_in(&packet)
+///         .data_out_size(packet.len())
+///         .repeat(100),
+/// )?;
+/// println!("returned {} in {:?}", output.retval, output.duration);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TestRunOptions<'a> {
+    data_in: &'a [u8],
+    data_out_size: usize,
+    ctx_in: &'a [u8],
+    ctx_out_size: usize,
+    repeat: u32,
+    cpu: u32,
+    flags: u32,
+}
+
+impl<'a> TestRunOptions<'a> {
+    /// Creates options to run a program once, without input or output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the input packet.
+    pub fn data_in(mut self, data: &'a [u8]) -> Self {
+        self.data_in = data;
+        self
+    }
+
+    /// Sets the size of the buffer the output packet is copied to.
+    ///
+    /// The output packet is truncated if it doesn't fit in the buffer, in
+    /// which case running the program fails with `ENOSPC`.
+    pub fn data_out_size(mut self, size: usize) -> Self {
+        self.data_out_size = size;
+        self
+    }
+
+    /// Sets the input context.
+    pub fn ctx_in(mut self, ctx: &'a [u8]) -> Self {
+        self.ctx_in = ctx;
+        self
+    }
+
+    /// Sets the size of the buffer the output context is copied to.
+    pub fn ctx_out_size(mut self, size: usize) -> Self {
+        self.ctx_out_size = size;
+        self
+    }
+
+    /// Sets the number of times the program is run.
+    ///
+    /// The returned [`duration`](TestRunOutput::duration) is the average
+    /// duration of the runs.
+    pub fn repeat(mut self, repeat: u32) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Sets the CPU the program runs on.
+    ///
+    /// The CPU is only used if the `BPF_F_TEST_RUN_ON_CPU` flag is set.
+    pub fn cpu(mut self, cpu: u32) -> Self {
+        self.cpu = cpu;
+        self
+    }
+
+    /// Sets the `BPF_F_TEST_*` flags.
+    pub fn flags(mut self, flags: u32) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub(crate) fn run(&self, prog_fd: BorrowedFd<'_>) -> Result<TestRunOutput, ProgramError> {
+        let Self {
+            data_in,
+            data_out_size,
+            ctx_in,
+            ctx_out_size,
+            repeat,
+            cpu,
+            flags,
+        } = *self;
+        // The kernel copies the output context of some program types back
+        // into the input context buffer.
+        let mut ctx_in = ctx_in.to_vec();
+        let mut data_out = vec![0; data_out_size];
+        let mut ctx_out = vec![0; ctx_out_size];
+
+        let output = bpf_prog_test_run(
+            prog_fd,
+            ProgTestRunArgs {
+                data_in,
+                data_out: &mut data_out,
+                ctx_in: &mut ctx_in,
+                ctx_out: &mut ctx_out,
+                repeat,
+                cpu,
+                flags,
+            },
+        )
+        .map_err(|io_error| SyscallError {
+            call: "bpf_prog_test_run",
+            io_error,
+        })?;
+
+        data_out.truncate(output.data_size_out as usize);
+        ctx_out.truncate(output.ctx_size_out as usize);
+        Ok(TestRunOutput {
+            retval: output.retval,
+            data_out,
+            ctx_out,
+            duration: Duration::from_nanos(output.duration.into()),
+        })
+    }
+}
+
+/// The result of running a program with `BPF_PROG_TEST_RUN`.
+#[derive(Debug)]
+pub struct TestRunOutput {
+    /// The value returned by the program.
+    pub retval: u32,
+    /// The output packet.
+    pub data_out: Vec<u8>,
+    /// The output context.
+    pub ctx_out: Vec<u8>,
+    /// The average duration of a run of the program.
+    pub duration: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::fd::BorrowedFd, slice, time::Duration};
+
+    use aya_obj::generated::bpf_cmd;
+
+    use super::TestRunOptions;
+    use crate::sys::{Syscall, override_syscall};
+
+    #[test]
+    fn test_run_options() {
+        override_syscall(|call| match call {
+            Syscall::Ebpf {
+                cmd: bpf_cmd::BPF_PROG_TEST_RUN,
+                attr,
+            } => {
+                let test = unsafe { &mut attr.test };
+                assert_eq!(test.prog_fd, 42);
+                assert_eq!(test.data_size_in, 4);
+                assert_eq!(test.data_size_out, 8);
+                assert_eq!(test.ctx_size_in, 2);
+                assert_eq!(test.ctx_size_out, 0);
+                assert_eq!(test.ctx_out, 0);
+                assert_eq!(test.repeat, 10);
+                assert_eq!(test.cpu, 1);
+                assert_eq!(test.flags, 2);
+
+                let data_in = unsafe {
+                    slice::from_raw_parts(test.data_in as *const u8, test.data_size_in as usize)
+                };
+                let data_out = unsafe {
+                    slice::from_raw_parts_mut(test.data_out as *mut u8, test.data_size_out as usize)
+                };
+                data_out[..4].copy_from_slice(data_in);
+                test.data_size_out = 4;
+                test.ctx_size_out = 0;
+                test.retval = 2;
+                test.duration = 100;
+                Ok(0)
+            }
+            _ => Err((-1, std::io::Error::from_raw_os_error(libc::EINVAL))),
+        });
+
+        let prog_fd = unsafe { BorrowedFd::borrow_raw(42) };
+        let output = TestRunOptions::new()
+            .data_in(&[1, 2, 3, 4])
+            .data_out_size(8)
+            .ctx_in(&[5, 6])
+            .repeat(10)
+            .cpu(1)
+            .flags(2)
+            .run(prog_fd)
+            .unwrap();
+        assert_eq!(output.retval, 2);
+        assert_eq!(output.data_out, [1, 2, 3, 4]);
+        assert!(output.ctx_out.is_empty());
+        assert_eq!(output.duration, Duration::from_nanos(100));
+    }
+}
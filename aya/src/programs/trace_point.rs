@@ -11,7 +11,7 @@ use thiserror::Error;
 use crate::{
     programs::{
         FdLink, LinkError, ProgramData, ProgramError, ProgramType, define_link_wrapper,
-        impl_try_into_fdlink, load_program,
+        impl_try_into_fdlink, impl_update_program, load_program,
         perf_attach::{PerfLinkIdInner, PerfLinkInner, perf_attach},
         utils::find_tracefs_path,
     },
@@ -98,6 +98,7 @@ define_link_wrapper!(
 );
 
 impl_try_into_fdlink!(TracePointLink, PerfLinkInner);
+impl_update_program!(TracePointLink, PerfLinkInner);
 
 impl TryFrom<FdLink> for TracePointLink {
     type Error = LinkError;
@@ -19,7 +19,7 @@ use crate::{
     VerifierLogLevel,
     programs::{
         FdLink, LinkError, ProgramData, ProgramError, ProgramType, define_link_wrapper,
-        impl_try_into_fdlink, load_program,
+        impl_try_into_fdlink, impl_update_program, load_program,
         perf_attach::{PerfLinkIdInner, PerfLinkInner},
         probe::{OsStringExt as _, ProbeKind, attach},
     },
@@ -114,21 +114,7 @@ impl UProbe {
     ) -> Result<UProbeLinkId, ProgramError> {
         let proc_map = pid.map(ProcMap::new).transpose()?;
         let path = resolve_attach_path(target.as_ref(), proc_map.as_ref())?;
-        let (symbol, offset) = match location.into() {
-            UProbeAttachLocation::Symbol(s) => (Some(s), 0),
-            UProbeAttachLocation::SymbolOffset(s, offset) => (Some(s), offset),
-            UProbeAttachLocation::AbsoluteOffset(offset) => (None, offset),
-        };
-        let offset = if let Some(symbol) = symbol {
-            let symbol_offset =
-                resolve_symbol(path, symbol).map_err(|error| UProbeError::SymbolError {
-                    symbol: symbol.to_string(),
-                    error: Box::new(error),
-                })?;
-            symbol_offset + offset
-        } else {
-            offset
-        };
+        let offset = resolve_attach_location(path, &location.into())?;
 
         let path = path.as_os_str();
         attach(&mut self.data, self.kind, path, offset, pid, cookie)
@@ -146,7 +132,30 @@ impl UProbe {
     }
 }
 
-fn resolve_attach_path<'a, 'b, 'c, T>(
+/// Resolves `location` to an offset in the object file at `path`.
+pub(crate) fn resolve_attach_location(
+    path: &Path,
+    location: &UProbeAttachLocation<'_>,
+) -> Result<u64, UProbeError> {
+    let (symbol, offset) = match *location {
+        UProbeAttachLocation::Symbol(s) => (Some(s), 0),
+        UProbeAttachLocation::SymbolOffset(s, offset) => (Some(s), offset),
+        UProbeAttachLocation::AbsoluteOffset(offset) => (None, offset),
+    };
+    let offset = if let Some(symbol) = symbol {
+        let symbol_offset =
+            resolve_symbol(path, symbol).map_err(|error| UProbeError::SymbolError {
+                symbol: symbol.to_string(),
+                error: Box::new(error),
+            })?;
+        symbol_offset + offset
+    } else {
+        offset
+    };
+    Ok(offset)
+}
+
+pub(crate) fn resolve_attach_path<'a, 'b, 'c, T>(
     target: &'a Path,
     proc_map: Option<&'b ProcMap<T>>,
 ) -> Result<&'c Path, UProbeError>
@@ -219,6 +228,7 @@ define_link_wrapper!(
 );
 
 impl_try_into_fdlink!(UProbeLink, PerfLinkInner);
+impl_update_program!(UProbeLink, PerfLinkInner);
 
 impl TryFrom<FdLink> for UProbeLink {
     type Error = LinkError;
@@ -405,13 +415,13 @@ impl<'a> ProcMapEntry<'a> {
 /// This is read from /proc/`pid`/maps.
 ///
 /// The information here may be used to resolve addresses to paths.
-struct ProcMap<T> {
+pub(crate) struct ProcMap<T> {
     pid: pid_t,
     data: T,
 }
 
 impl ProcMap<Vec<u8>> {
-    fn new(pid: pid_t) -> Result<Self, UProbeError> {
+    pub(crate) fn new(pid: pid_t) -> Result<Self, UProbeError> {
         let filename = PathBuf::from(format!("/proc/{pid}/maps"));
         let data = fs::read(&filename)
             .map_err(|io_error| UProbeError::FileError { filename, io_error })?;
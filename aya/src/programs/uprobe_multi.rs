@@ -0,0 +1,198 @@
+//! User space probes attached to many locations through a single link.
+use std::{
+    ffi::{CString, NulError},
+    os::{fd::AsFd as _, unix::ffi::OsStrExt as _},
+    path::{Path, PathBuf},
+};
+
+use aya_obj::generated::{
+    BPF_F_UPROBE_MULTI_RETURN, bpf_attach_type::BPF_TRACE_UPROBE_MULTI,
+    bpf_prog_type::BPF_PROG_TYPE_KPROBE,
+};
+use libc::pid_t;
+use thiserror::Error;
+
+use crate::{
+    VerifierLogLevel,
+    programs::{
+        FdLink, FdLinkId, ProgramData, ProgramError, ProgramType, define_link_wrapper,
+        load_program,
+        probe::ProbeKind,
+        uprobe::{ProcMap, UProbeAttachLocation, resolve_attach_location, resolve_attach_path},
+    },
+    sys::{BpfLinkCreateArgs, LinkTarget, SyscallError, bpf_link_create},
+};
+
+/// A user space probe attached to many locations at once.
+///
+/// Unlike [`UProbe`](super::UProbe), which creates one perf event per probed
+/// location, a `UProbeMulti` program is attached to all of its target
+/// locations in a binary or library through a single
+/// `BPF_LINK_TYPE_UPROBE_MULTI` link.
+///
+/// The program is a `uprobe` if it was declared in a `uprobe.multi` section
+/// and a `uretprobe` if it was declared in a `uretprobe.multi` section.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 6.6.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = Ebpf::load_file("ebpf_programs.o")?;
+/// use aya::{
+///     Ebpf,
+///     programs::{UProbeMulti, uprobe::UProbeAttachLocation},
+/// };
+///
+/// let program: &mut UProbeMulti = bpf.program_mut("malloc_calls").unwrap().try_into()?;
+/// program.load()?;
+/// program.attach(
+///     &[
+///         UProbeAttachLocation::Symbol("malloc"),
+///         UProbeAttachLocation::Symbol("calloc"),
+///     ],
+///     "libc",
+///     None,
+///     Some(&[1, 2]),
+/// )?;
+/// # Ok::<(), aya::EbpfError>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "BPF_TRACE_UPROBE_MULTI")]
+pub struct UProbeMulti {
+    pub(crate) data: ProgramData<UProbeMultiLink>,
+    pub(crate) kind: ProbeKind,
+}
+
+impl UProbeMulti {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::KProbe;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        self.data.expected_attach_type = Some(BPF_TRACE_UPROBE_MULTI);
+        load_program(BPF_PROG_TYPE_KPROBE, &mut self.data)
+    }
+
+    /// Returns `UProbe` if the program is a `uprobe.multi`, or `URetProbe` if
+    /// the program is a `uretprobe.multi`.
+    pub fn kind(&self) -> ProbeKind {
+        self.kind
+    }
+
+    /// Attaches the program.
+    ///
+    /// Attaches the probe to every location in `locations` inside `target`.
+    /// Locations are resolved the same way as in [`UProbe::attach`](super::UProbe::attach),
+    /// and so is `target`, which can be an absolute path to a binary or
+    /// library, or a library name (eg: `"libc"`). If `pid` is not `None`, the
+    /// program executes only when the target locations are hit by the given
+    /// `pid`.
+    ///
+    /// If `cookies` is provided it must contain exactly one value per location;
+    /// the value at index `i` can be retrieved with `bpf_get_attach_cookie`
+    /// when the program runs for `locations[i]`.
+    ///
+    /// The returned value can be used to detach, see [`UProbeMulti::detach`].
+    pub fn attach<T: AsRef<Path>>(
+        &mut self,
+        locations: &[UProbeAttachLocation<'_>],
+        target: T,
+        pid: Option<pid_t>,
+        cookies: Option<&[u64]>,
+    ) -> Result<UProbeMultiLinkId, ProgramError> {
+        if let Some(cookies) = cookies {
+            if cookies.len() != locations.len() {
+                return Err(UProbeMultiError::CookieCountMismatch {
+                    locations: locations.len(),
+                    cookies: cookies.len(),
+                }
+                .into());
+            }
+        }
+        let proc_map = pid.map(ProcMap::new).transpose()?;
+        let path = resolve_attach_path(target.as_ref(), proc_map.as_ref())?;
+        let offsets = locations
+            .iter()
+            .map(|location| resolve_attach_location(path, location))
+            .collect::<Result<Vec<_>, _>>()?;
+        let path_string = CString::new(path.as_os_str().as_bytes()).map_err(|error| {
+            UProbeMultiError::InvalidPath {
+                path: path.to_path_buf(),
+                error,
+            }
+        })?;
+
+        let prog_fd = self.fd()?;
+        let prog_fd = prog_fd.as_fd();
+        let flags = match self.kind {
+            ProbeKind::URetProbe => BPF_F_UPROBE_MULTI_RETURN,
+            ProbeKind::UProbe | ProbeKind::KProbe | ProbeKind::KRetProbe => 0,
+        };
+        let link_fd = bpf_link_create(
+            prog_fd,
+            LinkTarget::None,
+            BPF_TRACE_UPROBE_MULTI,
+            0,
+            Some(BpfLinkCreateArgs::UProbeMulti {
+                path: &path_string,
+                offsets: &offsets,
+                cookies,
+                pid: pid.unwrap_or(0) as u32,
+                flags,
+            }),
+        )
+        .map_err(|io_error| SyscallError {
+            call: "bpf_link_create",
+            io_error,
+        })?;
+
+        self.data
+            .links
+            .insert(UProbeMultiLink::new(FdLink::new(link_fd)))
+    }
+
+    /// Creates a program from a pinned entry on a bpffs.
+    ///
+    /// Existing links will not be populated. To work with existing links you should use [`crate::programs::links::PinnedLink`].
+    ///
+    /// On drop, any managed links are detached and the program is unloaded. This will not result in
+    /// the program being unloaded from the kernel if it is still pinned.
+    pub fn from_pin<P: AsRef<Path>>(path: P, kind: ProbeKind) -> Result<Self, ProgramError> {
+        let data = ProgramData::from_pinned_path(path, VerifierLogLevel::default())?;
+        Ok(Self { data, kind })
+    }
+}
+
+define_link_wrapper!(
+    UProbeMultiLink,
+    UProbeMultiLinkId,
+    FdLink,
+    FdLinkId,
+    UProbeMulti,
+);
+
+/// The type returned when attaching a [`UProbeMulti`] fails.
+#[derive(Debug, Error)]
+pub enum UProbeMultiError {
+    /// The resolved target path contains a nul byte.
+    #[error("invalid uprobe target `{}`", path.display())]
+    InvalidPath {
+        /// The resolved target path
+        path: PathBuf,
+        /// The [`NulError`] returned when converting the path
+        #[source]
+        error: NulError,
+    },
+
+    /// The number of cookies doesn't match the number of locations.
+    #[error("got {cookies} cookies for {locations} locations")]
+    CookieCountMismatch {
+        /// The number of locations
+        locations: usize,
+        /// The number of cookies
+        cookies: usize,
+    },
+}
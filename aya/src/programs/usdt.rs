@@ -0,0 +1,958 @@
+//! User statically-defined tracepoints.
+use std::{
+    error::Error,
+    io,
+    os::fd::{AsFd as _, BorrowedFd},
+    path::{Path, PathBuf},
+    str,
+    sync::{Arc, Mutex},
+};
+
+use aya_obj::generated::bpf_prog_type::BPF_PROG_TYPE_KPROBE;
+use libc::pid_t;
+use object::{
+    Architecture, Endian as _, Endianness, Object as _, ObjectSection as _, SectionFlags,
+};
+use thiserror::Error;
+
+use crate::{
+    Pod, VerifierLogLevel,
+    maps::{MapData, MapError, MapFd},
+    programs::{
+        Link, ProgramData, ProgramError, ProgramType, define_link_wrapper, id_as_key, load_program,
+        perf_attach::{PerfLinkIdInner, PerfLinkInner, perf_attach},
+        probe::{ProbeKind, create_as_probe},
+        uprobe::{ProcMap, resolve_attach_path},
+    },
+    sys::{SyscallError, bpf_map_update_elem},
+    util::MMap,
+};
+
+/// Returns the name of the map holding the argument specs of the probes the
+/// given [`Usdt`] program is attached to.
+///
+/// The map is declared along with the program by the `#[usdt]` macro of
+/// `aya-ebpf`, so objects without USDT programs don't carry it.
+pub(crate) fn usdt_spec_map_name(prog_name: &str) -> String {
+    format!("{prog_name}_usdt_specs")
+}
+
+const USDT_MAX_ARG_COUNT: usize = 12;
+
+const USDT_ARG_CONST: u32 = 0;
+const USDT_ARG_REG: u32 = 1;
+const USDT_ARG_REG_DEREF: u32 = 2;
+
+// The layout of `UsdtArgSpec` and `UsdtSpec` must be kept in sync with
+// `aya_ebpf::programs::usdt`.
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct UsdtArgSpec {
+    /// The constant value, or the offset added to the register for `USDT_ARG_REG_DEREF`.
+    val_off: u64,
+    arg_type: u32,
+    /// The offset of the register in `struct pt_regs`.
+    reg_off: i16,
+    arg_signed: u8,
+    /// The shift used to truncate and sign-extend the value to its actual size.
+    arg_bitshift: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct UsdtSpec {
+    args: [UsdtArgSpec; USDT_MAX_ARG_COUNT],
+    cookie: u64,
+    arg_count: u16,
+    _pad: [u8; 6],
+}
+
+unsafe impl Pod for UsdtSpec {}
+
+/// The spec map of a [`Usdt`] program, along with the slots currently in use.
+#[derive(Debug)]
+pub(crate) struct UsdtSpecMap {
+    fd: MapFd,
+    used: Mutex<Vec<bool>>,
+}
+
+impl UsdtSpecMap {
+    pub(crate) fn new(map: &MapData) -> Result<Self, MapError> {
+        let size = map.obj().value_size() as usize;
+        let expected = size_of::<UsdtSpec>();
+        if size != expected {
+            return Err(MapError::InvalidValueSize { size, expected });
+        }
+        let fd = map.fd().try_clone()?;
+        let used = Mutex::new(vec![false; map.obj().max_entries() as usize]);
+        Ok(Self { fd, used })
+    }
+
+    fn insert(&self, spec: &UsdtSpec) -> Result<u32, ProgramError> {
+        let mut used = self.used.lock().unwrap();
+        let id = used
+            .iter()
+            .position(|used| !used)
+            .ok_or(UsdtError::TooManySpecs {
+                max_entries: used.len(),
+            })?;
+        let id = id as u32;
+        bpf_map_update_elem(self.fd.as_fd(), Some(&id), spec, 0).map_err(|io_error| {
+            SyscallError {
+                call: "bpf_map_update_elem",
+                io_error,
+            }
+        })?;
+        used[id as usize] = true;
+        Ok(id)
+    }
+
+    fn remove(&self, id: u32) {
+        self.used.lock().unwrap()[id as usize] = false;
+    }
+}
+
+/// A user statically-defined tracepoint.
+///
+/// USDT probes are static tracepoints compiled into user space programs and
+/// libraries, for example through the `DTRACE_PROBE` macros of SystemTap's
+/// `sys/sdt.h`. They are described by the `.note.stapsdt` ELF notes, which
+/// record the location of each probe, the optional semaphore guarding it and
+/// how to fetch its arguments.
+///
+/// When attaching, the notes of the target are parsed and a uprobe is created
+/// for each location of the probe. The argument specs are stored in the spec
+/// map declared along with the program by the `#[usdt]` macro of `aya-ebpf`,
+/// where `UsdtContext::arg` looks them up to decode the arguments.
+///
+/// # Minimum kernel version
+///
+/// The minimum kernel version required to use this feature is 5.15.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let mut bpf = Ebpf::load_file("ebpf_programs.o")?;
+/// use aya::{Ebpf, programs::Usdt};
+///
+/// let program: &mut Usdt = bpf.program_mut("pg_query_start").unwrap().try_into()?;
+/// program.load()?;
+/// program.attach("postgresql", "query__start", "/usr/bin/postgres", None, None)?;
+/// # Ok::<(), aya::EbpfError>(())
+/// ```
+#[derive(Debug)]
+#[doc(alias = "usdt")]
+pub struct Usdt {
+    pub(crate) data: ProgramData<UsdtLink>,
+    pub(crate) spec_map: Option<Arc<UsdtSpecMap>>,
+}
+
+impl Usdt {
+    /// The type of the program according to the kernel.
+    pub const PROGRAM_TYPE: ProgramType = ProgramType::KProbe;
+
+    /// Loads the program inside the kernel.
+    pub fn load(&mut self) -> Result<(), ProgramError> {
+        load_program(BPF_PROG_TYPE_KPROBE, &mut self.data)
+    }
+
+    /// Attaches the program.
+    ///
+    /// Attaches the program to every location of the `provider:name` probe
+    /// in `target`, which can be an absolute path to a binary or library, or
+    /// a library name (eg: `"libc"`). If the probe is guarded by a semaphore,
+    /// the kernel increments it while the program is attached.
+    ///
+    /// If `pid` is not `None`, the program executes only when the probe is hit
+    /// by the given `pid`.
+    ///
+    /// The `cookie` can be retrieved with `UsdtContext::cookie`.
+    ///
+    /// The returned value can be used to detach, see [`Usdt::detach`].
+    pub fn attach<T: AsRef<Path>>(
+        &mut self,
+        provider: &str,
+        name: &str,
+        target: T,
+        pid: Option<pid_t>,
+        cookie: Option<u64>,
+    ) -> Result<UsdtLinkId, ProgramError> {
+        let spec_map = self
+            .spec_map
+            .as_ref()
+            .map(Arc::clone)
+            .ok_or(UsdtError::SpecMapNotFound)?;
+        let proc_map = pid.map(ProcMap::new).transpose()?;
+        let path = resolve_attach_path(target.as_ref(), proc_map.as_ref())?;
+
+        let (arch, notes) = find_usdt_notes(path, provider, name)?;
+        if notes.is_empty() {
+            return Err(UsdtError::ProbeNotFound {
+                provider: provider.to_owned(),
+                name: name.to_owned(),
+                path: path.to_owned(),
+            }
+            .into());
+        }
+        // The program reads the arguments from the registers of the kernel it
+        // runs on, so the arguments of targets built for another architecture
+        // can't be decoded.
+        if Some(arch) != HOST_ARCH && notes.iter().any(|note| !note.args.trim().is_empty()) {
+            return Err(UsdtError::UnsupportedArch {
+                arch: arch_name(arch),
+            }
+            .into());
+        }
+        let specs = notes
+            .iter()
+            .map(|note| parse_usdt_spec(arch, &note.args, cookie.unwrap_or(0)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let prog_fd = self.fd()?;
+        let prog_fd = prog_fd.as_fd();
+        let mut link = UsdtLinkInner {
+            links: Vec::with_capacity(notes.len()),
+            spec_ids: Vec::with_capacity(notes.len()),
+            spec_map,
+        };
+        for (note, spec) in notes.iter().zip(&specs) {
+            if let Err(err) = link.attach_location(prog_fd, path, note, spec, pid) {
+                let _: Result<(), ProgramError> = link.detach();
+                return Err(err);
+            }
+        }
+        self.data.links.insert(UsdtLink::new(link))
+    }
+
+    /// Creates a program from a pinned entry on a bpffs.
+    ///
+    /// Existing links will not be populated. To work with existing links you should use [`crate::programs::links::PinnedLink`].
+    ///
+    /// The spec map of the object the program was loaded from isn't known, so
+    /// the returned program can't be attached.
+    ///
+    /// On drop, any managed links are detached and the program is unloaded. This will not result in
+    /// the program being unloaded from the kernel if it is still pinned.
+    pub fn from_pin<P: AsRef<Path>>(path: P) -> Result<Self, ProgramError> {
+        let data = ProgramData::from_pinned_path(path, VerifierLogLevel::default())?;
+        Ok(Self {
+            data,
+            spec_map: None,
+        })
+    }
+}
+
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub(crate) struct UsdtLinkIdInner(Vec<PerfLinkIdInner>);
+
+/// The uprobes of an attached USDT probe and the specs they use.
+#[derive(Debug)]
+pub(crate) struct UsdtLinkInner {
+    links: Vec<PerfLinkInner>,
+    spec_ids: Vec<u32>,
+    spec_map: Arc<UsdtSpecMap>,
+}
+
+impl UsdtLinkInner {
+    fn attach_location(
+        &mut self,
+        prog_fd: BorrowedFd<'_>,
+        path: &Path,
+        note: &UsdtNote,
+        spec: &UsdtSpec,
+        pid: Option<pid_t>,
+    ) -> Result<(), ProgramError> {
+        let spec_id = self.spec_map.insert(spec)?;
+        self.spec_ids.push(spec_id);
+        let fd = create_as_probe(
+            ProbeKind::UProbe,
+            path.as_os_str(),
+            note.offset,
+            note.semaphore_offset,
+            pid,
+        )?;
+        // The cookie is the index of the spec, the user provided cookie is
+        // stored in the spec itself.
+        let link = perf_attach(prog_fd, fd, Some(u64::from(spec_id)))?;
+        self.links.push(link);
+        Ok(())
+    }
+}
+
+impl Link for UsdtLinkInner {
+    type Id = UsdtLinkIdInner;
+
+    fn id(&self) -> Self::Id {
+        UsdtLinkIdInner(self.links.iter().map(Link::id).collect())
+    }
+
+    fn detach(self) -> Result<(), ProgramError> {
+        let Self {
+            links,
+            spec_ids,
+            spec_map,
+        } = self;
+        let mut result = Ok(());
+        for link in links {
+            if let Err(err) = link.detach() {
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        for id in spec_ids {
+            spec_map.remove(id);
+        }
+        result
+    }
+}
+
+id_as_key!(UsdtLinkInner, UsdtLinkIdInner);
+
+define_link_wrapper!(UsdtLink, UsdtLinkId, UsdtLinkInner, UsdtLinkIdInner, Usdt,);
+
+/// The type returned when attaching a [`Usdt`] fails.
+#[derive(Debug, Error)]
+pub enum UsdtError {
+    /// The object doesn't define the spec map of the program.
+    #[error("the USDT spec map of the program was not found, is it declared with `#[usdt]`?")]
+    SpecMapNotFound,
+
+    /// Error reading the target.
+    #[error("`{filename}`")]
+    FileError {
+        /// The file name
+        filename: PathBuf,
+        /// The [`io::Error`] returned from the file operation
+        #[source]
+        io_error: io::Error,
+    },
+
+    /// Error parsing the USDT notes of the target.
+    #[error("error parsing the USDT notes of `{}`", path.display())]
+    InvalidNotes {
+        /// The path of the target
+        path: PathBuf,
+        /// The original error
+        #[source]
+        error: Box<dyn Error + Send + Sync>,
+    },
+
+    /// The probe wasn't found in the target.
+    #[error("USDT probe `{provider}:{name}` not found in `{}`", path.display())]
+    ProbeNotFound {
+        /// The provider of the probe
+        provider: String,
+        /// The name of the probe
+        name: String,
+        /// The path of the target
+        path: PathBuf,
+    },
+
+    /// An argument spec couldn't be parsed.
+    #[error("invalid USDT argument spec `{spec}`")]
+    InvalidArgSpec {
+        /// The argument spec
+        spec: String,
+    },
+
+    /// A probe has more arguments than supported.
+    #[error("USDT probe has {count} arguments, at most {USDT_MAX_ARG_COUNT} are supported")]
+    TooManyArgs {
+        /// The number of arguments
+        count: usize,
+    },
+
+    /// Argument specs of targets built for the architecture can't be decoded.
+    #[error("USDT arguments of {arch} targets are not supported")]
+    UnsupportedArch {
+        /// The architecture of the target
+        arch: String,
+    },
+
+    /// All the entries of the spec map are in use.
+    #[error("all {max_entries} entries of the USDT spec map are in use")]
+    TooManySpecs {
+        /// The size of the spec map
+        max_entries: usize,
+    },
+}
+
+#[derive(Error, Debug)]
+enum UsdtNoteError {
+    #[error(transparent)]
+    Object(#[from] object::Error),
+
+    #[error("truncated note")]
+    Truncated,
+
+    #[error("note contains a string which is not valid UTF-8")]
+    InvalidString,
+
+    #[error("address {0:#x} is not in a section of the file")]
+    InvalidAddress(u64),
+}
+
+/// A location of a USDT probe.
+#[derive(Debug, PartialEq, Eq)]
+struct UsdtNote {
+    /// The offset of the probe in the file.
+    offset: u64,
+    /// The offset of the semaphore in the file, if any.
+    semaphore_offset: Option<u64>,
+    args: String,
+}
+
+/// A `.note.stapsdt` note, as found in the file.
+#[derive(Debug, PartialEq, Eq)]
+struct RawUsdtNote<'a> {
+    pc: u64,
+    base: u64,
+    semaphore: u64,
+    provider: &'a str,
+    name: &'a str,
+    args: &'a str,
+}
+
+/// The architecture of the running kernel, whose registers the argument specs
+/// refer to.
+const HOST_ARCH: Option<Architecture> = if cfg!(target_arch = "x86_64") {
+    Some(Architecture::X86_64)
+} else if cfg!(target_arch = "aarch64") {
+    Some(Architecture::Aarch64)
+} else if cfg!(target_arch = "riscv64") {
+    Some(Architecture::Riscv64)
+} else {
+    None
+};
+
+fn arch_name(arch: Architecture) -> String {
+    format!("{arch:?}").to_lowercase()
+}
+
+/// Returns the architecture of the target, from its ELF header, along with the
+/// locations of the probe.
+fn find_usdt_notes(
+    path: &Path,
+    provider: &str,
+    name: &str,
+) -> Result<(Architecture, Vec<UsdtNote>), UsdtError> {
+    let data = MMap::map_copy_read_only(path).map_err(|io_error| UsdtError::FileError {
+        filename: path.to_owned(),
+        io_error,
+    })?;
+    usdt_notes(data.as_ref(), provider, name).map_err(|error| UsdtError::InvalidNotes {
+        path: path.to_owned(),
+        error: Box::new(error),
+    })
+}
+
+fn usdt_notes(
+    data: &[u8],
+    provider: &str,
+    name: &str,
+) -> Result<(Architecture, Vec<UsdtNote>), UsdtNoteError> {
+    let obj = object::File::parse(data)?;
+    let arch = obj.architecture();
+    let Some(section) = obj.section_by_name(".note.stapsdt") else {
+        return Ok((arch, Vec::new()));
+    };
+    // Set when the file was prelinked, in which case the addresses in the notes
+    // must be adjusted by how much the file moved.
+    let base = obj.section_by_name(".stapsdt.base").map(|s| s.address());
+
+    let notes = parse_usdt_notes(section.data()?, obj.endianness(), obj.is_64())?
+        .into_iter()
+        .filter(|note| note.provider == provider && note.name == name)
+        .map(|note| {
+            let pc = match base {
+                Some(base) if note.base != 0 => note.pc.wrapping_add(base).wrapping_sub(note.base),
+                _ => note.pc,
+            };
+            let semaphore_offset = (note.semaphore != 0)
+                .then(|| file_offset(&obj, note.semaphore))
+                .transpose()?;
+            Ok(UsdtNote {
+                offset: file_offset(&obj, pc)?,
+                semaphore_offset,
+                args: note.args.to_owned(),
+            })
+        })
+        .collect::<Result<_, UsdtNoteError>>()?;
+    Ok((arch, notes))
+}
+
+/// Translates a virtual address to an offset in the file.
+fn file_offset(obj: &object::File<'_>, addr: u64) -> Result<u64, UsdtNoteError> {
+    obj.sections()
+        .find_map(|section| {
+            // Only sections that are loaded in memory have meaningful addresses,
+            // others such as debug sections can overlap them.
+            let SectionFlags::Elf { sh_flags } = section.flags() else {
+                return None;
+            };
+            if sh_flags & u64::from(object::elf::SHF_ALLOC) == 0 {
+                return None;
+            }
+            let (offset, _size) = section.file_range()?;
+            let start = section.address();
+            (start..start + section.size())
+                .contains(&addr)
+                .then(|| addr - start + offset)
+        })
+        .ok_or(UsdtNoteError::InvalidAddress(addr))
+}
+
+fn parse_usdt_notes(
+    mut data: &[u8],
+    endianness: Endianness,
+    is_64: bool,
+) -> Result<Vec<RawUsdtNote<'_>>, UsdtNoteError> {
+    // See https://sourceware.org/systemtap/wiki/UserSpaceProbeImplementation.
+    const NT_STAPSDT: u32 = 3;
+
+    fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], UsdtNoteError> {
+        let (head, tail) = data.split_at_checked(len).ok_or(UsdtNoteError::Truncated)?;
+        *data = tail;
+        Ok(head)
+    }
+
+    fn read_str<'a>(data: &mut &'a [u8]) -> Result<&'a str, UsdtNoteError> {
+        let len = data
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(UsdtNoteError::Truncated)?;
+        let s = take(data, len + 1)?;
+        str::from_utf8(&s[..len]).map_err(|_: str::Utf8Error| UsdtNoteError::InvalidString)
+    }
+
+    let read_u32 = |data: &mut &[u8]| -> Result<u32, UsdtNoteError> {
+        Ok(endianness.read_u32_bytes(take(data, 4)?.try_into().unwrap()))
+    };
+    let read_addr = |data: &mut &[u8]| -> Result<u64, UsdtNoteError> {
+        if is_64 {
+            Ok(endianness.read_u64_bytes(take(data, 8)?.try_into().unwrap()))
+        } else {
+            Ok(endianness
+                .read_u32_bytes(take(data, 4)?.try_into().unwrap())
+                .into())
+        }
+    };
+    let mut notes = Vec::new();
+    while !data.is_empty() {
+        let name_size = read_u32(&mut data)? as usize;
+        let desc_size = read_u32(&mut data)? as usize;
+        let note_type = read_u32(&mut data)?;
+        let name = take(&mut data, name_size)?;
+        take(&mut data, name_size.next_multiple_of(4) - name_size)?;
+        let mut desc = take(&mut data, desc_size)?;
+        // The padding of the last note may be missing.
+        let padding = (desc_size.next_multiple_of(4) - desc_size).min(data.len());
+        take(&mut data, padding)?;
+
+        if note_type != NT_STAPSDT || name != b"stapsdt\0" {
+            continue;
+        }
+        notes.push(RawUsdtNote {
+            pc: read_addr(&mut desc)?,
+            base: read_addr(&mut desc)?,
+            semaphore: read_addr(&mut desc)?,
+            provider: read_str(&mut desc)?,
+            name: read_str(&mut desc)?,
+            args: read_str(&mut desc)?,
+        });
+    }
+    Ok(notes)
+}
+
+fn parse_usdt_spec(arch: Architecture, args: &str, cookie: u64) -> Result<UsdtSpec, UsdtError> {
+    let parse_operand: fn(&str) -> Option<(u32, u64, i16)> = match arch {
+        Architecture::X86_64 => parse_operand_x86_64,
+        Architecture::Aarch64 => parse_operand_aarch64,
+        Architecture::Riscv64 => parse_operand_riscv64,
+        arch => {
+            // Probes without arguments can still be attached.
+            if !args.trim().is_empty() {
+                return Err(UsdtError::UnsupportedArch {
+                    arch: arch_name(arch),
+                });
+            }
+            |_: &str| None
+        }
+    };
+
+    let mut spec = UsdtSpec {
+        args: [UsdtArgSpec::default(); USDT_MAX_ARG_COUNT],
+        cookie,
+        arg_count: 0,
+        _pad: [0; 6],
+    };
+    let args = split_arg_specs(args);
+    if args.len() > USDT_MAX_ARG_COUNT {
+        return Err(UsdtError::TooManyArgs { count: args.len() });
+    }
+    for (slot, arg) in spec.args.iter_mut().zip(&args) {
+        *slot = parse_arg_spec(arg, parse_operand).ok_or_else(|| UsdtError::InvalidArgSpec {
+            spec: (*arg).to_owned(),
+        })?;
+    }
+    spec.arg_count = args.len() as u16;
+    Ok(spec)
+}
+
+/// Splits the argument specs of a probe, which are separated by spaces. Spaces
+/// can also appear inside brackets, eg: `8@[sp, 16]` on aarch64.
+fn split_arg_specs(args: &str) -> Vec<&str> {
+    let mut specs = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    for (i, c) in args.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            c if c.is_ascii_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    specs.push(&args[start..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(start) = start {
+        specs.push(&args[start..]);
+    }
+    specs
+}
+
+/// Parses an argument spec of the form `[-]SIZE@OPERAND`.
+fn parse_arg_spec(
+    arg: &str,
+    parse_operand: fn(&str) -> Option<(u32, u64, i16)>,
+) -> Option<UsdtArgSpec> {
+    let (size, operand) = arg.split_once('@')?;
+    let size: i8 = size.parse().ok()?;
+    let arg_signed = size < 0;
+    let size = size.unsigned_abs();
+    if !matches!(size, 1 | 2 | 4 | 8) {
+        return None;
+    }
+    let (arg_type, val_off, reg_off) = parse_operand(operand)?;
+    Some(UsdtArgSpec {
+        val_off,
+        arg_type,
+        reg_off,
+        arg_signed: arg_signed.into(),
+        arg_bitshift: 64 - 8 * size,
+    })
+}
+
+fn parse_int(s: &str) -> Option<i64> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let value = match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()? as i64,
+        None => s.parse().ok()?,
+    };
+    Some(if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    })
+}
+
+/// Parses AT&T syntax operands: `$imm`, `%reg` and `off(%reg)`.
+fn parse_operand_x86_64(operand: &str) -> Option<(u32, u64, i16)> {
+    // Offsets in `struct pt_regs`, with the names of the 64, 32, 16 and 8 bit
+    // views of each register.
+    const REGS: &[(i16, [&str; 4])] = &[
+        (0, ["r15", "r15d", "r15w", "r15b"]),
+        (8, ["r14", "r14d", "r14w", "r14b"]),
+        (16, ["r13", "r13d", "r13w", "r13b"]),
+        (24, ["r12", "r12d", "r12w", "r12b"]),
+        (32, ["rbp", "ebp", "bp", "bpl"]),
+        (40, ["rbx", "ebx", "bx", "bl"]),
+        (48, ["r11", "r11d", "r11w", "r11b"]),
+        (56, ["r10", "r10d", "r10w", "r10b"]),
+        (64, ["r9", "r9d", "r9w", "r9b"]),
+        (72, ["r8", "r8d", "r8w", "r8b"]),
+        (80, ["rax", "eax", "ax", "al"]),
+        (88, ["rcx", "ecx", "cx", "cl"]),
+        (96, ["rdx", "edx", "dx", "dl"]),
+        (104, ["rsi", "esi", "si", "sil"]),
+        (112, ["rdi", "edi", "di", "dil"]),
+        (128, ["rip", "eip", "", ""]),
+        (152, ["rsp", "esp", "sp", "spl"]),
+    ];
+    let reg = |name: &str| {
+        let name = name.strip_prefix('%')?;
+        REGS.iter()
+            .find_map(|(off, names)| names.contains(&name).then_some(*off))
+    };
+
+    if let Some(imm) = operand.strip_prefix('$') {
+        Some((USDT_ARG_CONST, parse_int(imm)? as u64, 0))
+    } else if let Some((off, rest)) = operand.split_once('(') {
+        let off = if off.is_empty() { 0 } else { parse_int(off)? };
+        let reg_off = reg(rest.strip_suffix(')')?)?;
+        Some((USDT_ARG_REG_DEREF, off as u64, reg_off))
+    } else {
+        Some((USDT_ARG_REG, 0, reg(operand)?))
+    }
+}
+
+/// Parses aarch64 operands: `imm`, `xN`, `[xN]` and `[xN, off]`.
+fn parse_operand_aarch64(operand: &str) -> Option<(u32, u64, i16)> {
+    // `struct user_pt_regs` starts with `regs[31]` followed by `sp`.
+    let reg = |name: &str| match name.trim() {
+        "sp" => Some(31 * 8),
+        name => {
+            let n: i16 = name.strip_prefix(['x', 'w'])?.parse().ok()?;
+            (n < 31).then_some(n * 8)
+        }
+    };
+
+    if let Some(inner) = operand
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        let (name, off) = match inner.split_once(',') {
+            Some((name, off)) => (name, parse_int(off.trim())?),
+            None => (inner, 0),
+        };
+        Some((USDT_ARG_REG_DEREF, off as u64, reg(name)?))
+    } else if let Some(reg_off) = reg(operand) {
+        Some((USDT_ARG_REG, 0, reg_off))
+    } else {
+        Some((USDT_ARG_CONST, parse_int(operand)? as u64, 0))
+    }
+}
+
+/// Parses riscv64 operands: `imm`, `reg` and `off(reg)`.
+fn parse_operand_riscv64(operand: &str) -> Option<(u32, u64, i16)> {
+    // In the order of `struct user_regs_struct`.
+    const REGS: &[&str] = &[
+        "pc", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+        "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+        "t5", "t6",
+    ];
+    let reg = |name: &str| {
+        let name = if name == "fp" { "s0" } else { name };
+        REGS.iter()
+            .position(|reg| *reg == name)
+            .map(|n| n as i16 * 8)
+    };
+
+    if let Some((off, rest)) = operand.split_once('(') {
+        let off = if off.is_empty() { 0 } else { parse_int(off)? };
+        let reg_off = reg(rest.strip_suffix(')')?)?;
+        Some((USDT_ARG_REG_DEREF, off as u64, reg_off))
+    } else if let Some(reg_off) = reg(operand) {
+        Some((USDT_ARG_REG, 0, reg_off))
+    } else {
+        Some((USDT_ARG_CONST, parse_int(operand)? as u64, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object::{
+        Architecture, BinaryFormat,
+        write::{Object, SectionKind},
+    };
+
+    use super::*;
+
+    fn arg(arg_type: u32, val_off: u64, reg_off: i16, signed: bool, size: u8) -> UsdtArgSpec {
+        UsdtArgSpec {
+            val_off,
+            arg_type,
+            reg_off,
+            arg_signed: signed.into(),
+            arg_bitshift: 64 - 8 * size,
+        }
+    }
+
+    fn args(spec: &UsdtSpec) -> &[UsdtArgSpec] {
+        &spec.args[..spec.arg_count as usize]
+    }
+
+    #[test]
+    fn test_parse_usdt_spec_x86_64() {
+        let spec = parse_usdt_spec(
+            Architecture::X86_64,
+            "-4@%edi 8@%rsi 8@-24(%rbp) -4@$-5 1@(%rax) 2@0x10(%r12)",
+            42,
+        )
+        .unwrap();
+        assert_eq!(spec.cookie, 42);
+        assert_eq!(
+            args(&spec),
+            [
+                arg(USDT_ARG_REG, 0, 112, true, 4),
+                arg(USDT_ARG_REG, 0, 104, false, 8),
+                arg(USDT_ARG_REG_DEREF, -24i64 as u64, 32, false, 8),
+                arg(USDT_ARG_CONST, -5i64 as u64, 0, true, 4),
+                arg(USDT_ARG_REG_DEREF, 0, 80, false, 1),
+                arg(USDT_ARG_REG_DEREF, 16, 24, false, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_usdt_spec_aarch64() {
+        let spec = parse_usdt_spec(
+            Architecture::Aarch64,
+            "-4@x0 8@[sp, 16] 8@[x1] 4@5 -8@[x29, -8]",
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            args(&spec),
+            [
+                arg(USDT_ARG_REG, 0, 0, true, 4),
+                arg(USDT_ARG_REG_DEREF, 16, 248, false, 8),
+                arg(USDT_ARG_REG_DEREF, 0, 8, false, 8),
+                arg(USDT_ARG_CONST, 5, 0, false, 4),
+                arg(USDT_ARG_REG_DEREF, -8i64 as u64, 232, true, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_usdt_spec_riscv64() {
+        let spec =
+            parse_usdt_spec(Architecture::Riscv64, "-4@a0 8@-24(s0) 8@8(sp) 4@-1", 0).unwrap();
+        assert_eq!(
+            args(&spec),
+            [
+                arg(USDT_ARG_REG, 0, 80, true, 4),
+                arg(USDT_ARG_REG_DEREF, -24i64 as u64, 64, false, 8),
+                arg(USDT_ARG_REG_DEREF, 8, 16, false, 8),
+                arg(USDT_ARG_CONST, -1i64 as u64, 0, false, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_usdt_spec_errors() {
+        assert_matches::assert_matches!(
+            parse_usdt_spec(Architecture::X86_64, "3@%rdi", 0),
+            Err(UsdtError::InvalidArgSpec { spec }) if spec == "3@%rdi"
+        );
+        assert_matches::assert_matches!(
+            parse_usdt_spec(Architecture::X86_64, "8@%xmm0", 0),
+            Err(UsdtError::InvalidArgSpec { spec }) if spec == "8@%xmm0"
+        );
+        assert_matches::assert_matches!(
+            parse_usdt_spec(Architecture::X86_64, "8@foo(%rip)", 0),
+            Err(UsdtError::InvalidArgSpec { .. })
+        );
+        assert_matches::assert_matches!(
+            parse_usdt_spec(Architecture::X86_64, &["8@%rdi"; 13].join(" "), 0),
+            Err(UsdtError::TooManyArgs { count: 13 })
+        );
+        assert_matches::assert_matches!(
+            parse_usdt_spec(Architecture::Mips, "8@$1", 0),
+            Err(UsdtError::UnsupportedArch { arch }) if arch == "mips"
+        );
+        assert_matches::assert_matches!(
+            parse_usdt_spec(Architecture::Mips, "", 0),
+            Ok(UsdtSpec { arg_count: 0, .. })
+        );
+    }
+
+    fn note(pc: u64, base: u64, semaphore: u64, strings: &[&str]) -> Vec<u8> {
+        let mut desc = Vec::new();
+        for addr in [pc, base, semaphore] {
+            desc.extend(addr.to_le_bytes());
+        }
+        for s in strings {
+            desc.extend(s.as_bytes());
+            desc.push(0);
+        }
+        let mut note = Vec::new();
+        note.extend(8u32.to_le_bytes());
+        note.extend((desc.len() as u32).to_le_bytes());
+        note.extend(3u32.to_le_bytes());
+        note.extend(b"stapsdt\0");
+        note.extend(&desc);
+        note.resize(note.len().next_multiple_of(4), 0);
+        note
+    }
+
+    #[test]
+    fn test_parse_usdt_notes() {
+        let mut data = note(0x1000, 0x2000, 0, &["libc", "setjmp", "8@%rdi -4@%esi"]);
+        data.extend(note(0x1010, 0x2000, 0x3000, &["libc", "longjmp", ""]));
+        assert_eq!(
+            parse_usdt_notes(&data, Endianness::Little, true).unwrap(),
+            [
+                RawUsdtNote {
+                    pc: 0x1000,
+                    base: 0x2000,
+                    semaphore: 0,
+                    provider: "libc",
+                    name: "setjmp",
+                    args: "8@%rdi -4@%esi",
+                },
+                RawUsdtNote {
+                    pc: 0x1010,
+                    base: 0x2000,
+                    semaphore: 0x3000,
+                    provider: "libc",
+                    name: "longjmp",
+                    args: "",
+                },
+            ]
+        );
+
+        data.truncate(data.len() - 8);
+        assert_matches::assert_matches!(
+            parse_usdt_notes(&data, Endianness::Little, true),
+            Err(UsdtNoteError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_usdt_notes() {
+        let mut obj = Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+        // Debug sections aren't loaded, their addresses overlap the ones of
+        // loaded sections.
+        let debug = obj.add_section(vec![], b".debug_info".to_vec(), SectionKind::Debug);
+        obj.append_section_data(debug, &[0; 32], 16);
+        let text = obj.add_section(vec![], b".text".to_vec(), SectionKind::Text);
+        let text_offset = obj.append_section_data(text, &[0x90; 32], 16);
+        let notes = obj.add_section(vec![], b".note.stapsdt".to_vec(), SectionKind::Note);
+        let mut data = note(text_offset + 4, 0, 0, &["test", "probe", "8@%rdi"]);
+        data.extend(note(text_offset + 8, 0, 0, &["test", "other", ""]));
+        obj.append_section_data(notes, &data, 4);
+        let elf = obj.write().unwrap();
+
+        let (text_file_offset, _) = object::File::parse(&*elf)
+            .unwrap()
+            .section_by_name(".text")
+            .unwrap()
+            .file_range()
+            .unwrap();
+        assert_eq!(
+            usdt_notes(&elf, "test", "probe").unwrap(),
+            (
+                Architecture::X86_64,
+                vec![UsdtNote {
+                    offset: text_file_offset + 4,
+                    semaphore_offset: None,
+                    args: "8@%rdi".to_owned(),
+                }]
+            )
+        );
+        assert_eq!(
+            usdt_notes(&elf, "test", "missing").unwrap(),
+            (Architecture::X86_64, vec![])
+        );
+    }
+}
@@ -9,11 +9,124 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use aya_obj::btf::{Btf, BtfError, BtfKind};
+use object::Endianness;
+
 use crate::{
     programs::{FdLink, Link, ProgramData, ProgramError},
-    sys::{SyscallError, bpf_raw_tracepoint_open},
+    sys::{
+        SyscallError, bpf_btf_get_fd_by_id, bpf_prog_get_info_by_fd, bpf_raw_tracepoint_open,
+        btf_obj_get_info_by_fd, btf_obj_get_name_by_fd, iter_btf_ids,
+    },
 };
 
+/// Retrieves the FD of the BTF object for the provided `prog_fd` and the BTF ID of the function
+/// with the name `func_name` within that BTF object.
+///
+/// Returns `None` if the program doesn't have BTF loaded to the kernel.
+pub(crate) fn get_prog_btf_info(
+    prog_fd: BorrowedFd<'_>,
+    func_name: &str,
+) -> Result<Option<(crate::MockableFd, u32)>, ProgramError> {
+    // retrieve program information
+    let info = bpf_prog_get_info_by_fd(prog_fd, &mut [])?;
+
+    // btf_id refers to the ID of the program btf that was loaded with bpf(BPF_BTF_LOAD)
+    if info.btf_id == 0 {
+        return Ok(None);
+    }
+
+    // the bpf fd of the BTF object
+    let btf_fd = bpf_btf_get_fd_by_id(info.btf_id)?;
+
+    // we need to read the btf bytes into a buffer but we don't know the size ahead of time.
+    // assume 4kb. if this is too small we can resize based on the size obtained in the response.
+    let mut buf = vec![0u8; 4096];
+    loop {
+        let info = btf_obj_get_info_by_fd(btf_fd.as_fd(), &mut buf)?;
+        let btf_size = info.btf_size as usize;
+        if btf_size > buf.len() {
+            buf.resize(btf_size, 0u8);
+            continue;
+        }
+        buf.truncate(btf_size);
+        break;
+    }
+
+    let btf = Btf::parse(&buf, Endianness::default())?;
+    let btf_id = btf.id_by_type_name_kind(func_name, BtfKind::Func)?;
+
+    Ok(Some((btf_fd, btf_id)))
+}
+
+/// Sets the BTF id of the kernel type the program attaches to.
+///
+/// The type is looked up in `btf` first, then in the BTF of the loaded kernel
+/// modules, using `btf` as their base. Type ids of module types are relative
+/// to the module BTF, so the fd of the module BTF is set too. If the type
+/// can't be found anywhere, the error of the lookup in `btf` is returned.
+pub(crate) fn set_attach_btf_id<T: Link>(
+    program_data: &mut ProgramData<T>,
+    btf: &Btf,
+    type_name: &str,
+    kind: BtfKind,
+) -> Result<(), ProgramError> {
+    let error = match btf.id_by_type_name_kind(type_name, kind) {
+        Ok(type_id) => {
+            program_data.attach_btf_obj_fd = None;
+            program_data.attach_btf_id = Some(type_id);
+            return Ok(());
+        }
+        Err(error @ BtfError::UnknownBtfTypeName { .. }) => error,
+        Err(error) => return Err(error.into()),
+    };
+
+    // Modules can be unloaded while we iterate, so errors aren't fatal.
+    for id in iter_btf_ids() {
+        let Ok(id) = id else {
+            continue;
+        };
+        let Ok(fd) = bpf_btf_get_fd_by_id(id) else {
+            continue;
+        };
+        // MODULE_NAME_LEN in the kernel.
+        let mut name = [0u8; 64];
+        let Ok(info) = btf_obj_get_name_by_fd(fd.as_fd(), &mut name) else {
+            continue;
+        };
+        if info.kernel_btf == 0 {
+            continue;
+        }
+        let Some(name) = CStr::from_bytes_until_nul(&name)
+            .ok()
+            .and_then(|name| name.to_str().ok())
+        else {
+            continue;
+        };
+        if name == "vmlinux" {
+            continue;
+        }
+        // Only the types of the module are searched, so the base isn't copied
+        // for every module.
+        let data = match std::fs::read(Path::new("/sys/kernel/btf").join(name)) {
+            Ok(data) => data,
+            Err(io_error) if io_error.kind() == io::ErrorKind::NotFound => continue,
+            Err(io_error) => return Err(io_error.into()),
+        };
+        match Btf::split_id_by_type_name_kind(btf, &data, Endianness::default(), type_name, kind) {
+            Ok(type_id) => {
+                program_data.attach_btf_obj_fd = Some(fd);
+                program_data.attach_btf_id = Some(type_id);
+                return Ok(());
+            }
+            Err(BtfError::UnknownBtfTypeName { .. }) => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    Err(error.into())
+}
+
 /// Attaches the program to a raw tracepoint.
 pub(crate) fn attach_raw_tracepoint<T: Link + From<FdLink>>(
     program_data: &mut ProgramData<T>,
@@ -21,7 +21,7 @@ use crate::{
     VerifierLogLevel,
     programs::{
         FdLink, Link, LinkError, ProgramData, ProgramError, ProgramType, define_link_wrapper,
-        id_as_key, impl_try_into_fdlink, load_program,
+        id_as_key, impl_try_into_fdlink, impl_update_program, load_program,
     },
     sys::{
         LinkTarget, NetlinkError, SyscallError, bpf_link_create, bpf_link_get_info_by_fd,
@@ -305,6 +305,7 @@ impl Link for XdpLinkInner {
 id_as_key!(XdpLinkInner, XdpLinkIdInner);
 
 impl_try_into_fdlink!(XdpLink, XdpLinkInner);
+impl_update_program!(XdpLink, XdpLinkInner);
 
 impl TryFrom<FdLink> for XdpLink {
     type Error = LinkError;
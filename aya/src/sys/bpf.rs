@@ -17,8 +17,8 @@ use aya_obj::{
     generated::{
         BPF_ADD, BPF_ALU64, BPF_CALL, BPF_DW, BPF_EXIT, BPF_F_REPLACE, BPF_IMM, BPF_JMP, BPF_K,
         BPF_LD, BPF_MEM, BPF_MOV, BPF_PSEUDO_MAP_VALUE, BPF_ST, BPF_X, bpf_attach_type, bpf_attr,
-        bpf_btf_info, bpf_cmd, bpf_func_id::*, bpf_insn, bpf_link_info, bpf_map_info, bpf_map_type,
-        bpf_prog_info, bpf_prog_type, bpf_stats_type,
+        bpf_btf_info, bpf_cmd, bpf_func_id::*, bpf_insn, bpf_iter_link_info, bpf_link_info,
+        bpf_map_info, bpf_map_type, bpf_prog_info, bpf_prog_type, bpf_stats_type,
     },
     maps::{LegacyMap, bpf_map_def},
 };
@@ -46,6 +46,7 @@ pub(crate) fn bpf_create_map(
     name: &CStr,
     def: &aya_obj::Map,
     btf_fd: Option<BorrowedFd<'_>>,
+    inner_map_fd: Option<BorrowedFd<'_>>,
 ) -> io::Result<crate::MockableFd> {
     let mut attr = unsafe { mem::zeroed::<bpf_attr>() };
 
@@ -55,6 +56,32 @@ pub(crate) fn bpf_create_map(
     u.value_size = def.value_size();
     u.max_entries = def.max_entries();
     u.map_flags = def.map_flags();
+    u.map_extra = def.map_extra();
+    if let Some(inner_map_fd) = inner_map_fd {
+        u.inner_map_fd = inner_map_fd.as_raw_fd() as u32;
+    }
+
+    // Local storage maps can't be created without BTF for their key and value,
+    // which legacy map definitions don't have.
+    let _local_storage_btf_fd = match (def, u.map_type.try_into()) {
+        (
+            aya_obj::Map::Legacy(_),
+            Ok(
+                bpf_map_type::BPF_MAP_TYPE_SK_STORAGE
+                | bpf_map_type::BPF_MAP_TYPE_INODE_STORAGE
+                | bpf_map_type::BPF_MAP_TYPE_TASK_STORAGE
+                | bpf_map_type::BPF_MAP_TYPE_CGRP_STORAGE,
+            ),
+        ) => {
+            let (btf, key_type_id, value_type_id) = Btf::local_storage(u.value_size);
+            let fd = bpf_load_btf(btf.to_bytes().as_slice(), &mut [], Default::default())?;
+            u.btf_fd = fd.as_raw_fd() as u32;
+            u.btf_key_type_id = key_type_id;
+            u.btf_value_type_id = value_type_id;
+            Some(fd)
+        }
+        _ => None,
+    };
 
     if let aya_obj::Map::Btf(m) = def {
         use bpf_map_type::*;
@@ -89,6 +116,12 @@ pub(crate) fn bpf_create_map(
         }
     }
 
+    // The value type of struct_ops maps is defined in the kernel BTF rather
+    // than in the program BTF.
+    if let aya_obj::Map::StructOps(m) = def {
+        u.btf_vmlinux_value_type_id = m.kernel_value_type_id();
+    }
+
     // https://github.com/torvalds/linux/commit/ad5b177bd73f5107d97c36f56395c4281fb6f089
     // The map name was added as a parameter in kernel 4.15+ so we skip adding it on
     // older kernels for compatibility
@@ -126,7 +159,7 @@ pub(crate) struct EbpfLoadProgramAttrs<'a> {
     pub(crate) insns: &'a [bpf_insn],
     pub(crate) license: &'a CStr,
     pub(crate) kernel_version: u32,
-    pub(crate) expected_attach_type: Option<bpf_attach_type>,
+    pub(crate) expected_attach_type: Option<u32>,
     pub(crate) prog_btf_fd: Option<BorrowedFd<'a>>,
     pub(crate) attach_btf_obj_fd: Option<BorrowedFd<'a>>,
     pub(crate) attach_btf_id: Option<u32>,
@@ -157,7 +190,7 @@ pub(crate) fn bpf_load_program(
     u.prog_flags = aya_attr.flags;
     u.prog_type = aya_attr.ty as u32;
     if let Some(v) = aya_attr.expected_attach_type {
-        u.expected_attach_type = v as u32;
+        u.expected_attach_type = v;
     }
     u.insns = aya_attr.insns.as_ptr() as u64;
     u.insn_cnt = aya_attr.insns.len() as u32;
@@ -380,15 +413,43 @@ pub(crate) enum LinkTarget<'f> {
     Fd(BorrowedFd<'f>),
     IfIndex(u32),
     Iter,
+    None,
 }
 
 // Models https://github.com/torvalds/linux/blob/2144da25/include/uapi/linux/bpf.h#L1724-L1782.
 pub(crate) enum BpfLinkCreateArgs<'a> {
     TargetBtfId(u32),
     // since kernel 5.15
-    PerfEvent { bpf_cookie: u64 },
+    PerfEvent {
+        bpf_cookie: u64,
+    },
+    // since kernel 5.18
+    KProbeMulti {
+        syms: &'a [*const c_char],
+        cookies: Option<&'a [u64]>,
+        flags: u32,
+    },
+    // since kernel 6.4
+    Netfilter {
+        pf: u32,
+        hooknum: u32,
+        priority: i32,
+        flags: u32,
+    },
     // since kernel 6.6
     Tcx(&'a LinkRef),
+    // since kernel 6.6
+    UProbeMulti {
+        path: &'a CStr,
+        offsets: &'a [u64],
+        cookies: Option<&'a [u64]>,
+        pid: u32,
+        flags: u32,
+    },
+    // since kernel 6.7
+    Netkit(&'a LinkRef),
+    // since kernel 5.9
+    IterInfo(&'a bpf_iter_link_info),
 }
 
 // since kernel 5.7
@@ -415,6 +476,9 @@ pub(crate) fn bpf_link_create(
         // iterators:
         // https://github.com/torvalds/linux/blob/v6.12/kernel/bpf/bpf_iter.c#L517-L518
         LinkTarget::Iter => {}
+        // Links without a target FD or ifindex, such as kprobe.multi links,
+        // whose targets are passed in `BpfLinkCreateArgs` instead.
+        LinkTarget::None => {}
     };
     attr.link_create.attach_type = attach_type as u32;
     attr.link_create.flags = flags;
@@ -427,6 +491,30 @@ pub(crate) fn bpf_link_create(
             BpfLinkCreateArgs::PerfEvent { bpf_cookie } => {
                 attr.link_create.__bindgen_anon_3.perf_event.bpf_cookie = bpf_cookie;
             }
+            BpfLinkCreateArgs::KProbeMulti {
+                syms,
+                cookies,
+                flags,
+            } => {
+                attr.link_create.__bindgen_anon_3.kprobe_multi.flags = flags;
+                attr.link_create.__bindgen_anon_3.kprobe_multi.cnt = syms.len() as u32;
+                attr.link_create.__bindgen_anon_3.kprobe_multi.syms = syms.as_ptr() as u64;
+                if let Some(cookies) = cookies {
+                    attr.link_create.__bindgen_anon_3.kprobe_multi.cookies =
+                        cookies.as_ptr() as u64;
+                }
+            }
+            BpfLinkCreateArgs::Netfilter {
+                pf,
+                hooknum,
+                priority,
+                flags,
+            } => {
+                attr.link_create.__bindgen_anon_3.netfilter.pf = pf;
+                attr.link_create.__bindgen_anon_3.netfilter.hooknum = hooknum;
+                attr.link_create.__bindgen_anon_3.netfilter.priority = priority;
+                attr.link_create.__bindgen_anon_3.netfilter.flags = flags;
+            }
             BpfLinkCreateArgs::Tcx(link_ref) => match link_ref {
                 LinkRef::Fd(fd) => {
                     attr.link_create
@@ -443,6 +531,47 @@ pub(crate) fn bpf_link_create(
                         .relative_id = id.to_owned();
                 }
             },
+            BpfLinkCreateArgs::UProbeMulti {
+                path,
+                offsets,
+                cookies,
+                pid,
+                flags,
+            } => {
+                attr.link_create.__bindgen_anon_3.uprobe_multi.path = path.as_ptr() as u64;
+                attr.link_create.__bindgen_anon_3.uprobe_multi.offsets = offsets.as_ptr() as u64;
+                attr.link_create.__bindgen_anon_3.uprobe_multi.cnt = offsets.len() as u32;
+                if let Some(cookies) = cookies {
+                    attr.link_create.__bindgen_anon_3.uprobe_multi.cookies =
+                        cookies.as_ptr() as u64;
+                }
+                attr.link_create.__bindgen_anon_3.uprobe_multi.pid = pid;
+                attr.link_create.__bindgen_anon_3.uprobe_multi.flags = flags;
+            }
+            BpfLinkCreateArgs::IterInfo(info) => {
+                attr.link_create.__bindgen_anon_3.__bindgen_anon_1.iter_info =
+                    std::ptr::from_ref(info) as u64;
+                attr.link_create
+                    .__bindgen_anon_3
+                    .__bindgen_anon_1
+                    .iter_info_len = mem::size_of::<bpf_iter_link_info>() as u32;
+            }
+            BpfLinkCreateArgs::Netkit(link_ref) => match link_ref {
+                LinkRef::Fd(fd) => {
+                    attr.link_create
+                        .__bindgen_anon_3
+                        .netkit
+                        .__bindgen_anon_1
+                        .relative_fd = fd.to_owned() as u32;
+                }
+                LinkRef::Id(id) => {
+                    attr.link_create
+                        .__bindgen_anon_3
+                        .netkit
+                        .__bindgen_anon_1
+                        .relative_id = id.to_owned();
+                }
+            },
         }
     }
 
@@ -471,6 +600,14 @@ pub(crate) fn bpf_link_update(
     unit_sys_bpf(bpf_cmd::BPF_LINK_UPDATE, &mut attr)
 }
 
+// since kernel 5.9
+pub(crate) fn bpf_link_detach(link_fd: BorrowedFd<'_>) -> io::Result<()> {
+    let mut attr = unsafe { mem::zeroed::<bpf_attr>() };
+    attr.link_detach.link_fd = link_fd.as_raw_fd() as u32;
+
+    unit_sys_bpf(bpf_cmd::BPF_LINK_DETACH, &mut attr)
+}
+
 pub(crate) fn bpf_prog_attach(
     prog_fd: BorrowedFd<'_>,
     target_fd: BorrowedFd<'_>,
@@ -562,6 +699,74 @@ pub(crate) fn bpf_prog_get_fd_by_id(prog_id: u32) -> Result<crate::MockableFd, S
     })
 }
 
+// Models the `test` member of `union bpf_attr`, used by BPF_PROG_TEST_RUN.
+pub(crate) struct ProgTestRunArgs<'a> {
+    pub(crate) data_in: &'a [u8],
+    pub(crate) data_out: &'a mut [u8],
+    // The kernel copies the context of syscall programs back into `ctx_in`.
+    pub(crate) ctx_in: &'a mut [u8],
+    pub(crate) ctx_out: &'a mut [u8],
+    pub(crate) repeat: u32,
+    pub(crate) cpu: u32,
+    pub(crate) flags: u32,
+}
+
+pub(crate) struct ProgTestRunOutput {
+    pub(crate) retval: u32,
+    pub(crate) data_size_out: u32,
+    pub(crate) ctx_size_out: u32,
+    pub(crate) duration: u32,
+}
+
+// since kernel 4.12
+pub(crate) fn bpf_prog_test_run(
+    prog_fd: BorrowedFd<'_>,
+    args: ProgTestRunArgs<'_>,
+) -> io::Result<ProgTestRunOutput> {
+    let mut attr = unsafe { mem::zeroed::<bpf_attr>() };
+    let ProgTestRunArgs {
+        data_in,
+        data_out,
+        ctx_in,
+        ctx_out,
+        repeat,
+        cpu,
+        flags,
+    } = args;
+
+    let test = unsafe { &mut attr.test };
+    test.prog_fd = prog_fd.as_raw_fd() as u32;
+    if !data_in.is_empty() {
+        test.data_in = data_in.as_ptr() as u64;
+        test.data_size_in = data_in.len() as u32;
+    }
+    if !data_out.is_empty() {
+        test.data_out = data_out.as_mut_ptr() as u64;
+        test.data_size_out = data_out.len() as u32;
+    }
+    if !ctx_in.is_empty() {
+        test.ctx_in = ctx_in.as_mut_ptr() as u64;
+        test.ctx_size_in = ctx_in.len() as u32;
+    }
+    if !ctx_out.is_empty() {
+        test.ctx_out = ctx_out.as_mut_ptr() as u64;
+        test.ctx_size_out = ctx_out.len() as u32;
+    }
+    test.repeat = repeat;
+    test.cpu = cpu;
+    test.flags = flags;
+
+    unit_sys_bpf(bpf_cmd::BPF_PROG_TEST_RUN, &mut attr)?;
+
+    let test = unsafe { &attr.test };
+    Ok(ProgTestRunOutput {
+        retval: test.retval,
+        data_size_out: test.data_size_out,
+        ctx_size_out: test.ctx_size_out,
+        duration: test.duration,
+    })
+}
+
 /// Introduced in kernel v4.13.
 fn bpf_obj_get_info_by_fd<T, F: FnOnce(&mut T)>(
     fd: BorrowedFd<'_>,
@@ -646,6 +851,16 @@ pub(crate) fn btf_obj_get_info_by_fd(
     })
 }
 
+pub(crate) fn btf_obj_get_name_by_fd(
+    fd: BorrowedFd<'_>,
+    name: &mut [u8],
+) -> Result<bpf_btf_info, SyscallError> {
+    bpf_obj_get_info_by_fd(fd, |info: &mut bpf_btf_info| {
+        info.name = name.as_mut_ptr() as _;
+        info.name_len = name.len() as _;
+    })
+}
+
 pub(crate) fn bpf_raw_tracepoint_open(
     name: Option<&CStr>,
     prog_fd: BorrowedFd<'_>,
@@ -861,19 +1076,19 @@ pub(crate) fn is_bpf_global_data_supported() -> bool {
     let u = unsafe { &mut attr.__bindgen_anon_3 };
 
     let map = MapData::create(
-        aya_obj::Map::Legacy(LegacyMap {
-            def: bpf_map_def {
+        aya_obj::Map::Legacy(LegacyMap::new(
+            bpf_map_def {
                 map_type: bpf_map_type::BPF_MAP_TYPE_ARRAY as u32,
                 key_size: 4,
                 value_size: 32,
                 max_entries: 1,
                 ..Default::default()
             },
-            section_index: 0,
-            section_kind: EbpfSectionKind::Maps,
-            symbol_index: None,
-            data: Vec::new(),
-        }),
+            0,
+            EbpfSectionKind::Maps,
+            None,
+            Vec::new(),
+        )),
         "aya_global",
         None,
     );
@@ -1176,6 +1391,11 @@ pub(crate) fn iter_map_ids() -> impl Iterator<Item = Result<u32, SyscallError>>
     iter_obj_ids(bpf_cmd::BPF_MAP_GET_NEXT_ID, "bpf_map_get_next_id")
 }
 
+/// Introduced in kernel v5.4.
+pub(crate) fn iter_btf_ids() -> impl Iterator<Item = Result<u32, SyscallError>> {
+    iter_obj_ids(bpf_cmd::BPF_BTF_GET_NEXT_ID, "bpf_btf_get_next_id")
+}
+
 /// Introduced in kernel v5.8.
 pub(crate) fn bpf_enable_stats(
     stats_type: bpf_stats_type,
@@ -19,9 +19,9 @@ pub(crate) use bpf::*;
 #[cfg(test)]
 pub(crate) use fake::*;
 pub use feature_probe::{is_map_supported, is_program_supported};
-#[doc(hidden)]
-pub use netlink::netlink_set_link_up;
 pub(crate) use netlink::*;
+#[doc(hidden)]
+pub use netlink::{netlink_add_netkit_pair, netlink_set_link_up};
 pub(crate) use perf_event::*;
 use thiserror::Error;
 
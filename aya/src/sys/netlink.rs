@@ -1,8 +1,9 @@
 use std::{
     collections::HashMap,
-    ffi::CStr,
+    ffi::{CStr, CString},
     io, mem,
-    os::fd::{AsRawFd as _, BorrowedFd, FromRawFd as _},
+    net::IpAddr,
+    os::fd::{AsRawFd as _, BorrowedFd, FromRawFd as _, RawFd},
     ptr, slice,
 };
 
@@ -12,18 +13,49 @@ use aya_obj::generated::{
     TCA_KIND, TCA_OPTIONS, XDP_FLAGS_REPLACE, ifinfomsg, nlmsgerr_attrs::NLMSGERR_ATTR_MSG, tcmsg,
 };
 use libc::{
-    AF_NETLINK, AF_UNSPEC, ETH_P_ALL, IFF_UP, IFLA_XDP, NETLINK_CAP_ACK, NETLINK_EXT_ACK,
-    NETLINK_ROUTE, NLA_ALIGNTO, NLA_F_NESTED, NLA_TYPE_MASK, NLM_F_ACK, NLM_F_CREATE, NLM_F_DUMP,
-    NLM_F_ECHO, NLM_F_EXCL, NLM_F_MULTI, NLM_F_REQUEST, NLMSG_DONE, NLMSG_ERROR, RTM_DELTFILTER,
-    RTM_GETTFILTER, RTM_NEWQDISC, RTM_NEWTFILTER, RTM_SETLINK, SOCK_RAW, SOL_NETLINK, getsockname,
-    nlattr, nlmsgerr, nlmsghdr, recv, send, setsockopt, sockaddr_nl, socket,
+    AF_INET, AF_INET6, AF_NETLINK, AF_UNSPEC, ETH_P_ALL, IFF_UP, IFLA_XDP, NETLINK_CAP_ACK,
+    NETLINK_EXT_ACK, NETLINK_ROUTE, NLA_ALIGNTO, NLA_F_NESTED, NLA_TYPE_MASK, NLM_F_ACK,
+    NLM_F_CREATE, NLM_F_DUMP, NLM_F_ECHO, NLM_F_EXCL, NLM_F_MULTI, NLM_F_REPLACE, NLM_F_REQUEST,
+    NLMSG_DONE, NLMSG_ERROR, RT_SCOPE_NOWHERE, RT_TABLE_UNSPEC, RTA_DST, RTA_GATEWAY, RTA_OIF,
+    RTA_TABLE, RTM_DELROUTE, RTM_DELTFILTER, RTM_GETROUTE, RTM_GETTFILTER, RTM_NEWLINK,
+    RTM_NEWQDISC, RTM_NEWROUTE, RTM_NEWTFILTER, RTM_SETLINK, RTN_UNICAST, RTPROT_BOOT, SOCK_RAW,
+    SOL_NETLINK, getsockname, nlattr, nlmsgerr, nlmsghdr, recv, send, setsockopt, sockaddr_nl,
+    socket,
 };
 use thiserror::Error;
 
-use crate::{programs::TcAttachType, util::tc_handler_make};
+use crate::{
+    programs::{TcAttachType, lwt::LwtHook},
+    util::tc_handler_make,
+};
 
 const NLA_HDR_LEN: usize = align_to(mem::size_of::<nlattr>(), NLA_ALIGNTO as usize);
 
+// Route encapsulation attributes, from linux/rtnetlink.h, linux/lwtunnel.h and
+// linux/seg6_local.h.
+const RTA_ENCAP_TYPE: u16 = 21;
+const RTA_ENCAP: u16 = 22;
+const LWTUNNEL_ENCAP_BPF: u16 = 6;
+const LWTUNNEL_ENCAP_SEG6_LOCAL: u16 = 7;
+const LWT_BPF_IN: u16 = 1;
+const LWT_BPF_OUT: u16 = 2;
+const LWT_BPF_XMIT: u16 = 3;
+const LWT_BPF_XMIT_HEADROOM: u16 = 4;
+const LWT_BPF_PROG_FD: u16 = 1;
+const LWT_BPF_PROG_NAME: u16 = 2;
+const SEG6_LOCAL_ACTION: u16 = 1;
+const SEG6_LOCAL_BPF: u16 = 8;
+const SEG6_LOCAL_ACTION_END_BPF: u32 = 15;
+const SEG6_LOCAL_BPF_PROG: u16 = 1;
+const SEG6_LOCAL_BPF_PROG_NAME: u16 = 2;
+
+// Link attributes, from linux/if_link.h.
+const IFLA_IFNAME: u16 = 3;
+const IFLA_LINKINFO: u16 = 18;
+const IFLA_INFO_KIND: u16 = 1;
+const IFLA_INFO_DATA: u16 = 2;
+const IFLA_NETKIT_PEER_INFO: u16 = 1;
+
 /// A private error type for internal use in this module.
 #[derive(Error, Debug)]
 pub(crate) enum NetlinkErrorInternal {
@@ -304,6 +336,345 @@ pub(crate) unsafe fn netlink_find_filter_with_name(
     Ok(filter_info)
 }
 
+/// A route through an interface.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub(crate) struct Route {
+    pub(crate) if_index: i32,
+    pub(crate) dst: IpAddr,
+    pub(crate) prefix_len: u8,
+    pub(crate) table: u32,
+    pub(crate) gateway: Option<IpAddr>,
+    pub(crate) scope: u8,
+}
+
+// Adds a route which runs the given lightweight tunnel program. If `replace` is
+// set, the existing route is replaced instead.
+pub(crate) unsafe fn netlink_route_add_lwt(
+    route: &Route,
+    replace: bool,
+    hook: &LwtHook,
+    prog_fd: BorrowedFd<'_>,
+    prog_name: &CStr,
+) -> Result<(), NetlinkError> {
+    let sock = NetlinkSocket::open()?;
+
+    let mut req = unsafe { mem::zeroed::<RouteRequest>() };
+
+    let nlmsg_len = mem::size_of::<nlmsghdr>() + mem::size_of::<rtmsg>();
+    let flags = if replace {
+        NLM_F_REPLACE
+    } else {
+        NLM_F_EXCL | NLM_F_CREATE
+    };
+    req.header = nlmsghdr {
+        nlmsg_len: nlmsg_len as u32,
+        nlmsg_flags: (NLM_F_REQUEST | NLM_F_ACK | flags) as u16,
+        nlmsg_type: RTM_NEWROUTE,
+        nlmsg_pid: 0,
+        nlmsg_seq: 1,
+    };
+    req.rt_info = route_msg(route);
+
+    let attrs_buf = unsafe { request_attributes(&mut req, nlmsg_len) };
+    let attrs_len = write_lwt_attrs(attrs_buf, route, hook, prog_fd.as_raw_fd(), prog_name)
+        .map_err(|e| NetlinkError(NetlinkErrorInternal::IoError(e)))?;
+
+    req.header.nlmsg_len += align_to(attrs_len, NLA_ALIGNTO as usize) as u32;
+    sock.send(&bytes_of(&req)[..req.header.nlmsg_len as usize])?;
+    sock.recv()?;
+
+    Ok(())
+}
+
+// Replaces an existing route with one which doesn't run any program.
+pub(crate) unsafe fn netlink_route_replace(route: &Route) -> Result<(), NetlinkError> {
+    let sock = NetlinkSocket::open()?;
+
+    let mut req = unsafe { mem::zeroed::<RouteRequest>() };
+
+    let nlmsg_len = mem::size_of::<nlmsghdr>() + mem::size_of::<rtmsg>();
+    req.header = nlmsghdr {
+        nlmsg_len: nlmsg_len as u32,
+        nlmsg_flags: (NLM_F_REQUEST | NLM_F_ACK | NLM_F_REPLACE) as u16,
+        nlmsg_type: RTM_NEWROUTE,
+        nlmsg_pid: 0,
+        nlmsg_seq: 1,
+    };
+    req.rt_info = route_msg(route);
+
+    let attrs_buf = unsafe { request_attributes(&mut req, nlmsg_len) };
+    let attrs_len = write_route_attrs(attrs_buf, route)
+        .map_err(|e| NetlinkError(NetlinkErrorInternal::IoError(e)))?;
+    req.header.nlmsg_len += align_to(attrs_len, NLA_ALIGNTO as usize) as u32;
+
+    sock.send(&bytes_of(&req)[..req.header.nlmsg_len as usize])?;
+    sock.recv()?;
+
+    Ok(())
+}
+
+// Returns the name of the lightweight tunnel program run by `route` at the
+// given hook, or `None` if the route doesn't exist or doesn't run a program at
+// this hook.
+pub(crate) unsafe fn netlink_route_lwt_prog_name(
+    route: &Route,
+    hook: &LwtHook,
+) -> Result<Option<CString>, NetlinkError> {
+    let sock = NetlinkSocket::open()?;
+
+    let mut req = unsafe { mem::zeroed::<RouteRequest>() };
+
+    let nlmsg_len = mem::size_of::<nlmsghdr>() + mem::size_of::<rtmsg>();
+    req.header = nlmsghdr {
+        nlmsg_len: nlmsg_len as u32,
+        nlmsg_flags: (NLM_F_REQUEST | NLM_F_DUMP) as u16,
+        nlmsg_type: RTM_GETROUTE,
+        nlmsg_pid: 0,
+        nlmsg_seq: 1,
+    };
+    req.rt_info.rtm_family = route_msg(route).rtm_family;
+
+    sock.send(&bytes_of(&req)[..req.header.nlmsg_len as usize])?;
+    for msg in sock.recv()? {
+        if msg.header.nlmsg_type != RTM_NEWROUTE {
+            continue;
+        }
+        if let Some(name) = lwt_prog_name(route, hook, &msg.data)
+            .map_err(|e| NetlinkError(NetlinkErrorInternal::NlAttrError(e)))?
+        {
+            return Ok(Some(name.to_owned()));
+        }
+    }
+
+    Ok(None)
+}
+
+// Returns the name of the lightweight tunnel program run at the given hook by
+// the route in `data`, the payload of a `RTM_NEWROUTE` message, if it is
+// `route`.
+fn lwt_prog_name<'a>(
+    route: &Route,
+    hook: &LwtHook,
+    data: &'a [u8],
+) -> Result<Option<&'a CStr>, NlAttrError> {
+    let rt_msg_len = mem::size_of::<rtmsg>();
+    if data.len() < rt_msg_len {
+        return Err(NlAttrError::InvalidBufferLength {
+            size: data.len(),
+            expected: rt_msg_len,
+        });
+    }
+    let rt_msg: rtmsg = unsafe { ptr::read_unaligned(data.as_ptr().cast()) };
+    let attrs = parse_attrs(&data[rt_msg_len..])?;
+
+    let table = match attrs.get(&RTA_TABLE) {
+        Some(table) => table
+            .data
+            .try_into()
+            .map(u32::from_ne_bytes)
+            .unwrap_or_default(),
+        None => rt_msg.rtm_table.into(),
+    };
+    let dst = match route.dst {
+        IpAddr::V4(dst) => dst.octets().to_vec(),
+        IpAddr::V6(dst) => dst.octets().to_vec(),
+    };
+    // The default route has no destination.
+    let same_dst = match attrs.get(&RTA_DST) {
+        Some(attr) => attr.data == dst,
+        None => route.prefix_len == 0,
+    };
+    let same_oif = attrs
+        .get(&RTA_OIF)
+        .is_some_and(|attr| attr.data == route.if_index.to_ne_bytes());
+    if rt_msg.rtm_family != route_msg(route).rtm_family
+        || rt_msg.rtm_dst_len != route.prefix_len
+        || table != route.table
+        || !same_dst
+        || !same_oif
+    {
+        return Ok(None);
+    }
+
+    let LwtAttrs {
+        encap_type,
+        prog_attr,
+        name_attr,
+        ..
+    } = LwtAttrs::new(hook);
+    if attrs
+        .get(&RTA_ENCAP_TYPE)
+        .is_none_or(|attr| attr.data != encap_type.to_ne_bytes())
+    {
+        return Ok(None);
+    }
+    let Some(encap) = attrs.get(&RTA_ENCAP) else {
+        return Ok(None);
+    };
+    let encap = parse_attrs(encap.data)?;
+    let Some(prog) = encap.get(&prog_attr) else {
+        return Ok(None);
+    };
+    let prog = parse_attrs(prog.data)?;
+    Ok(prog
+        .get(&name_attr)
+        .and_then(|name| CStr::from_bytes_until_nul(name.data).ok()))
+}
+
+/// The route attributes describing a lightweight tunnel program.
+struct LwtAttrs {
+    encap_type: u16,
+    prog_attr: u16,
+    fd_attr: u16,
+    name_attr: u16,
+}
+
+impl LwtAttrs {
+    fn new(hook: &LwtHook) -> Self {
+        let (encap_type, prog_attr, fd_attr, name_attr) = match hook {
+            LwtHook::In => (
+                LWTUNNEL_ENCAP_BPF,
+                LWT_BPF_IN,
+                LWT_BPF_PROG_FD,
+                LWT_BPF_PROG_NAME,
+            ),
+            LwtHook::Out => (
+                LWTUNNEL_ENCAP_BPF,
+                LWT_BPF_OUT,
+                LWT_BPF_PROG_FD,
+                LWT_BPF_PROG_NAME,
+            ),
+            LwtHook::Xmit { .. } => (
+                LWTUNNEL_ENCAP_BPF,
+                LWT_BPF_XMIT,
+                LWT_BPF_PROG_FD,
+                LWT_BPF_PROG_NAME,
+            ),
+            LwtHook::Seg6Local => (
+                LWTUNNEL_ENCAP_SEG6_LOCAL,
+                SEG6_LOCAL_BPF,
+                SEG6_LOCAL_BPF_PROG,
+                SEG6_LOCAL_BPF_PROG_NAME,
+            ),
+        };
+        Self {
+            encap_type,
+            prog_attr,
+            fd_attr,
+            name_attr,
+        }
+    }
+}
+
+// Writes the attributes of `route` which runs the given lightweight tunnel
+// program.
+fn write_lwt_attrs(
+    buf: &mut [u8],
+    route: &Route,
+    hook: &LwtHook,
+    prog_fd: RawFd,
+    prog_name: &CStr,
+) -> Result<usize, io::Error> {
+    let mut attrs_len = write_route_attrs(buf, route)?;
+
+    let LwtAttrs {
+        encap_type,
+        prog_attr,
+        fd_attr,
+        name_attr,
+    } = LwtAttrs::new(hook);
+    attrs_len += write_attr(buf, attrs_len, RTA_ENCAP_TYPE, encap_type)?;
+
+    // add RTA_ENCAP which includes the program fd and name, nested in the
+    // attribute of the hook
+    let mut encap = NestedAttrs::new(&mut buf[attrs_len..], RTA_ENCAP);
+    if let LwtHook::Seg6Local = hook {
+        encap.write_attr(SEG6_LOCAL_ACTION, SEG6_LOCAL_ACTION_END_BPF)?;
+    }
+    encap.write_nested(prog_attr, |prog| {
+        prog.write_attr(fd_attr, prog_fd)?;
+        prog.write_attr_bytes(name_attr, prog_name.to_bytes_with_nul())?;
+        Ok(())
+    })?;
+    if let LwtHook::Xmit { headroom } = hook {
+        encap.write_attr(LWT_BPF_XMIT_HEADROOM, *headroom)?;
+    }
+    attrs_len += encap.finish()?;
+
+    Ok(attrs_len)
+}
+
+pub(crate) unsafe fn netlink_route_del(route: &Route) -> Result<(), NetlinkError> {
+    let sock = NetlinkSocket::open()?;
+
+    let mut req = unsafe { mem::zeroed::<RouteRequest>() };
+
+    let nlmsg_len = mem::size_of::<nlmsghdr>() + mem::size_of::<rtmsg>();
+    req.header = nlmsghdr {
+        nlmsg_len: nlmsg_len as u32,
+        nlmsg_flags: (NLM_F_REQUEST | NLM_F_ACK) as u16,
+        nlmsg_type: RTM_DELROUTE,
+        nlmsg_pid: 0,
+        nlmsg_seq: 1,
+    };
+    req.rt_info = route_msg(route);
+    req.rt_info.rtm_scope = RT_SCOPE_NOWHERE;
+
+    let attrs_buf = unsafe { request_attributes(&mut req, nlmsg_len) };
+    let attrs_len = write_route_attrs(attrs_buf, route)
+        .map_err(|e| NetlinkError(NetlinkErrorInternal::IoError(e)))?;
+    req.header.nlmsg_len += align_to(attrs_len, NLA_ALIGNTO as usize) as u32;
+
+    sock.send(&bytes_of(&req)[..req.header.nlmsg_len as usize])?;
+    sock.recv()?;
+
+    Ok(())
+}
+
+fn route_msg(route: &Route) -> rtmsg {
+    rtmsg {
+        rtm_family: match route.dst {
+            IpAddr::V4(_) => AF_INET,
+            IpAddr::V6(_) => AF_INET6,
+        } as u8,
+        rtm_dst_len: route.prefix_len,
+        rtm_src_len: 0,
+        rtm_tos: 0,
+        // Tables which don't fit in the header are set with RTA_TABLE.
+        rtm_table: u8::try_from(route.table).unwrap_or(RT_TABLE_UNSPEC),
+        rtm_protocol: RTPROT_BOOT,
+        rtm_scope: route.scope,
+        rtm_type: RTN_UNICAST,
+        rtm_flags: 0,
+    }
+}
+
+fn write_ip_attr(
+    buf: &mut [u8],
+    offset: usize,
+    attr_type: u16,
+    addr: IpAddr,
+) -> Result<usize, io::Error> {
+    match addr {
+        IpAddr::V4(addr) => write_attr_bytes(buf, offset, attr_type, &addr.octets()),
+        IpAddr::V6(addr) => write_attr_bytes(buf, offset, attr_type, &addr.octets()),
+    }
+}
+
+// Writes the RTA_DST, RTA_OIF, RTA_GATEWAY and RTA_TABLE attributes identifying
+// a route.
+fn write_route_attrs(buf: &mut [u8], route: &Route) -> Result<usize, io::Error> {
+    let mut attrs_len = write_ip_attr(buf, 0, RTA_DST, route.dst)?;
+    attrs_len += write_attr(buf, attrs_len, RTA_OIF, route.if_index)?;
+    if let Some(gateway) = route.gateway {
+        attrs_len += write_ip_attr(buf, attrs_len, RTA_GATEWAY, gateway)?;
+    }
+    if route.table > u8::MAX.into() {
+        attrs_len += write_attr(buf, attrs_len, RTA_TABLE, route.table)?;
+    }
+    Ok(attrs_len)
+}
+
 #[doc(hidden)]
 pub unsafe fn netlink_set_link_up(if_index: i32) -> Result<(), NetlinkError> {
     let sock = NetlinkSocket::open()?;
@@ -330,11 +701,60 @@ pub unsafe fn netlink_set_link_up(if_index: i32) -> Result<(), NetlinkError> {
     Ok(())
 }
 
+#[doc(hidden)]
+pub unsafe fn netlink_add_netkit_pair(primary: &CStr, peer: &CStr) -> Result<(), NetlinkError> {
+    let sock = NetlinkSocket::open()?;
+
+    // Safety: Request is POD so this is safe
+    let mut req = unsafe { mem::zeroed::<Request>() };
+
+    let nlmsg_len = mem::size_of::<nlmsghdr>() + mem::size_of::<ifinfomsg>();
+    req.header = nlmsghdr {
+        nlmsg_len: nlmsg_len as u32,
+        nlmsg_flags: (NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE) as u16,
+        nlmsg_type: RTM_NEWLINK,
+        nlmsg_pid: 0,
+        nlmsg_seq: 1,
+    };
+    req.if_info.ifi_family = AF_UNSPEC as u8;
+
+    let attrs_buf = unsafe { request_attributes(&mut req, nlmsg_len) };
+    let mut attrs_len = write_attr_bytes(attrs_buf, 0, IFLA_IFNAME, primary.to_bytes_with_nul())
+        .map_err(|e| NetlinkError(NetlinkErrorInternal::IoError(e)))?;
+
+    // add IFLA_LINKINFO which includes the kind of the link and the peer, whose
+    // info starts with its own ifinfomsg
+    let mut link_info = NestedAttrs::new(&mut attrs_buf[attrs_len..], IFLA_LINKINFO);
+    link_info
+        .write_attr_bytes(IFLA_INFO_KIND, c"netkit".to_bytes_with_nul())
+        .map_err(|e| NetlinkError(NetlinkErrorInternal::IoError(e)))?;
+    link_info
+        .write_nested(IFLA_INFO_DATA, |data| {
+            data.write_nested(IFLA_NETKIT_PEER_INFO, |peer_info| {
+                let if_info = unsafe { mem::zeroed::<ifinfomsg>() };
+                peer_info.write_bytes(bytes_of(&if_info))?;
+                peer_info.write_attr_bytes(IFLA_IFNAME, peer.to_bytes_with_nul())?;
+                Ok(())
+            })?;
+            Ok(())
+        })
+        .map_err(|e| NetlinkError(NetlinkErrorInternal::IoError(e)))?;
+    attrs_len += link_info
+        .finish()
+        .map_err(|e| NetlinkError(NetlinkErrorInternal::IoError(e)))?;
+
+    req.header.nlmsg_len += align_to(attrs_len, NLA_ALIGNTO as usize) as u32;
+    sock.send(&bytes_of(&req)[..req.header.nlmsg_len as usize])?;
+    sock.recv()?;
+
+    Ok(())
+}
+
 #[repr(C)]
 struct Request {
     header: nlmsghdr,
     if_info: ifinfomsg,
-    attrs: [u8; 64],
+    attrs: [u8; 128],
 }
 
 #[repr(C)]
@@ -344,6 +764,35 @@ struct TcRequest {
     attrs: [u8; 64],
 }
 
+// Models struct rtmsg from linux/rtnetlink.h.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct rtmsg {
+    rtm_family: u8,
+    rtm_dst_len: u8,
+    rtm_src_len: u8,
+    rtm_tos: u8,
+    rtm_table: u8,
+    rtm_protocol: u8,
+    rtm_scope: u8,
+    rtm_type: u8,
+    rtm_flags: u32,
+}
+
+// The size of the attributes of a route running a lightweight tunnel program,
+// in the worst case: RTA_DST (20) with an IPv6 address, RTA_OIF (8), RTA_GATEWAY
+// (20), RTA_TABLE (8), RTA_ENCAP_TYPE (8) and RTA_ENCAP (44) with the action or
+// headroom (8) and the program fd (8) and name, truncated to BPF_OBJ_NAME_LEN
+// (20), nested in the hook attribute (4).
+const ROUTE_ATTRS_LEN: usize = 108;
+
+#[repr(C)]
+struct RouteRequest {
+    header: nlmsghdr,
+    rt_info: rtmsg,
+    attrs: [u8; ROUTE_ATTRS_LEN],
+}
+
 struct NetlinkSocket {
     sock: crate::MockableFd,
     _nl_pid: u32,
@@ -570,6 +1019,24 @@ impl<'a> NestedAttrs<'a> {
         Ok(size)
     }
 
+    fn write_bytes(&mut self, value: &[u8]) -> Result<usize, io::Error> {
+        let size = write_bytes(self.buf, self.offset, value)?;
+        self.offset += size;
+        Ok(size)
+    }
+
+    fn write_nested(
+        &mut self,
+        attr_type: u16,
+        write: impl FnOnce(&mut NestedAttrs<'_>) -> Result<(), io::Error>,
+    ) -> Result<usize, io::Error> {
+        let mut nested = NestedAttrs::new(&mut self.buf[self.offset..], attr_type);
+        write(&mut nested)?;
+        let size = align_to(nested.finish()?, NLA_ALIGNTO as usize);
+        self.offset += size;
+        Ok(size)
+    }
+
     fn finish(self) -> Result<usize, io::Error> {
         let nla_len = self.offset;
         let attr = nlattr {
@@ -621,7 +1088,10 @@ fn write_attr_header(buf: &mut [u8], offset: usize, attr: nlattr) -> Result<usiz
 fn write_bytes(buf: &mut [u8], offset: usize, value: &[u8]) -> Result<usize, io::Error> {
     let align_len = align_to(value.len(), NLA_ALIGNTO as usize);
     if offset + align_len > buf.len() {
-        return Err(io::Error::other("no space left"));
+        return Err(io::Error::other(format!(
+            "netlink request buffer too small: {align_len} bytes needed at offset {offset}, the buffer is {} bytes long",
+            buf.len()
+        )));
     }
 
     buf[offset..offset + value.len()].copy_from_slice(value);
@@ -724,7 +1194,13 @@ fn bytes_of<T>(val: &T) -> &[u8] {
 
 #[cfg(test)]
 mod tests {
-    use std::ffi::CString;
+    use std::{
+        ffi::CString,
+        net::{Ipv4Addr, Ipv6Addr},
+    };
+
+    use aya_obj::generated::BPF_OBJ_NAME_LEN;
+    use libc::{RT_SCOPE_LINK, RT_TABLE_MAIN};
 
     use super::*;
 
@@ -855,4 +1331,141 @@ mod tests {
         let name = CStr::from_bytes_with_nul(inner.data).unwrap();
         assert_eq!(name.to_str().unwrap(), "foo");
     }
+
+    fn route(dst: IpAddr) -> Route {
+        Route {
+            if_index: 3,
+            dst,
+            prefix_len: 24,
+            table: RT_TABLE_MAIN.into(),
+            gateway: None,
+            scope: RT_SCOPE_LINK,
+        }
+    }
+
+    #[test]
+    fn test_lwt_attrs_xmit() {
+        let mut buf = [0; ROUTE_ATTRS_LEN];
+        let name = CString::new("foo").unwrap();
+        let dst = Ipv4Addr::new(10, 0, 0, 0);
+        let len = write_lwt_attrs(
+            &mut buf,
+            &route(dst.into()),
+            &LwtHook::Xmit { headroom: 16 },
+            42,
+            &name,
+        )
+        .unwrap();
+
+        let attrs = parse_attrs(&buf[..len]).unwrap();
+        assert_eq!(attrs.len(), 4);
+        assert_eq!(attrs[&RTA_DST].data, dst.octets());
+        assert_eq!(attrs[&RTA_OIF].data, 3i32.to_ne_bytes());
+        assert_eq!(
+            attrs[&RTA_ENCAP_TYPE].data,
+            LWTUNNEL_ENCAP_BPF.to_ne_bytes()
+        );
+
+        let encap = &attrs[&RTA_ENCAP];
+        assert_eq!(encap.header.nla_type, NLA_F_NESTED as u16 | RTA_ENCAP);
+        let encap = parse_attrs(encap.data).unwrap();
+        assert_eq!(encap.len(), 2);
+        assert_eq!(encap[&LWT_BPF_XMIT_HEADROOM].data, 16u32.to_ne_bytes());
+
+        let prog = parse_attrs(encap[&LWT_BPF_XMIT].data).unwrap();
+        assert_eq!(prog[&LWT_BPF_PROG_FD].data, 42i32.to_ne_bytes());
+        assert_eq!(prog[&LWT_BPF_PROG_NAME].data, name.to_bytes_with_nul());
+    }
+
+    #[test]
+    fn test_lwt_attrs_seg6local() {
+        let mut buf = [0; ROUTE_ATTRS_LEN];
+        let name = CString::new("foo").unwrap();
+        let dst = Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 1);
+        let len =
+            write_lwt_attrs(&mut buf, &route(dst.into()), &LwtHook::Seg6Local, 42, &name).unwrap();
+
+        let attrs = parse_attrs(&buf[..len]).unwrap();
+        assert_eq!(attrs[&RTA_DST].data, dst.octets());
+        assert_eq!(attrs[&RTA_OIF].data, 3i32.to_ne_bytes());
+        assert_eq!(
+            attrs[&RTA_ENCAP_TYPE].data,
+            LWTUNNEL_ENCAP_SEG6_LOCAL.to_ne_bytes()
+        );
+
+        let encap = parse_attrs(attrs[&RTA_ENCAP].data).unwrap();
+        assert_eq!(encap.len(), 2);
+        assert_eq!(
+            encap[&SEG6_LOCAL_ACTION].data,
+            SEG6_LOCAL_ACTION_END_BPF.to_ne_bytes()
+        );
+
+        let prog = parse_attrs(encap[&SEG6_LOCAL_BPF].data).unwrap();
+        assert_eq!(prog[&SEG6_LOCAL_BPF_PROG].data, 42i32.to_ne_bytes());
+        assert_eq!(
+            prog[&SEG6_LOCAL_BPF_PROG_NAME].data,
+            name.to_bytes_with_nul()
+        );
+    }
+
+    #[test]
+    fn test_lwt_attrs_worst_case() {
+        let mut buf = [0; ROUTE_ATTRS_LEN];
+        let name = CString::new("a".repeat(BPF_OBJ_NAME_LEN as usize - 1)).unwrap();
+        let gateway = Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 2);
+        let route = Route {
+            table: 1000,
+            gateway: Some(gateway.into()),
+            ..route(Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 1).into())
+        };
+        let len =
+            write_lwt_attrs(&mut buf, &route, &LwtHook::Xmit { headroom: 16 }, 42, &name).unwrap();
+        assert_eq!(len, ROUTE_ATTRS_LEN);
+
+        let attrs = parse_attrs(&buf[..len]).unwrap();
+        assert_eq!(attrs[&RTA_GATEWAY].data, gateway.octets());
+        assert_eq!(attrs[&RTA_TABLE].data, 1000u32.to_ne_bytes());
+
+        // a longer name doesn't fit
+        let name = CString::new("a".repeat(BPF_OBJ_NAME_LEN as usize)).unwrap();
+        assert!(
+            write_lwt_attrs(&mut buf, &route, &LwtHook::Xmit { headroom: 16 }, 42, &name,).is_err()
+        );
+    }
+
+    #[test]
+    fn test_lwt_prog_name() {
+        let name = CString::new("foo").unwrap();
+        let route = route(Ipv4Addr::new(10, 0, 0, 0).into());
+        let hook = LwtHook::Xmit { headroom: 16 };
+
+        let mut buf = [0; mem::size_of::<rtmsg>() + ROUTE_ATTRS_LEN];
+        let (rt_msg, attrs) = buf.split_at_mut(mem::size_of::<rtmsg>());
+        rt_msg.copy_from_slice(bytes_of(&route_msg(&route)));
+        let len = write_lwt_attrs(attrs, &route, &hook, 42, &name).unwrap();
+        let msg = &buf[..mem::size_of::<rtmsg>() + len];
+
+        assert_eq!(
+            lwt_prog_name(&route, &hook, msg).unwrap(),
+            Some(name.as_c_str())
+        );
+        // another hook
+        assert_eq!(lwt_prog_name(&route, &LwtHook::In, msg).unwrap(), None);
+        // another route
+        let other = Route {
+            prefix_len: 16,
+            ..route
+        };
+        assert_eq!(lwt_prog_name(&other, &hook, msg).unwrap(), None);
+        let other = Route {
+            if_index: 4,
+            ..route
+        };
+        assert_eq!(lwt_prog_name(&other, &hook, msg).unwrap(), None);
+        let other = Route {
+            table: 1000,
+            ..route
+        };
+        assert_eq!(lwt_prog_name(&other, &hook, msg).unwrap(), None);
+    }
 }
@@ -64,15 +64,22 @@ pub(crate) fn perf_event_open_probe(
     ret_bit: Option<u32>,
     name: &OsStr,
     offset: u64,
+    ref_ctr_offset: Option<u64>,
     pid: Option<pid_t>,
 ) -> io::Result<crate::MockableFd> {
     use std::os::unix::ffi::OsStrExt as _;
 
+    // See PERF_UPROBE_REF_CTR_OFFSET_SHIFT in kernel/events/core.c.
+    const REF_CTR_OFFSET_SHIFT: u32 = 32;
+
     let mut attr = unsafe { mem::zeroed::<perf_event_attr>() };
 
     if let Some(ret_bit) = ret_bit {
         attr.config = 1 << ret_bit;
     }
+    if let Some(ref_ctr_offset) = ref_ctr_offset {
+        attr.config |= ref_ctr_offset << REF_CTR_OFFSET_SHIFT;
+    }
 
     let c_name = CString::new(name.as_bytes()).unwrap();
 
@@ -320,6 +320,9 @@ fn parse_kernel_symbols(reader: impl BufRead) -> Result<BTreeMap<u64, String>, i
 
 /// Returns the prefix used by syscalls.
 ///
+/// To attach a probe to a syscall, use
+/// [`KProbe::attach_syscall`](crate::programs::KProbe::attach_syscall) instead.
+///
 /// # Example
 ///
 /// ```no_run
@@ -461,7 +464,19 @@ impl MMap {
         flags: c_int,
         offset: off_t,
     ) -> Result<Self, SyscallError> {
-        match unsafe { mmap(ptr::null_mut(), len, prot, flags, fd, offset) } {
+        Self::new_at(ptr::null_mut(), fd, len, prot, flags, offset)
+    }
+
+    /// Maps `fd`, using `addr` as a hint for the address of the mapping.
+    pub(crate) fn new_at(
+        addr: *mut c_void,
+        fd: BorrowedFd<'_>,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        offset: off_t,
+    ) -> Result<Self, SyscallError> {
+        match unsafe { mmap(addr, len, prot, flags, fd, offset) } {
             MAP_FAILED => Err(SyscallError {
                 call: "mmap",
                 io_error: io::Error::last_os_error(),
@@ -501,6 +516,10 @@ impl MMap {
     pub(crate) fn ptr(&self) -> ptr::NonNull<c_void> {
         self.ptr
     }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
 }
 
 impl AsRef<[u8]> for MMap {
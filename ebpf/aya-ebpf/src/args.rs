@@ -475,6 +475,49 @@ impl_from_pt_regs!(i64);
 impl_from_pt_regs!(usize);
 impl_from_pt_regs!(isize);
 
+/// A trait that indicates a valid type for a syscall argument.
+///
+/// Syscall arguments are passed in registers, so this is implemented for the
+/// types which fit in a register. Users should not implement this trait.
+pub trait FromSyscallArg: Sized {
+    /// Coerces a `T` from the value of the register holding a syscall argument.
+    fn from_syscall_arg(reg: u64) -> Self;
+}
+
+impl<T> FromSyscallArg for *const T {
+    fn from_syscall_arg(reg: u64) -> Self {
+        reg as *const T
+    }
+}
+
+impl<T> FromSyscallArg for *mut T {
+    fn from_syscall_arg(reg: u64) -> Self {
+        reg as *mut T
+    }
+}
+
+/// Helper macro to implement [`FromSyscallArg`] for a primitive type.
+macro_rules! impl_from_syscall_arg {
+    ($type:ident) => {
+        impl FromSyscallArg for $type {
+            fn from_syscall_arg(reg: u64) -> Self {
+                reg as $type
+            }
+        }
+    };
+}
+
+impl_from_syscall_arg!(u8);
+impl_from_syscall_arg!(u16);
+impl_from_syscall_arg!(u32);
+impl_from_syscall_arg!(u64);
+impl_from_syscall_arg!(i8);
+impl_from_syscall_arg!(i16);
+impl_from_syscall_arg!(i32);
+impl_from_syscall_arg!(i64);
+impl_from_syscall_arg!(usize);
+impl_from_syscall_arg!(isize);
+
 /// A Rust wrapper on `bpf_raw_tracepoint_args`.
 pub struct RawTracepointArgs {
     args: *mut bpf_raw_tracepoint_args,
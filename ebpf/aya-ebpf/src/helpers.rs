@@ -20,6 +20,7 @@ pub use aya_ebpf_bindings::helpers as generated;
 pub use generated::*;
 
 use crate::{
+    bindings::seq_file,
     check_bounds_signed,
     cty::{c_char, c_long, c_void},
 };
@@ -822,6 +823,86 @@ pub unsafe fn bpf_printk_impl<const FMT_LEN: usize, const NUM_ARGS: usize>(
     }
 }
 
+/// Writes `data` to the `seq_file` of an iterator program.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #![expect(dead_code)]
+/// # use aya_ebpf::{cty::c_long, helpers::bpf_seq_write, programs::{IterContext, iter::bpf_iter__task}};
+/// fn try_dump_task(ctx: IterContext<bpf_iter__task>) -> Result<(), c_long> {
+///     unsafe { bpf_seq_write(ctx.seq(), b"task\n") }
+/// }
+/// ```
+///
+/// # Errors
+///
+/// On overflow of the buffer of the `seq_file`, the error is `-EOVERFLOW`. The
+/// object is visited again after the buffer is flushed.
+#[inline]
+pub unsafe fn bpf_seq_write(seq: *mut seq_file, data: &[u8]) -> Result<(), c_long> {
+    let ret =
+        unsafe { generated::bpf_seq_write(seq, data.as_ptr() as *const _, data.len() as u32) };
+    if ret == 0 { Ok(()) } else { Err(ret) }
+}
+
+/// Writes a formatted message to the `seq_file` of an iterator program.
+///
+/// The format string syntax is the same as that of [`bpf_printk!`], with the
+/// addition of specifiers such as `%pI4` and `%pK` for printing pointers. It
+/// is passed in as a fixed-size byte array (`&[u8; N]`), so you will have to
+/// prefix your string literal with a `b`. A terminating zero byte is appended
+/// automatically.
+///
+/// The return value of the BPF helper is returned from this macro: 0 on
+/// success, `-EOVERFLOW` if the buffer of the `seq_file` overflowed.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![expect(dead_code)]
+/// # use aya_ebpf::{helpers::bpf_seq_printf, programs::{IterContext, iter::bpf_iter__task}};
+/// fn dump_task(ctx: IterContext<bpf_iter__task>) -> i32 {
+///     unsafe {
+///         bpf_seq_printf!(ctx.seq(), b"task %llu\n", ctx.seq_num());
+///     }
+///     0
+/// }
+/// ```
+#[macro_export]
+macro_rules! bpf_seq_printf {
+    ($seq:expr, $fmt:literal $(,)? $($arg:expr),* $(,)?) => {{
+        use $crate::helpers::PrintkArg;
+        const FMT: [u8; { $fmt.len() + 1 }] = $crate::helpers::zero_pad_array::<
+            { $fmt.len() }, { $fmt.len() + 1 }>(*$fmt);
+        let data = [$(PrintkArg::from($arg)),*];
+        $crate::helpers::bpf_seq_printf_impl($seq, &FMT, &data)
+    }};
+}
+
+// Macros are always exported from the crate root. Also export it from `helpers`.
+#[doc(inline)]
+pub use crate::bpf_seq_printf;
+
+/// Internal helper function for the [`bpf_seq_printf!`] macro.
+#[inline]
+#[doc(hidden)]
+pub unsafe fn bpf_seq_printf_impl<const FMT_LEN: usize, const NUM_ARGS: usize>(
+    seq: *mut seq_file,
+    fmt: &[u8; FMT_LEN],
+    args: &[PrintkArg; NUM_ARGS],
+) -> i64 {
+    let fmt_ptr = fmt.as_ptr() as *const c_char;
+    let fmt_size = fmt.len() as u32;
+    // The verifier only accepts a null pointer for empty arguments.
+    let args_ptr = if NUM_ARGS == 0 {
+        core::ptr::null()
+    } else {
+        args.as_ptr() as *const c_void
+    };
+    unsafe { generated::bpf_seq_printf(seq, fmt_ptr, fmt_size, args_ptr, (NUM_ARGS * 8) as u32) }
+}
+
 /// Compares the given byte `s1` with a [`&CStr`](core::ffi::CStr) `s2`.
 ///
 /// # Examples
@@ -0,0 +1,181 @@
+use core::{cell::UnsafeCell, ptr};
+
+use aya_ebpf_cty::{c_int, c_void};
+
+use crate::{
+    bindings::{BPF_F_MMAPABLE, bpf_map_def, bpf_map_type::BPF_MAP_TYPE_ARENA},
+    maps::PinningType,
+};
+
+unsafe extern "C" {
+    fn bpf_arena_alloc_pages(
+        map: *mut c_void,
+        addr: *mut c_void,
+        page_cnt: u32,
+        node_id: c_int,
+        flags: u64,
+    ) -> *mut c_void;
+
+    fn bpf_arena_free_pages(map: *mut c_void, ptr: *mut c_void, page_cnt: u32);
+}
+
+/// A sparse region of memory shared with userspace.
+///
+/// Pages are allocated with [`Arena::alloc_pages`] and are accessed through [`ArenaPtr`].
+#[repr(C)]
+pub struct Arena {
+    def: UnsafeCell<bpf_map_def>,
+    // Read by the loader, which sets it as the `map_extra` of the map.
+    map_extra: u64,
+}
+
+unsafe impl Sync for Arena {}
+
+impl Arena {
+    /// Declare an arena of up to `max_pages` pages, mapped in userspace at an address picked by
+    /// the kernel.
+    pub const fn with_max_pages(max_pages: u32, flags: u32) -> Self {
+        Self::new(max_pages, 0, flags, PinningType::None)
+    }
+
+    /// Declare an arena of up to `max_pages` pages, mapped in userspace at `address`.
+    ///
+    /// The address must be page aligned and the arena must not cross a 4GiB boundary.
+    pub const fn with_address(max_pages: u32, address: u64, flags: u32) -> Self {
+        Self::new(max_pages, address, flags, PinningType::None)
+    }
+
+    /// Declare a pinned arena of up to `max_pages` pages, mapped in userspace at an address
+    /// picked by the kernel.
+    pub const fn pinned(max_pages: u32, flags: u32) -> Self {
+        Self::new(max_pages, 0, flags, PinningType::ByName)
+    }
+
+    const fn new(max_pages: u32, address: u64, flags: u32, pinning_type: PinningType) -> Self {
+        Self {
+            def: UnsafeCell::new(bpf_map_def {
+                type_: BPF_MAP_TYPE_ARENA,
+                key_size: 0,
+                value_size: 0,
+                max_entries: max_pages,
+                // Arenas can't be created without BPF_F_MMAPABLE.
+                map_flags: flags | BPF_F_MMAPABLE,
+                id: 0,
+                pinning: pinning_type as u32,
+            }),
+            map_extra: address,
+        }
+    }
+
+    /// Allocates `page_cnt` contiguous pages in the arena, on the NUMA node `node_id`, or on any
+    /// node if it's `-1`.
+    ///
+    /// Returns `None` if the pages couldn't be allocated. This can only be called from sleepable
+    /// programs.
+    pub fn alloc_pages<T>(&self, page_cnt: u32, node_id: i32) -> Option<ArenaPtr<T>> {
+        let ptr = unsafe {
+            bpf_arena_alloc_pages(
+                self.def.get() as *mut _,
+                ptr::null_mut(),
+                page_cnt,
+                node_id,
+                0,
+            )
+        };
+        (!ptr.is_null()).then_some(ArenaPtr(ptr as *mut T))
+    }
+
+    /// Frees `page_cnt` pages starting at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// The pages must not be accessed after they are freed, neither by eBPF programs nor by
+    /// userspace.
+    pub unsafe fn free_pages<T>(&self, ptr: ArenaPtr<T>, page_cnt: u32) {
+        unsafe { bpf_arena_free_pages(self.def.get() as *mut _, ptr.0 as *mut _, page_cnt) }
+    }
+}
+
+/// A pointer to memory in an [`Arena`].
+///
+/// Arena pointers hold the address at which the memory is mapped in userspace, so they can be
+/// stored in the arena and followed by userspace. They are cast to the address space in which the
+/// program accesses the arena by [`ArenaPtr::as_ptr`].
+#[repr(transparent)]
+pub struct ArenaPtr<T>(*mut T);
+
+impl<T> Clone for ArenaPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaPtr<T> {}
+
+impl<T> ArenaPtr<T> {
+    /// Creates a null arena pointer.
+    pub const fn null() -> Self {
+        Self(ptr::null_mut())
+    }
+
+    /// Returns `true` if the pointer is null.
+    pub fn is_null(self) -> bool {
+        self.0.is_null()
+    }
+
+    /// Creates an arena pointer from a pointer returned by [`ArenaPtr::as_ptr`].
+    #[inline(always)]
+    pub fn from_ptr(ptr: *mut T) -> Self {
+        Self(cast_user(ptr))
+    }
+
+    /// Returns a pointer through which the program can access the memory.
+    #[inline(always)]
+    pub fn as_ptr(self) -> *mut T {
+        cast_kern(self.0)
+    }
+
+    /// Returns the address at which the memory is mapped in userspace.
+    pub fn addr(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Casts to a pointer of another type.
+    pub const fn cast<U>(self) -> ArenaPtr<U> {
+        ArenaPtr(self.0.cast())
+    }
+}
+
+// Casts a pointer from the userspace address space of the arena to the one used by the program.
+#[inline(always)]
+fn cast_kern<T>(ptr: *mut T) -> *mut T {
+    #[cfg(target_arch = "bpf")]
+    unsafe {
+        let mut ptr = ptr;
+        core::arch::asm!("{ptr} = addr_space_cast({ptr}, 0, 1)", ptr = inout(reg) ptr);
+        ptr
+    }
+    // We only need this for doc tests which are compiled for the host target
+    #[cfg(not(target_arch = "bpf"))]
+    {
+        let _ = ptr;
+        unimplemented!()
+    }
+}
+
+// Casts a pointer from the address space used by the program to the userspace one of the arena.
+#[inline(always)]
+fn cast_user<T>(ptr: *mut T) -> *mut T {
+    #[cfg(target_arch = "bpf")]
+    unsafe {
+        let mut ptr = ptr;
+        core::arch::asm!("{ptr} = addr_space_cast({ptr}, 1, 0)", ptr = inout(reg) ptr);
+        ptr
+    }
+    // We only need this for doc tests which are compiled for the host target
+    #[cfg(not(target_arch = "bpf"))]
+    {
+        let _ = ptr;
+        unimplemented!()
+    }
+}
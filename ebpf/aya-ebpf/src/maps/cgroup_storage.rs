@@ -0,0 +1,107 @@
+use core::{cell::UnsafeCell, marker::PhantomData, mem};
+
+use crate::{
+    bindings::{
+        bpf_cgroup_storage_key, bpf_map_def,
+        bpf_map_type::{BPF_MAP_TYPE_CGROUP_STORAGE, BPF_MAP_TYPE_PERCPU_CGROUP_STORAGE},
+    },
+    helpers::bpf_get_local_storage,
+    maps::PinningType,
+};
+
+/// Storage attached to the cgroup a program is running for.
+///
+/// Each cgroup and attach type the program is attached to gets its own value of type `T`, which
+/// is allocated when the program is attached and freed when it is detached.
+#[repr(transparent)]
+pub struct CgroupStorage<T> {
+    def: UnsafeCell<bpf_map_def>,
+    _t: PhantomData<T>,
+}
+
+unsafe impl<T: Sync> Sync for CgroupStorage<T> {}
+
+impl<T> CgroupStorage<T> {
+    pub const fn new(flags: u32) -> CgroupStorage<T> {
+        CgroupStorage {
+            def: UnsafeCell::new(build_def::<T>(
+                BPF_MAP_TYPE_CGROUP_STORAGE,
+                flags,
+                PinningType::None,
+            )),
+            _t: PhantomData,
+        }
+    }
+
+    pub const fn pinned(flags: u32) -> CgroupStorage<T> {
+        CgroupStorage {
+            def: UnsafeCell::new(build_def::<T>(
+                BPF_MAP_TYPE_CGROUP_STORAGE,
+                flags,
+                PinningType::ByName,
+            )),
+            _t: PhantomData,
+        }
+    }
+
+    /// Returns a pointer to the storage of the cgroup the program is running for.
+    ///
+    /// The storage is shared by all the CPUs, so concurrent accesses must be synchronized, for
+    /// example with atomic operations.
+    #[inline(always)]
+    pub fn get_ptr_mut(&self) -> *mut T {
+        unsafe { bpf_get_local_storage(self.def.get().cast(), 0) }.cast()
+    }
+}
+
+/// Similar to [`CgroupStorage`] but each CPU holds a separate value of type `T`.
+#[repr(transparent)]
+pub struct PerCpuCgroupStorage<T> {
+    def: UnsafeCell<bpf_map_def>,
+    _t: PhantomData<T>,
+}
+
+unsafe impl<T: Sync> Sync for PerCpuCgroupStorage<T> {}
+
+impl<T> PerCpuCgroupStorage<T> {
+    pub const fn new(flags: u32) -> PerCpuCgroupStorage<T> {
+        PerCpuCgroupStorage {
+            def: UnsafeCell::new(build_def::<T>(
+                BPF_MAP_TYPE_PERCPU_CGROUP_STORAGE,
+                flags,
+                PinningType::None,
+            )),
+            _t: PhantomData,
+        }
+    }
+
+    pub const fn pinned(flags: u32) -> PerCpuCgroupStorage<T> {
+        PerCpuCgroupStorage {
+            def: UnsafeCell::new(build_def::<T>(
+                BPF_MAP_TYPE_PERCPU_CGROUP_STORAGE,
+                flags,
+                PinningType::ByName,
+            )),
+            _t: PhantomData,
+        }
+    }
+
+    /// Returns a pointer to the current CPU's storage of the cgroup the program is running for.
+    #[inline(always)]
+    pub fn get_ptr_mut(&self) -> *mut T {
+        unsafe { bpf_get_local_storage(self.def.get().cast(), 0) }.cast()
+    }
+}
+
+const fn build_def<T>(ty: u32, flags: u32, pin: PinningType) -> bpf_map_def {
+    bpf_map_def {
+        type_: ty,
+        key_size: mem::size_of::<bpf_cgroup_storage_key>() as u32,
+        value_size: mem::size_of::<T>() as u32,
+        // Cgroup storage maps are sized by the kernel as programs are attached.
+        max_entries: 0,
+        map_flags: flags,
+        id: 0,
+        pinning: pin as u32,
+    }
+}
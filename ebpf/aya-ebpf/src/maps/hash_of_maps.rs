@@ -0,0 +1,74 @@
+use core::{cell::UnsafeCell, marker::PhantomData, mem};
+
+use crate::{
+    bindings::{bpf_map_def, bpf_map_type::BPF_MAP_TYPE_HASH_OF_MAPS},
+    lookup,
+    maps::{InnerMap, PinningType},
+};
+
+/// A hash map of maps.
+///
+/// The inner maps are created and stored from userspace. The map passed to
+/// the constructor is the inner map template: it describes the type of the
+/// maps that can be stored in the hash map, and is never created itself.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aya_ebpf::{macros::map, maps::{HashMap, HashOfMaps}};
+///
+/// #[map]
+/// static TENANTS: HashOfMaps<u32, HashMap<u32, u64>> =
+///     HashOfMaps::with_max_entries(16, 0, HashMap::with_max_entries(1024, 0));
+///
+/// # fn count(tenant: u32, key: u32) -> Option<()> {
+/// let counter = TENANTS.get(&tenant)?.get_ptr_mut(&key)?;
+/// unsafe { *counter += 1 };
+/// # Some(())
+/// # }
+/// ```
+#[repr(C)]
+pub struct HashOfMaps<K, T> {
+    def: UnsafeCell<bpf_map_def>,
+    // The loader reads the inner map template right after the outer definition.
+    inner: T,
+    _k: PhantomData<K>,
+}
+
+unsafe impl<K: Sync, T: InnerMap + Sync> Sync for HashOfMaps<K, T> {}
+
+impl<K, T: InnerMap> HashOfMaps<K, T> {
+    pub const fn with_max_entries(max_entries: u32, flags: u32, inner: T) -> HashOfMaps<K, T> {
+        HashOfMaps {
+            def: UnsafeCell::new(build_def::<K>(max_entries, flags, PinningType::None)),
+            inner,
+            _k: PhantomData,
+        }
+    }
+
+    pub const fn pinned(max_entries: u32, flags: u32, inner: T) -> HashOfMaps<K, T> {
+        HashOfMaps {
+            def: UnsafeCell::new(build_def::<K>(max_entries, flags, PinningType::ByName)),
+            inner,
+            _k: PhantomData,
+        }
+    }
+
+    /// Returns the inner map stored under the given key.
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&T> {
+        unsafe { lookup(self.def.get(), key).map(|p| p.as_ref()) }
+    }
+}
+
+const fn build_def<K>(max_entries: u32, flags: u32, pin: PinningType) -> bpf_map_def {
+    bpf_map_def {
+        type_: BPF_MAP_TYPE_HASH_OF_MAPS,
+        key_size: mem::size_of::<K>() as u32,
+        value_size: mem::size_of::<u32>() as u32,
+        max_entries,
+        map_flags: flags,
+        id: 0,
+        pinning: pin as u32,
+    }
+}
@@ -0,0 +1,185 @@
+use core::{cell::UnsafeCell, marker::PhantomData, mem, ptr};
+
+use aya_ebpf_cty::{c_long, c_void};
+
+use crate::{
+    bindings::{
+        BPF_F_NO_PREALLOC, BPF_LOCAL_STORAGE_GET_F_CREATE, bpf_map_def,
+        bpf_map_type::{
+            BPF_MAP_TYPE_CGRP_STORAGE, BPF_MAP_TYPE_INODE_STORAGE, BPF_MAP_TYPE_SK_STORAGE,
+            BPF_MAP_TYPE_TASK_STORAGE,
+        },
+        cgroup, task_struct,
+    },
+    helpers::{
+        bpf_cgrp_storage_delete, bpf_cgrp_storage_get, bpf_inode_storage_delete,
+        bpf_inode_storage_get, bpf_sk_storage_delete, bpf_sk_storage_get, bpf_task_storage_delete,
+        bpf_task_storage_get,
+    },
+    maps::PinningType,
+};
+
+macro_rules! local_storage {
+    (
+        $(#[$attr:meta])*
+        $name:ident,
+        $map_type:ident,
+        $get:ident,
+        $delete:ident,
+        $owner:ident: $owner_ty:ty,
+        $owner_doc:literal $(,)?
+    ) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        pub struct $name<T> {
+            def: UnsafeCell<bpf_map_def>,
+            _t: PhantomData<T>,
+        }
+
+        unsafe impl<T: Sync> Sync for $name<T> {}
+
+        impl<T> $name<T> {
+            pub const fn new(flags: u32) -> $name<T> {
+                $name {
+                    def: UnsafeCell::new(build_def::<T>($map_type, flags, PinningType::None)),
+                    _t: PhantomData,
+                }
+            }
+
+            pub const fn pinned(flags: u32) -> $name<T> {
+                $name {
+                    def: UnsafeCell::new(build_def::<T>($map_type, flags, PinningType::ByName)),
+                    _t: PhantomData,
+                }
+            }
+
+            #[doc = concat!(
+                "Returns a pointer to the storage of the given ",
+                stringify!($owner),
+                ", if any."
+            )]
+            ///
+            /// # Safety
+            ///
+            #[doc = $owner_doc]
+            #[inline(always)]
+            pub unsafe fn get(&self, $owner: $owner_ty) -> Option<*mut T> {
+                unsafe { self.get_with_flags($owner, ptr::null_mut(), 0) }
+            }
+
+            #[doc = concat!(
+                "Returns a pointer to the storage of the given ",
+                stringify!($owner),
+                ", creating it if it doesn't exist."
+            )]
+            ///
+            /// New storage is initialized with `value`, or zeroed if `value` is `None`.
+            ///
+            /// # Safety
+            ///
+            #[doc = $owner_doc]
+            #[inline(always)]
+            pub unsafe fn get_or_create(
+                &self,
+                $owner: $owner_ty,
+                value: Option<&T>,
+            ) -> Option<*mut T> {
+                let value = value.map_or(ptr::null_mut(), |value| {
+                    let value: *const T = value;
+                    value as *mut c_void
+                });
+                let flags = u64::from(BPF_LOCAL_STORAGE_GET_F_CREATE);
+                unsafe { self.get_with_flags($owner, value, flags) }
+            }
+
+            #[doc = concat!("Deletes the storage of the given ", stringify!($owner), ".")]
+            ///
+            /// # Safety
+            ///
+            #[doc = $owner_doc]
+            #[inline(always)]
+            pub unsafe fn delete(&self, $owner: $owner_ty) -> Result<(), c_long> {
+                match unsafe { $delete(self.def.get().cast(), $owner) } {
+                    0 => Ok(()),
+                    ret => Err(ret.into()),
+                }
+            }
+
+            #[inline(always)]
+            unsafe fn get_with_flags(
+                &self,
+                $owner: $owner_ty,
+                value: *mut c_void,
+                flags: u64,
+            ) -> Option<*mut T> {
+                let ptr = unsafe { $get(self.def.get().cast(), $owner, value, flags) };
+                (!ptr.is_null()).then_some(ptr.cast())
+            }
+        }
+    };
+}
+
+local_storage!(
+    /// Per-socket storage.
+    ///
+    /// Each socket gets its own value of type `T`, which is freed together with
+    /// the socket.
+    SkStorage,
+    BPF_MAP_TYPE_SK_STORAGE,
+    bpf_sk_storage_get,
+    bpf_sk_storage_delete,
+    sk: *mut c_void,
+    "`sk` must point to a socket, such as a `bpf_sock` or a `sock`.",
+);
+
+local_storage!(
+    /// Per-inode storage.
+    ///
+    /// Each inode gets its own value of type `T`, which is freed together with
+    /// the inode.
+    InodeStorage,
+    BPF_MAP_TYPE_INODE_STORAGE,
+    bpf_inode_storage_get,
+    bpf_inode_storage_delete,
+    inode: *mut c_void,
+    "`inode` must point to an `inode`.",
+);
+
+local_storage!(
+    /// Per-task storage.
+    ///
+    /// Each task gets its own value of type `T`, which is freed together with
+    /// the task.
+    TaskStorage,
+    BPF_MAP_TYPE_TASK_STORAGE,
+    bpf_task_storage_get,
+    bpf_task_storage_delete,
+    task: *mut task_struct,
+    "`task` must point to a `task_struct`.",
+);
+
+local_storage!(
+    /// Per-cgroup storage.
+    ///
+    /// Each cgroup gets its own value of type `T`, which is freed together with
+    /// the cgroup.
+    CgrpStorage,
+    BPF_MAP_TYPE_CGRP_STORAGE,
+    bpf_cgrp_storage_get,
+    bpf_cgrp_storage_delete,
+    cgroup: *mut cgroup,
+    "`cgroup` must point to a `cgroup`.",
+);
+
+const fn build_def<T>(map_type: u32, flags: u32, pin: PinningType) -> bpf_map_def {
+    bpf_map_def {
+        type_: map_type,
+        key_size: mem::size_of::<i32>() as u32,
+        value_size: mem::size_of::<T>() as u32,
+        // Local storage maps grow with the objects they are attached to.
+        max_entries: 0,
+        map_flags: flags | BPF_F_NO_PREALLOC,
+        id: 0,
+        pinning: pin as u32,
+    }
+}
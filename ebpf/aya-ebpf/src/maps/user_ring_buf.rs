@@ -0,0 +1,122 @@
+use core::{cell::UnsafeCell, mem, mem::MaybeUninit};
+
+use aya_ebpf_cty::{c_long, c_void};
+
+use crate::{
+    bindings::{bpf_dynptr, bpf_map_def, bpf_map_type::BPF_MAP_TYPE_USER_RINGBUF},
+    helpers::{bpf_dynptr_read, bpf_user_ringbuf_drain},
+    maps::PinningType,
+};
+
+/// A ring buffer used to receive samples from userspace.
+///
+/// Samples are produced by userspace and consumed by calling [`UserRingBuf::drain`].
+#[repr(transparent)]
+pub struct UserRingBuf {
+    def: UnsafeCell<bpf_map_def>,
+}
+
+unsafe impl Sync for UserRingBuf {}
+
+/// A sample drained from a [`UserRingBuf`].
+#[repr(transparent)]
+pub struct UserRingBufEntry(bpf_dynptr);
+
+impl UserRingBufEntry {
+    /// Reads a `T` from the sample at `offset`.
+    ///
+    /// Returns an error if the sample is too short to contain a `T` at `offset`.
+    pub fn read<T>(&self, offset: u32) -> Result<T, c_long> {
+        let mut value = MaybeUninit::<T>::uninit();
+        let ret = unsafe {
+            bpf_dynptr_read(
+                value.as_mut_ptr() as *mut _,
+                mem::size_of::<T>() as u32,
+                &self.0,
+                offset,
+                0,
+            )
+        };
+        if ret == 0 {
+            Ok(unsafe { value.assume_init() })
+        } else {
+            Err(ret)
+        }
+    }
+
+    /// Reads `buf.len()` bytes from the sample at `offset` into `buf`.
+    ///
+    /// Returns an error if the sample is too short to fill `buf`.
+    pub fn read_bytes(&self, offset: u32, buf: &mut [u8]) -> Result<(), c_long> {
+        let ret = unsafe {
+            bpf_dynptr_read(
+                buf.as_mut_ptr() as *mut _,
+                buf.len() as u32,
+                &self.0,
+                offset,
+                0,
+            )
+        };
+        if ret == 0 { Ok(()) } else { Err(ret) }
+    }
+}
+
+impl UserRingBuf {
+    /// Declare an eBPF user ring buffer.
+    ///
+    /// The linux kernel requires that `byte_size` be a power-of-2 multiple of the page size. The
+    /// loading program may coerce the size when loading the map.
+    pub const fn with_byte_size(byte_size: u32, flags: u32) -> Self {
+        Self::new(byte_size, flags, PinningType::None)
+    }
+
+    /// Declare a pinned eBPF user ring buffer.
+    ///
+    /// The linux kernel requires that `byte_size` be a power-of-2 multiple of the page size. The
+    /// loading program may coerce the size when loading the map.
+    pub const fn pinned(byte_size: u32, flags: u32) -> Self {
+        Self::new(byte_size, flags, PinningType::ByName)
+    }
+
+    const fn new(byte_size: u32, flags: u32, pinning_type: PinningType) -> Self {
+        Self {
+            def: UnsafeCell::new(bpf_map_def {
+                type_: BPF_MAP_TYPE_USER_RINGBUF,
+                key_size: 0,
+                value_size: 0,
+                max_entries: byte_size,
+                map_flags: flags,
+                id: 0,
+                pinning: pinning_type as u32,
+            }),
+        }
+    }
+
+    /// Drains samples submitted by userspace, calling `callback` for each of them.
+    ///
+    /// The callback returns `0` to keep draining or `1` to stop. Returns the number of samples
+    /// drained on success.
+    pub fn drain<F>(&self, mut callback: F, flags: u64) -> Result<u32, c_long>
+    where
+        F: FnMut(&UserRingBufEntry) -> c_long,
+    {
+        unsafe extern "C" fn trampoline<F>(dynptr: *mut bpf_dynptr, ctx: *mut c_void) -> c_long
+        where
+            F: FnMut(&UserRingBufEntry) -> c_long,
+        {
+            let callback = unsafe { &mut *(ctx as *mut F) };
+            let entry = unsafe { &*(dynptr as *const UserRingBufEntry) };
+            callback(entry)
+        }
+
+        let ret = unsafe {
+            bpf_user_ringbuf_drain(
+                self.def.get() as *mut _,
+                trampoline::<F> as *mut _,
+                &mut callback as *mut F as *mut _,
+                flags,
+            )
+        };
+        u32::try_from(ret).map_err(|_| ret)
+    }
+}
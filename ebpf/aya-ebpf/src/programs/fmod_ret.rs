@@ -0,0 +1,47 @@
+use core::ffi::c_void;
+
+use crate::{EbpfContext, args::FromBtfArgument};
+
+pub struct FModRetContext {
+    ctx: *mut c_void,
+}
+
+impl FModRetContext {
+    pub fn new(ctx: *mut c_void) -> FModRetContext {
+        FModRetContext { ctx }
+    }
+
+    /// Returns the `n`th argument to passed to the probe function, starting from 0.
+    ///
+    /// fmod_ret programs have access to an additional argument which holds the
+    /// return value of the previous fmod_ret program attached to the function,
+    /// or 0 if this is the first one. This argument is always last in the
+    /// argument list.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #![expect(dead_code)]
+    /// # use aya_ebpf::{cty::{c_int, c_void}, programs::FModRetContext};
+    /// unsafe fn try_security_file_open(ctx: FModRetContext) -> Result<i32, i32> {
+    ///     // In the kernel, this function is defined as:
+    ///     //   int security_file_open(struct file *file);
+    ///     let file: *const c_void = ctx.arg(0);
+    ///     let retval: c_int = ctx.arg(1);
+    ///
+    ///     // Do something with file
+    ///
+    ///     Ok(retval)
+    /// }
+    /// ```
+    #[expect(clippy::missing_safety_doc)]
+    pub unsafe fn arg<T: FromBtfArgument>(&self, n: usize) -> T {
+        unsafe { T::from_argument(self.ctx.cast(), n) }
+    }
+}
+
+impl EbpfContext for FModRetContext {
+    fn as_ptr(&self) -> *mut c_void {
+        self.ctx
+    }
+}
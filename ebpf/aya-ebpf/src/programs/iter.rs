@@ -0,0 +1,267 @@
+use core::ffi::c_void;
+
+use crate::{
+    EbpfContext,
+    bindings::{cgroup, file, seq_file, task_struct},
+    cty::c_long,
+    helpers::bpf_seq_write,
+};
+
+/// Models `struct bpf_map` from `include/linux/bpf.h`, which isn't part of the
+/// UAPI headers the bindings are generated from.
+#[repr(C)]
+pub struct bpf_map {
+    _unused: [u8; 0],
+}
+
+/// Models `struct sock_common` from `include/net/sock.h`.
+#[repr(C)]
+pub struct sock_common {
+    _unused: [u8; 0],
+}
+
+/// Models `struct udp_sock` from `include/linux/udp.h`.
+#[repr(C)]
+pub struct udp_sock {
+    _unused: [u8; 0],
+}
+
+/// Models `struct bpf_iter_meta` from `include/linux/bpf.h`, the metadata
+/// passed to every iterator program.
+#[repr(C)]
+pub struct bpf_iter_meta {
+    pub seq: *mut seq_file,
+    pub session_id: u64,
+    pub seq_num: u64,
+}
+
+/// The context of `task` iterators.
+#[repr(C)]
+pub struct bpf_iter__task {
+    pub meta: *mut bpf_iter_meta,
+    pub task: *mut task_struct,
+}
+
+/// The context of `task_file` iterators.
+#[repr(C)]
+pub struct bpf_iter__task_file {
+    pub meta: *mut bpf_iter_meta,
+    pub task: *mut task_struct,
+    pub fd: u32,
+    pub file: *mut file,
+}
+
+/// The context of `bpf_map` iterators.
+#[repr(C)]
+pub struct bpf_iter__bpf_map {
+    pub meta: *mut bpf_iter_meta,
+    pub map: *mut bpf_map,
+}
+
+/// The context of `bpf_map_elem` iterators.
+#[repr(C)]
+pub struct bpf_iter__bpf_map_elem {
+    pub meta: *mut bpf_iter_meta,
+    pub map: *mut bpf_map,
+    pub key: *mut c_void,
+    pub value: *mut c_void,
+}
+
+/// The context of `tcp` iterators.
+#[repr(C)]
+pub struct bpf_iter__tcp {
+    pub meta: *mut bpf_iter_meta,
+    pub sk_common: *mut sock_common,
+    pub uid: u32,
+}
+
+/// The context of `udp` iterators.
+#[repr(C)]
+pub struct bpf_iter__udp {
+    pub meta: *mut bpf_iter_meta,
+    pub udp_sk: *mut udp_sock,
+    pub uid: u32,
+    // `bucket` is declared `__aligned(8)`.
+    _pad: u32,
+    pub bucket: i32,
+}
+
+/// The context of `cgroup` iterators.
+#[repr(C)]
+pub struct bpf_iter__cgroup {
+    pub meta: *mut bpf_iter_meta,
+    pub cgroup: *mut cgroup,
+}
+
+/// The context of iterator programs.
+///
+/// `T` is the context struct of the iterator target, such as
+/// [`bpf_iter__task`] for `task` iterators. It is inferred from the signature
+/// of the function marked with the `#[iter]` macro.
+///
+/// The pointers returned by the target specific accessors are null on the
+/// last call of the program, which is made once all the objects have been
+/// visited.
+pub struct IterContext<T> {
+    pub ctx: *mut T,
+}
+
+impl<T> IterContext<T> {
+    pub fn new(ctx: *mut c_void) -> IterContext<T> {
+        IterContext { ctx: ctx.cast() }
+    }
+
+    /// Returns the metadata of the iteration.
+    #[inline]
+    pub fn meta(&self) -> *mut bpf_iter_meta {
+        // All the iterator contexts start with the metadata pointer.
+        unsafe { *self.ctx.cast::<*mut bpf_iter_meta>() }
+    }
+
+    /// Returns the `seq_file` the output of the iterator is written to.
+    #[inline]
+    pub fn seq(&self) -> *mut seq_file {
+        unsafe { (*self.meta()).seq }
+    }
+
+    /// Returns the identifier of the current read session.
+    #[inline]
+    pub fn session_id(&self) -> u64 {
+        unsafe { (*self.meta()).session_id }
+    }
+
+    /// Returns the sequence number of the object being visited, starting from
+    /// 0.
+    #[inline]
+    pub fn seq_num(&self) -> u64 {
+        unsafe { (*self.meta()).seq_num }
+    }
+
+    /// Writes `data` to the output of the iterator.
+    ///
+    /// Formatted output can be written with
+    /// [`bpf_seq_printf!`](crate::helpers::bpf_seq_printf).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #![expect(dead_code)]
+    /// # use aya_ebpf::programs::{IterContext, iter::bpf_iter__task};
+    /// fn try_dump_task(ctx: IterContext<bpf_iter__task>) -> Result<i32, i32> {
+    ///     if ctx.seq_num() == 0 {
+    ///         ctx.seq_write(b"tasks:\n").map_err(|_| 1)?;
+    ///     }
+    ///     Ok(0)
+    /// }
+    /// ```
+    #[inline]
+    pub fn seq_write(&self, data: &[u8]) -> Result<(), c_long> {
+        unsafe { bpf_seq_write(self.seq(), data) }
+    }
+}
+
+impl IterContext<bpf_iter__task> {
+    /// Returns the task being visited.
+    #[inline]
+    pub fn task(&self) -> *mut task_struct {
+        unsafe { (*self.ctx).task }
+    }
+}
+
+impl IterContext<bpf_iter__task_file> {
+    /// Returns the task owning the file being visited.
+    #[inline]
+    pub fn task(&self) -> *mut task_struct {
+        unsafe { (*self.ctx).task }
+    }
+
+    /// Returns the file descriptor of the file in the task.
+    #[inline]
+    pub fn fd(&self) -> u32 {
+        unsafe { (*self.ctx).fd }
+    }
+
+    /// Returns the file being visited.
+    #[inline]
+    pub fn file(&self) -> *mut file {
+        unsafe { (*self.ctx).file }
+    }
+}
+
+impl IterContext<bpf_iter__bpf_map> {
+    /// Returns the map being visited.
+    #[inline]
+    pub fn map(&self) -> *mut bpf_map {
+        unsafe { (*self.ctx).map }
+    }
+}
+
+impl IterContext<bpf_iter__bpf_map_elem> {
+    /// Returns the map whose elements are visited.
+    #[inline]
+    pub fn map(&self) -> *mut bpf_map {
+        unsafe { (*self.ctx).map }
+    }
+
+    /// Returns the key of the element being visited.
+    #[inline]
+    pub fn key<K>(&self) -> *const K {
+        unsafe { (*self.ctx).key.cast() }
+    }
+
+    /// Returns the value of the element being visited.
+    ///
+    /// The value can be updated in place.
+    #[inline]
+    pub fn value<V>(&self) -> *mut V {
+        unsafe { (*self.ctx).value.cast() }
+    }
+}
+
+impl IterContext<bpf_iter__tcp> {
+    /// Returns the socket being visited.
+    #[inline]
+    pub fn sk_common(&self) -> *mut sock_common {
+        unsafe { (*self.ctx).sk_common }
+    }
+
+    /// Returns the owner of the socket.
+    #[inline]
+    pub fn uid(&self) -> u32 {
+        unsafe { (*self.ctx).uid }
+    }
+}
+
+impl IterContext<bpf_iter__udp> {
+    /// Returns the socket being visited.
+    #[inline]
+    pub fn udp_sk(&self) -> *mut udp_sock {
+        unsafe { (*self.ctx).udp_sk }
+    }
+
+    /// Returns the owner of the socket.
+    #[inline]
+    pub fn uid(&self) -> u32 {
+        unsafe { (*self.ctx).uid }
+    }
+
+    /// Returns the bucket of the socket in the UDP hash table.
+    #[inline]
+    pub fn bucket(&self) -> i32 {
+        unsafe { (*self.ctx).bucket }
+    }
+}
+
+impl IterContext<bpf_iter__cgroup> {
+    /// Returns the cgroup being visited.
+    #[inline]
+    pub fn cgroup(&self) -> *mut cgroup {
+        unsafe { (*self.ctx).cgroup }
+    }
+}
+
+impl<T> EbpfContext for IterContext<T> {
+    fn as_ptr(&self) -> *mut c_void {
+        self.ctx.cast()
+    }
+}
@@ -0,0 +1,93 @@
+use core::ffi::c_void;
+
+#[cfg(bpf_target_arch = "x86_64")]
+use crate::bindings::pt_regs;
+// aarch64 uses user_pt_regs instead of pt_regs
+#[cfg(bpf_target_arch = "aarch64")]
+use crate::bindings::user_pt_regs as pt_regs;
+use crate::{
+    EbpfContext,
+    args::{FromPtRegs, FromSyscallArg},
+    helpers::bpf_probe_read_kernel,
+};
+
+/// The context of a program attached to a syscall with `ksyscall`.
+///
+/// Syscalls are implemented by wrappers such as `__x64_sys_openat` on x86_64
+/// or `__arm64_sys_openat` on aarch64, which take a pointer to the registers
+/// saved on syscall entry as their only argument. [`KSyscallContext::arg`]
+/// follows that pointer and reads the arguments using the syscall calling
+/// convention of the target architecture.
+pub struct KSyscallContext {
+    pub regs: *mut pt_regs,
+}
+
+impl KSyscallContext {
+    pub fn new(ctx: *mut c_void) -> KSyscallContext {
+        KSyscallContext {
+            regs: ctx as *mut pt_regs,
+        }
+    }
+
+    /// Returns a pointer to the user space registers saved on syscall entry.
+    pub fn syscall_regs(&self) -> Option<*const pt_regs> {
+        FromPtRegs::from_argument(unsafe { &*self.regs }, 0)
+    }
+
+    /// Returns the `n`th argument passed to the syscall, starting from 0.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #![expect(dead_code)]
+    /// # use aya_ebpf::{programs::KSyscallContext, cty::{c_char, c_int}};
+    /// unsafe fn try_ksyscall_openat(ctx: KSyscallContext) -> Result<u32, u32> {
+    ///     let dfd: c_int = ctx.arg(0).ok_or(1u32)?;
+    ///     let filename: *const c_char = ctx.arg(1).ok_or(1u32)?;
+    ///
+    ///     // Do something with dfd and filename
+    ///
+    ///     Ok(0)
+    /// }
+    /// ```
+    pub fn arg<T: FromSyscallArg>(&self, n: usize) -> Option<T> {
+        let reg = syscall_arg_reg(self.syscall_regs()?, n)?;
+        let value = unsafe { bpf_probe_read_kernel(reg) }.ok()?;
+        Some(T::from_syscall_arg(value))
+    }
+}
+
+#[cfg(bpf_target_arch = "x86_64")]
+fn syscall_arg_reg(regs: *const pt_regs, n: usize) -> Option<*const u64> {
+    // The fourth argument is passed in r10 rather than rcx, which is clobbered
+    // by the syscall instruction.
+    let reg = unsafe {
+        match n {
+            0 => &raw const (*regs).rdi,
+            1 => &raw const (*regs).rsi,
+            2 => &raw const (*regs).rdx,
+            3 => &raw const (*regs).r10,
+            4 => &raw const (*regs).r8,
+            5 => &raw const (*regs).r9,
+            _ => return None,
+        }
+    };
+    Some(reg.cast())
+}
+
+#[cfg(bpf_target_arch = "aarch64")]
+fn syscall_arg_reg(regs: *const pt_regs, n: usize) -> Option<*const u64> {
+    match n {
+        // x0 is overwritten by the return value, the kernel's pt_regs keeps a
+        // copy of the first argument in orig_x0, right after user_pt_regs.
+        0 => Some(unsafe { regs.add(1) }.cast()),
+        1..=5 => Some(unsafe { &raw const (*regs).regs[n] }),
+        _ => None,
+    }
+}
+
+impl EbpfContext for KSyscallContext {
+    fn as_ptr(&self) -> *mut c_void {
+        self.regs as *mut c_void
+    }
+}
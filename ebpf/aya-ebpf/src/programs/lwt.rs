@@ -0,0 +1,172 @@
+use aya_ebpf_bindings::helpers::{
+    bpf_lwt_push_encap, bpf_lwt_seg6_action, bpf_lwt_seg6_adjust_srh, bpf_lwt_seg6_store_bytes,
+};
+use aya_ebpf_cty::{c_long, c_void};
+
+use crate::{EbpfContext, bindings::__sk_buff, programs::sk_buff::SkBuff};
+
+macro_rules! lwt_context {
+    ($name:ident) => {
+        pub struct $name {
+            pub skb: SkBuff,
+        }
+
+        impl $name {
+            pub fn new(skb: *mut __sk_buff) -> $name {
+                let skb = SkBuff { skb };
+                $name { skb }
+            }
+
+            #[expect(clippy::len_without_is_empty)]
+            #[inline]
+            pub fn len(&self) -> u32 {
+                self.skb.len()
+            }
+
+            #[inline]
+            pub fn data(&self) -> usize {
+                self.skb.data()
+            }
+
+            #[inline]
+            pub fn data_end(&self) -> usize {
+                self.skb.data_end()
+            }
+
+            #[inline]
+            pub fn cb(&self) -> &[u32] {
+                self.skb.cb()
+            }
+
+            #[inline]
+            pub fn cb_mut(&mut self) -> &mut [u32] {
+                self.skb.cb_mut()
+            }
+
+            #[inline]
+            pub fn protocol(&self) -> u32 {
+                self.skb.protocol()
+            }
+
+            #[inline]
+            pub fn load<T>(&self, offset: usize) -> Result<T, c_long> {
+                self.skb.load(offset)
+            }
+
+            /// Reads some bytes from the packet into the specified buffer, returning
+            /// how many bytes were read.
+            ///
+            /// Starts reading at `offset` and reads at most `dst.len()` or
+            /// `self.len() - offset` bytes, depending on which one is smaller.
+            #[inline(always)]
+            pub fn load_bytes(&self, offset: usize, dst: &mut [u8]) -> Result<usize, c_long> {
+                self.skb.load_bytes(offset, dst)
+            }
+
+            /// Pulls in non-linear data in case the skb is non-linear.
+            ///
+            /// Make len bytes from skb readable and writable. If a zero value is passed for
+            /// `len`, then the whole length of the skb is pulled. This helper is only needed
+            /// for reading and writing with direct packet access.
+            #[inline(always)]
+            pub fn pull_data(&self, len: u32) -> Result<(), c_long> {
+                self.skb.pull_data(len)
+            }
+        }
+
+        impl EbpfContext for $name {
+            fn as_ptr(&self) -> *mut c_void {
+                self.skb.as_ptr()
+            }
+        }
+    };
+}
+
+// LWT programs may only write to the `cb` field of the context, so unlike
+// `SkBuffContext` these contexts don't expose setters such as `set_mark`.
+lwt_context!(LwtInContext);
+lwt_context!(LwtOutContext);
+lwt_context!(LwtXmitContext);
+lwt_context!(LwtSeg6LocalContext);
+
+impl LwtXmitContext {
+    #[inline]
+    pub fn store<T>(&mut self, offset: usize, v: &T, flags: u64) -> Result<(), c_long> {
+        self.skb.store(offset, v, flags)
+    }
+
+    #[inline]
+    pub fn l3_csum_replace(
+        &self,
+        offset: usize,
+        from: u64,
+        to: u64,
+        size: u64,
+    ) -> Result<(), c_long> {
+        self.skb.l3_csum_replace(offset, from, to, size)
+    }
+
+    #[inline]
+    pub fn l4_csum_replace(
+        &self,
+        offset: usize,
+        from: u64,
+        to: u64,
+        flags: u64,
+    ) -> Result<(), c_long> {
+        self.skb.l4_csum_replace(offset, from, to, flags)
+    }
+
+    #[inline]
+    pub fn clone_redirect(&self, if_index: u32, flags: u64) -> Result<(), c_long> {
+        self.skb.clone_redirect(if_index, flags)
+    }
+
+    /// Encapsulates the packet in the header `hdr`.
+    ///
+    /// `mode` is either `BPF_LWT_ENCAP_IP`, in which case `hdr` must be an
+    /// IPv4 or IPv6 header followed by the headers of the tunnel, or
+    /// `BPF_LWT_ENCAP_SEG6` and `BPF_LWT_ENCAP_SEG6_INLINE`, in which case
+    /// `hdr` is an IPv6 segment routing header.
+    #[inline]
+    pub fn push_encap(&mut self, mode: u32, hdr: &[u8]) -> Result<(), c_long> {
+        let len = u32::try_from(hdr.len()).map_err(|core::num::TryFromIntError { .. }| -1)?;
+        let ret = unsafe { bpf_lwt_push_encap(self.skb.skb, mode, hdr.as_ptr() as *mut _, len) };
+        if ret == 0 { Ok(()) } else { Err(ret) }
+    }
+}
+
+impl LwtSeg6LocalContext {
+    /// Writes `src` to the segment routing header of the packet at `offset`.
+    ///
+    /// Only the flags, tag and TLVs of the segment routing header can be
+    /// modified.
+    #[inline]
+    pub fn seg6_store_bytes(&mut self, offset: usize, src: &[u8]) -> Result<(), c_long> {
+        let len = u32::try_from(src.len()).map_err(|core::num::TryFromIntError { .. }| -1)?;
+        let ret = unsafe {
+            bpf_lwt_seg6_store_bytes(self.skb.skb, offset as u32, src.as_ptr() as *const _, len)
+        };
+        if ret == 0 { Ok(()) } else { Err(ret) }
+    }
+
+    /// Grows or shrinks the TLVs of the segment routing header of the packet
+    /// by `delta` bytes at `offset`.
+    #[inline]
+    pub fn seg6_adjust_srh(&mut self, offset: usize, delta: i32) -> Result<(), c_long> {
+        let ret = unsafe { bpf_lwt_seg6_adjust_srh(self.skb.skb, offset as u32, delta) };
+        if ret == 0 { Ok(()) } else { Err(ret) }
+    }
+
+    /// Applies the SRv6 network function `action` to the packet.
+    ///
+    /// `param` is the parameter of the action, such as the next hop address
+    /// of `SEG6_LOCAL_ACTION_END_X` or the table of `SEG6_LOCAL_ACTION_END_T`.
+    #[inline]
+    pub fn seg6_action(&mut self, action: u32, param: &[u8]) -> Result<(), c_long> {
+        let len = u32::try_from(param.len()).map_err(|core::num::TryFromIntError { .. }| -1)?;
+        let ret =
+            unsafe { bpf_lwt_seg6_action(self.skb.skb, action, param.as_ptr() as *mut _, len) };
+        if ret == 0 { Ok(()) } else { Err(ret) }
+    }
+}
@@ -2,7 +2,13 @@ pub mod device;
 pub mod fentry;
 pub mod fexit;
 pub mod flow_dissector;
+pub mod fmod_ret;
+pub mod iter;
+#[cfg(any(bpf_target_arch = "x86_64", bpf_target_arch = "aarch64"))]
+pub mod ksyscall;
 pub mod lsm;
+pub mod lwt;
+pub mod netfilter;
 pub mod perf_event;
 pub mod probe;
 pub mod raw_tracepoint;
@@ -10,21 +16,31 @@ pub mod retprobe;
 pub mod sk_buff;
 pub mod sk_lookup;
 pub mod sk_msg;
+pub mod sk_reuseport;
 pub mod sock;
 pub mod sock_addr;
 pub mod sock_ops;
 pub mod sockopt;
+pub mod struct_ops;
+pub mod syscall;
 pub mod sysctl;
 pub mod tc;
 pub mod tp_btf;
 pub mod tracepoint;
+pub mod usdt;
 pub mod xdp;
 
 pub use device::DeviceContext;
 pub use fentry::FEntryContext;
 pub use fexit::FExitContext;
 pub use flow_dissector::FlowDissectorContext;
+pub use fmod_ret::FModRetContext;
+pub use iter::IterContext;
+#[cfg(any(bpf_target_arch = "x86_64", bpf_target_arch = "aarch64"))]
+pub use ksyscall::KSyscallContext;
 pub use lsm::LsmContext;
+pub use lwt::{LwtInContext, LwtOutContext, LwtSeg6LocalContext, LwtXmitContext};
+pub use netfilter::NetfilterContext;
 pub use perf_event::PerfEventContext;
 pub use probe::ProbeContext;
 pub use raw_tracepoint::RawTracePointContext;
@@ -32,12 +48,16 @@ pub use retprobe::RetProbeContext;
 pub use sk_buff::SkBuffContext;
 pub use sk_lookup::SkLookupContext;
 pub use sk_msg::SkMsgContext;
+pub use sk_reuseport::SkReuseportContext;
 pub use sock::SockContext;
 pub use sock_addr::SockAddrContext;
 pub use sock_ops::SockOpsContext;
 pub use sockopt::SockoptContext;
+pub use struct_ops::StructOpsContext;
+pub use syscall::SyscallContext;
 pub use sysctl::SysctlContext;
 pub use tc::TcContext;
 pub use tp_btf::BtfTracePointContext;
 pub use tracepoint::TracePointContext;
+pub use usdt::UsdtContext;
 pub use xdp::XdpContext;
@@ -0,0 +1,61 @@
+use core::ffi::c_void;
+
+use crate::EbpfContext;
+
+/// The context of netfilter programs.
+///
+/// Models `struct bpf_nf_ctx` from `include/net/netfilter/nf_bpf_link.h`, which
+/// isn't part of the UAPI headers the bindings are generated from.
+#[repr(C)]
+pub struct bpf_nf_ctx {
+    pub state: *const nf_hook_state,
+    pub skb: *mut c_void,
+}
+
+/// The leading members of `struct nf_hook_state` from
+/// `include/linux/netfilter.h`.
+#[repr(C)]
+pub struct nf_hook_state {
+    pub hook: u8,
+    pub pf: u8,
+}
+
+pub struct NetfilterContext {
+    pub ctx: *mut bpf_nf_ctx,
+}
+
+impl NetfilterContext {
+    pub fn new(ctx: *mut bpf_nf_ctx) -> NetfilterContext {
+        NetfilterContext { ctx }
+    }
+
+    /// Returns the state of the hook the program runs at.
+    #[inline]
+    pub fn state(&self) -> *const nf_hook_state {
+        unsafe { (*self.ctx).state }
+    }
+
+    /// Returns the kernel `struct sk_buff` of the packet traversing the hook.
+    #[inline]
+    pub fn skb(&self) -> *mut c_void {
+        unsafe { (*self.ctx).skb }
+    }
+
+    /// Returns the hook the program runs at, e.g. `NF_INET_LOCAL_IN`.
+    #[inline]
+    pub fn hook(&self) -> u8 {
+        unsafe { (*self.state()).hook }
+    }
+
+    /// Returns the protocol family of the hook, e.g. `NFPROTO_IPV4`.
+    #[inline]
+    pub fn pf(&self) -> u8 {
+        unsafe { (*self.state()).pf }
+    }
+}
+
+impl EbpfContext for NetfilterContext {
+    fn as_ptr(&self) -> *mut c_void {
+        self.ctx as *mut _
+    }
+}
@@ -0,0 +1,81 @@
+use core::ffi::c_void;
+
+use crate::{
+    EbpfContext, bindings::sk_reuseport_md, helpers::bpf_sk_select_reuseport,
+    maps::ReusePortSockArray,
+};
+
+pub struct SkReuseportContext {
+    pub md: *mut sk_reuseport_md,
+}
+
+impl SkReuseportContext {
+    pub fn new(md: *mut sk_reuseport_md) -> SkReuseportContext {
+        SkReuseportContext { md }
+    }
+
+    /// Returns the length of the packet.
+    #[expect(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u32 {
+        unsafe { (*self.md).len }
+    }
+
+    /// Returns the Ethernet protocol of the packet, in network byte order.
+    pub fn eth_protocol(&self) -> u32 {
+        unsafe { (*self.md).eth_protocol }
+    }
+
+    /// Returns the IP protocol of the packet, e.g. `IPPROTO_TCP` or `IPPROTO_UDP`.
+    pub fn ip_protocol(&self) -> u32 {
+        unsafe { (*self.md).ip_protocol }
+    }
+
+    /// Returns whether the sockets are bound to `INANY`.
+    pub fn bind_inany(&self) -> bool {
+        unsafe { (*self.md).bind_inany != 0 }
+    }
+
+    /// Returns the hash of the packet's 4-tuple.
+    pub fn hash(&self) -> u32 {
+        unsafe { (*self.md).hash }
+    }
+
+    /// Selects the socket stored at `index` in `map` to receive the packet or
+    /// connection.
+    ///
+    /// The program must then return `SK_PASS` for the selection to take effect.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #![expect(dead_code)]
+    /// use aya_ebpf::{
+    ///     bindings::sk_action, macros::map, maps::ReusePortSockArray,
+    ///     programs::SkReuseportContext,
+    /// };
+    ///
+    /// #[map]
+    /// static SOCKETS: ReusePortSockArray = ReusePortSockArray::with_max_entries(4, 0);
+    ///
+    /// fn try_select_socket(ctx: SkReuseportContext) -> Result<u32, i64> {
+    ///     ctx.select_reuseport(&SOCKETS, ctx.hash() % 4, 0)?;
+    ///     Ok(sk_action::SK_PASS)
+    /// }
+    /// ```
+    pub fn select_reuseport(
+        &self,
+        map: &ReusePortSockArray,
+        mut index: u32,
+        flags: u64,
+    ) -> Result<(), i64> {
+        let index: *mut _ = &mut index;
+        let ret = unsafe { bpf_sk_select_reuseport(self.md, map.as_ptr(), index.cast(), flags) };
+        if ret == 0 { Ok(()) } else { Err(ret) }
+    }
+}
+
+impl EbpfContext for SkReuseportContext {
+    fn as_ptr(&self) -> *mut c_void {
+        self.md as *mut _
+    }
+}
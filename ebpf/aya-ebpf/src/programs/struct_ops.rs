@@ -0,0 +1,47 @@
+use core::ffi::c_void;
+
+use crate::{EbpfContext, args::FromBtfArgument};
+
+pub struct StructOpsContext {
+    ctx: *mut c_void,
+}
+
+impl StructOpsContext {
+    pub fn new(ctx: *mut c_void) -> StructOpsContext {
+        StructOpsContext { ctx }
+    }
+
+    /// Returns the `n`th argument passed to the implemented struct member,
+    /// starting from 0.
+    ///
+    /// # Safety
+    ///
+    /// This function is deeply unsafe, as we are reading raw pointers into kernel memory.
+    /// In particular, the value of `n` must not exceed the number of function arguments.
+    /// Luckily, the BPF verifier will catch this for us.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #![expect(dead_code)]
+    /// # use aya_ebpf::{programs::StructOpsContext, cty::c_void};
+    /// unsafe fn try_ssthresh(ctx: StructOpsContext) -> Result<u32, u32> {
+    ///     // In the kernel, this member is defined as:
+    ///     //   u32 (*ssthresh)(struct sock *sk);
+    ///     let sk: *const c_void = ctx.arg(0);
+    ///
+    ///     // Do something with sk
+    ///
+    ///     Ok(2)
+    /// }
+    /// ```
+    pub unsafe fn arg<T: FromBtfArgument>(&self, n: usize) -> T {
+        unsafe { T::from_argument(self.ctx.cast(), n) }
+    }
+}
+
+impl EbpfContext for StructOpsContext {
+    fn as_ptr(&self) -> *mut c_void {
+        self.ctx
+    }
+}
@@ -0,0 +1,66 @@
+use core::{ffi::c_void, marker::PhantomData};
+
+use crate::EbpfContext;
+
+/// The context of a syscall program.
+///
+/// `T` is the user-defined context struct, whose bytes are passed to the
+/// program by `Syscall::run` in userspace.
+pub struct SyscallContext<T> {
+    ctx: *mut c_void,
+    _t: PhantomData<T>,
+}
+
+impl<T> SyscallContext<T> {
+    pub fn new(ctx: *mut c_void) -> SyscallContext<T> {
+        SyscallContext {
+            ctx,
+            _t: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the context struct.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the context passed from userspace is at
+    /// least as large as `T`. The verifier rejects the program otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #![expect(dead_code)]
+    /// # use aya_ebpf::programs::SyscallContext;
+    /// #[repr(C)]
+    /// struct Args {
+    ///     map_fd: u32,
+    ///     value: u64,
+    /// }
+    ///
+    /// unsafe fn try_setup(ctx: SyscallContext<Args>) -> Result<i32, i32> {
+    ///     let args = unsafe { ctx.args() };
+    ///     Ok(args.map_fd as i32)
+    /// }
+    /// ```
+    pub unsafe fn args(&self) -> &T {
+        unsafe { &*self.ctx.cast() }
+    }
+
+    /// Returns a mutable reference to the context struct.
+    ///
+    /// Changes are copied back to userspace once the program has run.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the context passed from userspace is at
+    /// least as large as `T`. The verifier rejects the program otherwise.
+    pub unsafe fn args_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.ctx.cast() }
+    }
+}
+
+impl<T> EbpfContext for SyscallContext<T> {
+    fn as_ptr(&self) -> *mut c_void {
+        self.ctx
+    }
+}
@@ -0,0 +1,208 @@
+use core::ffi::c_void;
+
+#[cfg(any(
+    bpf_target_arch = "arm",
+    bpf_target_arch = "mips",
+    bpf_target_arch = "powerpc64",
+    bpf_target_arch = "x86_64",
+))]
+use crate::bindings::pt_regs;
+#[cfg(any(
+    bpf_target_arch = "aarch64",
+    bpf_target_arch = "loongarch64",
+    bpf_target_arch = "s390x",
+))]
+use crate::bindings::user_pt_regs as pt_regs;
+#[cfg(bpf_target_arch = "riscv64")]
+use crate::bindings::user_regs_struct as pt_regs;
+use crate::{
+    EbpfContext,
+    helpers::{bpf_get_attach_cookie, bpf_probe_read_kernel, bpf_probe_read_user},
+    maps::Array,
+};
+
+/// The maximum number of arguments of a USDT probe.
+pub const USDT_MAX_ARG_COUNT: usize = 12;
+
+/// The maximum number of USDT probe locations which can be attached at once.
+pub const USDT_MAX_SPEC_COUNT: u32 = 256;
+
+const USDT_ARG_CONST: u32 = 0;
+const USDT_ARG_REG: u32 = 1;
+const USDT_ARG_REG_DEREF: u32 = 2;
+
+// The layout of `UsdtArgSpec` and `UsdtSpec` must be kept in sync with
+// `aya::programs::usdt`, which fills the spec map when attaching.
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct UsdtArgSpec {
+    val_off: u64,
+    arg_type: u32,
+    reg_off: i16,
+    arg_signed: u8,
+    arg_bitshift: u8,
+}
+
+/// The location of the arguments of a USDT probe location.
+///
+/// User space decodes the argument specs of each probe location from its
+/// `.note.stapsdt` ELF note when attaching, and stores them in the
+/// [`UsdtSpecMap`] of the program. [`UsdtContext::arg`] then reads the
+/// arguments from the registers or memory they describe.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct UsdtSpec {
+    args: [UsdtArgSpec; USDT_MAX_ARG_COUNT],
+    cookie: u64,
+    arg_count: u16,
+    _pad: [u8; 6],
+}
+
+/// The specs of the attached locations of the probes of a USDT program,
+/// indexed by attach cookie.
+///
+/// The `#[usdt]` macro declares a spec map along with each program, so that
+/// objects without USDT programs don't carry one.
+#[repr(transparent)]
+pub struct UsdtSpecMap(Array<UsdtSpec>);
+
+impl UsdtSpecMap {
+    #[expect(clippy::new_without_default)]
+    pub const fn new() -> UsdtSpecMap {
+        UsdtSpecMap(Array::with_max_entries(USDT_MAX_SPEC_COUNT, 0))
+    }
+}
+
+pub struct UsdtContext {
+    pub regs: *mut pt_regs,
+    specs: &'static UsdtSpecMap,
+}
+
+impl UsdtContext {
+    pub fn new(ctx: *mut c_void, specs: &'static UsdtSpecMap) -> UsdtContext {
+        UsdtContext {
+            regs: ctx as *mut pt_regs,
+            specs,
+        }
+    }
+
+    /// Returns the spec of the probe location being hit. The attach cookie is
+    /// the index of the spec in the spec map of the program.
+    fn spec(&self) -> Option<&UsdtSpec> {
+        let id = unsafe { bpf_get_attach_cookie(self.as_ptr()) };
+        self.specs.0.get(id as u32)
+    }
+
+    /// Returns the cookie passed to `Usdt::attach` in user space.
+    pub fn cookie(&self) -> Option<u64> {
+        self.spec().map(|spec| spec.cookie)
+    }
+
+    /// Returns the number of arguments of the probe.
+    pub fn arg_count(&self) -> Option<usize> {
+        self.spec().map(|spec| usize::from(spec.arg_count))
+    }
+
+    /// Returns the `n`th argument of the probe, starting from 0.
+    ///
+    /// The argument is truncated to its actual size, as recorded in the
+    /// `.note.stapsdt` ELF note of the probe, and sign-extended if it is
+    /// signed. Returns `None` if the probe has fewer than `n + 1` arguments or
+    /// if the argument can't be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use aya_ebpf::programs::UsdtContext;
+    /// fn try_query_start(ctx: UsdtContext) -> Result<u32, u32> {
+    ///     let query: *const u8 = ctx.arg(0).ok_or(1u32)?;
+    ///
+    ///     // Do something with the query
+    ///
+    ///     Ok(0)
+    /// }
+    /// ```
+    pub fn arg<T: FromUsdtArg>(&self, n: usize) -> Option<T> {
+        let spec = self.spec()?;
+        if n >= USDT_MAX_ARG_COUNT || n >= usize::from(spec.arg_count) {
+            return None;
+        }
+        self.read_arg(&spec.args[n]).map(T::from_usdt_arg)
+    }
+
+    fn read_arg(&self, arg: &UsdtArgSpec) -> Option<u64> {
+        let val = match arg.arg_type {
+            USDT_ARG_CONST => arg.val_off,
+            USDT_ARG_REG => self.read_reg(arg.reg_off)?,
+            USDT_ARG_REG_DEREF => {
+                let addr = self.read_reg(arg.reg_off)?.wrapping_add(arg.val_off);
+                let val = unsafe { bpf_probe_read_user(addr as *const u64) }.ok()?;
+                // The value is at the start of the 8 bytes that were read, move
+                // it to the low bits.
+                #[cfg(target_endian = "big")]
+                let val = val >> arg.arg_bitshift;
+                val
+            }
+            _ => return None,
+        };
+        // Truncate the value to its size, sign-extending it if needed.
+        let shift = u32::from(arg.arg_bitshift);
+        let val = val << shift;
+        #[expect(clippy::cast_sign_loss)]
+        let val = if arg.arg_signed != 0 {
+            ((val as i64) >> shift) as u64
+        } else {
+            val >> shift
+        };
+        Some(val)
+    }
+
+    fn read_reg(&self, reg_off: i16) -> Option<u64> {
+        // The verifier doesn't allow variable offset accesses to the context.
+        let reg = unsafe { self.regs.cast::<u8>().offset(reg_off as isize) };
+        unsafe { bpf_probe_read_kernel(reg.cast::<u64>()) }.ok()
+    }
+}
+
+impl EbpfContext for UsdtContext {
+    fn as_ptr(&self) -> *mut c_void {
+        self.regs as *mut c_void
+    }
+}
+
+/// A trait that indicates a valid type for a USDT argument.
+///
+/// Arguments are decoded as 64-bit values which are then converted to the
+/// requested type. Users should not implement this trait.
+pub trait FromUsdtArg: Sized {
+    /// Converts the decoded value of an argument to `Self`.
+    fn from_usdt_arg(value: u64) -> Self;
+}
+
+impl<T> FromUsdtArg for *const T {
+    fn from_usdt_arg(value: u64) -> Self {
+        value as usize as *const T
+    }
+}
+
+impl<T> FromUsdtArg for *mut T {
+    fn from_usdt_arg(value: u64) -> Self {
+        value as usize as *mut T
+    }
+}
+
+/// Helper macro to implement [`FromUsdtArg`] for a primitive type.
+macro_rules! impl_from_usdt_arg {
+    ($($type:ident),+) => {
+        $(
+            impl FromUsdtArg for $type {
+                fn from_usdt_arg(value: u64) -> Self {
+                    value as $type
+                }
+            }
+        )+
+    };
+}
+
+impl_from_usdt_arg!(u8, u16, u32, u64, i8, i16, i32, i64, usize, isize);
@@ -14,6 +14,18 @@ pub mod bpf_probe_read {
     unsafe impl aya::Pod for TestResult {}
 }
 
+pub mod cgroup_storage {
+    /// The UDP port the test sends packets to. The program only counts these packets.
+    pub const PORT: u16 = 0x5a5a;
+}
+
+pub mod ksyscall {
+    /// The invalid file descriptor passed to `pread64` by the test.
+    pub const FD: i32 = 0x5a5a;
+    /// The offset passed to `pread64` by the test, in the fourth argument.
+    pub const OFFSET: i64 = 0x1234;
+}
+
 pub mod raw_tracepoint {
     #[repr(C)]
     #[derive(Clone, Copy)]
@@ -64,3 +76,19 @@ pub mod strncmp {
     #[cfg(feature = "user")]
     unsafe impl aya::Pod for TestResult {}
 }
+
+pub mod usdt {
+    /// The number of arguments of the probe fired by the test.
+    pub const ARG_COUNT: usize = 3;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    pub struct UsdtEvent {
+        pub cookie: u64,
+        pub arg_count: u64,
+        pub args: [i64; ARG_COUNT],
+    }
+
+    #[cfg(feature = "user")]
+    unsafe impl aya::Pod for UsdtEvent {}
+}
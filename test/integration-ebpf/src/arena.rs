@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    macros::{map, syscall},
+    maps::{Arena, Array},
+    programs::SyscallContext,
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+
+#[map]
+static ARENA: Arena = Arena::with_max_pages(4, 0);
+
+// Holds the userspace address of the value written to the arena.
+#[map]
+static RESULT: Array<u64> = Array::with_max_entries(1, 0);
+
+#[syscall]
+pub fn arena_alloc(mut ctx: SyscallContext<u64>) -> i32 {
+    let value = unsafe { *ctx.args_mut() };
+    let Some(ptr) = ARENA.alloc_pages::<u64>(1, -1) else {
+        return 1;
+    };
+    unsafe { *ptr.as_ptr() = value };
+    let Some(result) = RESULT.get_ptr_mut(0) else {
+        return 1;
+    };
+    unsafe { *result = ptr.addr() as u64 };
+    0
+}
@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    macros::{cgroup_skb, map},
+    maps::{CgroupStorage, PerCpuCgroupStorage},
+    programs::SkBuffContext,
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+use integration_common::cgroup_storage::PORT;
+
+#[map]
+static PACKETS: CgroupStorage<u64> = CgroupStorage::new(0);
+
+#[map]
+static PER_CPU_PACKETS: PerCpuCgroupStorage<u64> = PerCpuCgroupStorage::new(0);
+
+const IPPROTO_UDP: u8 = 17;
+
+#[cgroup_skb(egress)]
+pub fn count_packets(ctx: SkBuffContext) -> i32 {
+    // The packet starts at the IPv4 header, which has no options as it is built by the kernel.
+    let is_test_packet = matches!(ctx.load::<u8>(9), Ok(IPPROTO_UDP))
+        && matches!(ctx.load::<u16>(22), Ok(port) if u16::from_be(port) == PORT);
+    if is_test_packet {
+        // The test sends packets from a single thread, the increments don't race.
+        unsafe {
+            *PACKETS.get_ptr_mut() += 1;
+            *PER_CPU_PACKETS.get_ptr_mut() += 1;
+        }
+    }
+    1
+}
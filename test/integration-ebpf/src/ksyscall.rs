@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    cty::c_int,
+    macros::{ksyscall, map},
+    maps::Array,
+    programs::KSyscallContext,
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+use integration_common::ksyscall::FD;
+
+#[map]
+static RESULT: Array<i64> = Array::with_max_entries(1, 0);
+
+#[ksyscall(syscall = "pread64")]
+pub fn pread64(ctx: KSyscallContext) -> u32 {
+    if ctx.arg::<c_int>(0) != Some(FD) {
+        return 0;
+    }
+    if let (Some(offset), Some(ptr)) = (ctx.arg::<i64>(3), RESULT.get_ptr_mut(0)) {
+        unsafe { *ptr = offset };
+    }
+    0
+}
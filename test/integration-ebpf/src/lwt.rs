@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    bindings::bpf_ret_code::BPF_OK,
+    macros::{lwt_out, lwt_seg6local, map},
+    maps::Array,
+    programs::{LwtOutContext, LwtSeg6LocalContext},
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+
+#[map]
+static PACKETS: Array<u64> = Array::with_max_entries(1, 0);
+
+#[lwt_out]
+pub fn lwt_count(_ctx: LwtOutContext) -> i32 {
+    if let Some(count) = PACKETS.get_ptr_mut(0) {
+        unsafe { *count += 1 };
+    }
+    BPF_OK as i32
+}
+
+#[lwt_seg6local]
+pub fn lwt_end_bpf(_ctx: LwtSeg6LocalContext) -> i32 {
+    BPF_OK as i32
+}
@@ -0,0 +1,37 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    macros::{map, uprobe},
+    maps::{Array, ArrayOfMaps, HashOfMaps},
+    programs::ProbeContext,
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+
+#[map]
+static INNER_0: Array<u32> = Array::with_max_entries(1, 0);
+
+#[map]
+static INNER_1: Array<u32> = Array::with_max_entries(1, 0);
+
+#[map]
+static OUTER_ARRAY: ArrayOfMaps<Array<u32>> =
+    ArrayOfMaps::with_max_entries(1, 0, Array::with_max_entries(1, 0));
+
+#[map]
+static OUTER_HASH: HashOfMaps<u32, Array<u32>> =
+    HashOfMaps::with_max_entries(1, 0, Array::with_max_entries(1, 0));
+
+#[map]
+static RESULT: Array<u32> = Array::with_max_entries(2, 0);
+
+#[uprobe]
+pub fn map_of_maps(_ctx: ProbeContext) {
+    if let Some(value) = OUTER_ARRAY.get(0).and_then(|inner| inner.get(0)) {
+        let _ = RESULT.set(0, value, 0);
+    }
+    if let Some(value) = OUTER_HASH.get(&0).and_then(|inner| inner.get(0)) {
+        let _ = RESULT.set(1, value, 0);
+    }
+}
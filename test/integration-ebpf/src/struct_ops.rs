@@ -0,0 +1,73 @@
+#![no_std]
+#![no_main]
+
+use core::ffi::c_void;
+
+use aya_ebpf::{
+    macros::{struct_ops, struct_ops_map},
+    programs::StructOpsContext,
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+
+// The members of the kernel struct which are needed to register a congestion
+// control algorithm.
+#[repr(C)]
+pub struct tcp_congestion_ops {
+    ssthresh: Option<fn(*mut c_void) -> u32>,
+    cong_avoid: Option<fn(*mut c_void)>,
+    undo_cwnd: Option<fn(*mut c_void) -> u32>,
+    name: [u8; 16],
+}
+
+const fn name(s: &[u8]) -> [u8; 16] {
+    let mut name = [0; 16];
+    let mut i = 0;
+    while i < s.len() {
+        name[i] = s[i];
+        i += 1;
+    }
+    name
+}
+
+#[struct_ops]
+pub fn ssthresh(_ctx: StructOpsContext) -> u32 {
+    2
+}
+
+#[struct_ops]
+pub fn cong_avoid(_ctx: StructOpsContext) {}
+
+#[struct_ops]
+pub fn undo_cwnd(_ctx: StructOpsContext) -> u32 {
+    2
+}
+
+#[struct_ops]
+pub fn ssthresh_no_link(_ctx: StructOpsContext) -> u32 {
+    2
+}
+
+#[struct_ops]
+pub fn cong_avoid_no_link(_ctx: StructOpsContext) {}
+
+#[struct_ops]
+pub fn undo_cwnd_no_link(_ctx: StructOpsContext) -> u32 {
+    2
+}
+
+#[struct_ops_map]
+static TCP_CC: tcp_congestion_ops = tcp_congestion_ops {
+    ssthresh: Some(ssthresh),
+    cong_avoid: Some(cong_avoid),
+    undo_cwnd: Some(undo_cwnd),
+    name: name(b"aya_link"),
+};
+
+#[struct_ops_map(no_link)]
+static TCP_CC_NO_LINK: tcp_congestion_ops = tcp_congestion_ops {
+    ssthresh: Some(ssthresh_no_link),
+    cong_avoid: Some(cong_avoid_no_link),
+    undo_cwnd: Some(undo_cwnd_no_link),
+    name: name(b"aya_no_link"),
+};
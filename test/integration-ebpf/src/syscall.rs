@@ -0,0 +1,13 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{macros::syscall, programs::SyscallContext};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+
+#[syscall]
+pub fn increment(mut ctx: SyscallContext<u64>) -> i32 {
+    let value = unsafe { ctx.args_mut() };
+    *value += 1;
+    *value as i32
+}
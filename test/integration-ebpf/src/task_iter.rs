@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    helpers::bpf_seq_printf,
+    macros::iter,
+    programs::{IterContext, iter::bpf_iter__task},
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+
+#[iter(target = "task")]
+pub fn task_iter(ctx: IterContext<bpf_iter__task>) -> i32 {
+    if ctx.task().is_null() {
+        return 0;
+    }
+    if ctx.seq_num() == 0 {
+        let _ = ctx.seq_write(b"seq_num\n");
+    }
+    unsafe { bpf_seq_printf!(ctx.seq(), b"%llu\n", ctx.seq_num()) };
+    0
+}
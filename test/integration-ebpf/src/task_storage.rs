@@ -0,0 +1,32 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    helpers::bpf_get_current_task_btf,
+    macros::{map, tracepoint},
+    maps::TaskStorage,
+    programs::TracePointContext,
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+
+#[map]
+static TASK_STORAGE: TaskStorage<u64> = TaskStorage::new(0);
+
+// Counts the getpid calls of each task.
+#[tracepoint]
+pub fn task_storage_getpid(_ctx: TracePointContext) -> u32 {
+    let task = unsafe { bpf_get_current_task_btf() };
+    if let Some(count) = unsafe { TASK_STORAGE.get_or_create(task, None) } {
+        unsafe { *count += 1 };
+    }
+    0
+}
+
+// Resets the count of the calling task.
+#[tracepoint]
+pub fn task_storage_getppid(_ctx: TracePointContext) -> u32 {
+    let task = unsafe { bpf_get_current_task_btf() };
+    let _: Result<(), _> = unsafe { TASK_STORAGE.delete(task) };
+    0
+}
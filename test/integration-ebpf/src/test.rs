@@ -3,9 +3,10 @@
 
 use aya_ebpf::{
     bindings::{bpf_ret_code, xdp_action},
-    macros::{flow_dissector, kprobe, kretprobe, tracepoint, uprobe, uretprobe, xdp},
+    macros::{flow_dissector, kprobe, kretprobe, tracepoint, uprobe, uretprobe, usdt, xdp},
     programs::{
-        FlowDissectorContext, ProbeContext, RetProbeContext, TracePointContext, XdpContext,
+        FlowDissectorContext, ProbeContext, RetProbeContext, TracePointContext, UsdtContext,
+        XdpContext,
     },
 };
 #[cfg(not(test))]
@@ -33,6 +34,11 @@ pub fn test_kretprobe(_ctx: RetProbeContext) -> u32 {
     0
 }
 
+#[kprobe(multi)]
+pub fn test_kprobe_multi(_ctx: ProbeContext) -> u32 {
+    0
+}
+
 #[tracepoint]
 pub fn test_tracepoint(_ctx: TracePointContext) -> u32 {
     0
@@ -48,6 +54,17 @@ pub fn test_uretprobe(_ctx: RetProbeContext) -> u32 {
     0
 }
 
+#[uprobe(multi)]
+pub fn test_uprobe_multi(_ctx: ProbeContext) -> u32 {
+    0
+}
+
+#[usdt]
+pub fn test_usdt(ctx: UsdtContext) -> u32 {
+    let _: Option<u64> = ctx.arg(0);
+    0
+}
+
 #[flow_dissector]
 pub fn test_flow(_ctx: FlowDissectorContext) -> u32 {
     // TODO: write an actual flow dissector. See tools/testing/selftests/bpf/progs/bpf_flow.c in the
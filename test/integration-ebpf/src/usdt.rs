@@ -0,0 +1,28 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    macros::{map, usdt},
+    maps::Array,
+    programs::UsdtContext,
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+use integration_common::usdt::UsdtEvent;
+
+#[map]
+static RESULT: Array<UsdtEvent> = Array::with_max_entries(1, 0);
+
+#[usdt]
+pub fn usdt_args(ctx: UsdtContext) -> u32 {
+    let Some(event) = RESULT.get_ptr_mut(0) else {
+        return 0;
+    };
+    let event = unsafe { &mut *event };
+    event.cookie = ctx.cookie().unwrap_or(0);
+    event.arg_count = ctx.arg_count().unwrap_or(0) as u64;
+    for (n, arg) in event.args.iter_mut().enumerate() {
+        *arg = ctx.arg(n).unwrap_or(0);
+    }
+    0
+}
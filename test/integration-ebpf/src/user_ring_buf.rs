@@ -0,0 +1,39 @@
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    macros::{map, uprobe},
+    maps::{Array, UserRingBuf},
+    programs::ProbeContext,
+};
+#[cfg(not(test))]
+extern crate ebpf_panic;
+
+#[map]
+static USER_RING_BUF: UserRingBuf = UserRingBuf::with_byte_size(0, 0);
+
+// Holds the sum of the drained samples and the number of samples drained.
+#[map]
+static RESULT: Array<u64> = Array::with_max_entries(2, 0);
+
+#[uprobe]
+pub fn user_ring_buf_drain(_ctx: ProbeContext) {
+    let mut sum = 0u64;
+    let drained = USER_RING_BUF.drain(
+        |entry| {
+            if let Ok(value) = entry.read::<u64>(0) {
+                sum += value;
+            }
+            0
+        },
+        0,
+    );
+    if let Ok(drained) = drained {
+        if let Some(ptr) = RESULT.get_ptr_mut(0) {
+            unsafe { *ptr += sum };
+        }
+        if let Some(ptr) = RESULT.get_ptr_mut(1) {
+            unsafe { *ptr += u64::from(drained) };
+        }
+    }
+}
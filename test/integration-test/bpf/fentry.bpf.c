@@ -0,0 +1,33 @@
+// clang-format off
+#include <vmlinux.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+// clang-format on
+
+struct {
+  __uint(type, BPF_MAP_TYPE_ARRAY);
+  __type(key, __u32);
+  __type(value, __u64);
+  __uint(max_entries, 2);
+} hits SEC(".maps");
+
+static __always_inline void record_hit(__u32 index) {
+  __u64 *value = bpf_map_lookup_elem(&hits, &index);
+  if (value) {
+    __sync_fetch_and_add(value, 1);
+  }
+}
+
+SEC("fentry/xdp_pass")
+int BPF_PROG(fentry_xdp_pass) {
+  record_hit(0);
+  return 0;
+}
+
+SEC("fexit/xdp_pass")
+int BPF_PROG(fexit_xdp_pass) {
+  record_hit(1);
+  return 0;
+}
+
+char _license[] SEC("license") = "GPL";
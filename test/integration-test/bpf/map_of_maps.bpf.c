@@ -0,0 +1,56 @@
+// clang-format off
+#include <vmlinux.h>
+#include <bpf/bpf_helpers.h>
+// clang-format on
+
+struct inner {
+  __uint(type, BPF_MAP_TYPE_ARRAY);
+  __type(key, __u32);
+  __type(value, __u32);
+  __uint(max_entries, 1);
+};
+
+struct inner INNER_0 SEC(".maps");
+struct inner INNER_1 SEC(".maps");
+
+struct {
+  __uint(type, BPF_MAP_TYPE_ARRAY_OF_MAPS);
+  __type(key, __u32);
+  __uint(max_entries, 1);
+  __array(values, struct inner);
+} OUTER_ARRAY SEC(".maps");
+
+struct {
+  __uint(type, BPF_MAP_TYPE_HASH_OF_MAPS);
+  __type(key, __u32);
+  __uint(max_entries, 1);
+  __array(values, struct inner);
+} OUTER_HASH SEC(".maps");
+
+struct {
+  __uint(type, BPF_MAP_TYPE_ARRAY);
+  __type(key, __u32);
+  __type(value, __u32);
+  __uint(max_entries, 2);
+} RESULT SEC(".maps");
+
+static __always_inline void copy_value(void *outer, __u32 index) {
+  __u32 key = 0;
+  void *inner = bpf_map_lookup_elem(outer, &key);
+  if (!inner) {
+    return;
+  }
+  __u32 *value = bpf_map_lookup_elem(inner, &key);
+  if (value) {
+    bpf_map_update_elem(&RESULT, &index, value, BPF_ANY);
+  }
+}
+
+SEC("uprobe")
+int map_of_maps(void *ctx) {
+  copy_value(&OUTER_ARRAY, 0);
+  copy_value(&OUTER_HASH, 1);
+  return 0;
+}
+
+char _license[] SEC("license") = "GPL";
@@ -0,0 +1,30 @@
+// clang-format off
+#include <vmlinux.h>
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+// clang-format on
+
+// Defined by the nf_conntrack module, the flavor keeps it from clashing with
+// the definition in vmlinux.h.
+struct nf_conn___aya {
+  unsigned long status;
+} __attribute__((preserve_access_index));
+
+struct {
+  __uint(type, BPF_MAP_TYPE_ARRAY);
+  __type(key, __u32);
+  __type(value, __u64);
+  __uint(max_entries, 1);
+} status SEC(".maps");
+
+// nf_ct_delete is implemented by the nf_conntrack module.
+SEC("fentry/nf_ct_delete")
+int BPF_PROG(fentry_nf_ct_delete, struct nf_conn___aya *ct) {
+  __u32 index = 0;
+  __u64 value = BPF_CORE_READ(ct, status);
+  bpf_map_update_elem(&status, &index, &value, BPF_ANY);
+  return 0;
+}
+
+char _license[] SEC("license") = "GPL";
@@ -63,8 +63,11 @@ fn main() -> Result<()> {
 
     const C_BPF: &[(&str, bool)] = &[
         ("ext.bpf.c", false),
+        ("fentry.bpf.c", false),
         ("iter.bpf.c", true),
         ("main.bpf.c", false),
+        ("map_of_maps.bpf.c", false),
+        ("module_btf.bpf.c", false),
         ("multimap-btf.bpf.c", false),
         ("enum_signed_32_checked_variants_reloc.bpf.c", true),
         ("enum_signed_32_reloc.bpf.c", true),
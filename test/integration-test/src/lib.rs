@@ -8,8 +8,11 @@ macro_rules! bpf_file {
 
 bpf_file!(
     EXT => "ext.bpf.o",
+    FENTRY => "fentry.bpf.o",
     ITER_TASK => "iter.bpf.o",
     MAIN => "main.bpf.o",
+    MAP_OF_MAPS_BTF => "map_of_maps.bpf.o",
+    MODULE_BTF => "module_btf.bpf.o",
     MULTIMAP_BTF => "multimap-btf.bpf.o",
 
     ENUM_SIGNED_32_RELOC_BPF => "enum_signed_32_reloc.bpf.o",
@@ -39,8 +42,10 @@ bpf_file!(
     VARIABLES_RELOC => "variables_reloc.bpf.o",
 
     BPF_PROBE_READ => "bpf_probe_read",
+    KSYSCALL => "ksyscall",
     LOG => "log",
     MAP_TEST => "map_test",
+    MAP_OF_MAPS => "map_of_maps",
     MEMMOVE_TEST => "memmove_test",
     NAME_TEST => "name_test",
     PASS => "pass",
@@ -50,11 +55,20 @@ bpf_file!(
     RING_BUF => "ring_buf",
     SIMPLE_PROG => "simple_prog",
     STRNCMP => "strncmp",
+    SYSCALL => "syscall",
+    TASK_ITER => "task_iter",
     TCX => "tcx",
     TEST => "test",
     TWO_PROGS => "two_progs",
     XDP_SEC => "xdp_sec",
     UPROBE_COOKIE => "uprobe_cookie",
+    USER_RING_BUF => "user_ring_buf",
+    ARENA => "arena",
+    LWT => "lwt",
+    STRUCT_OPS => "struct_ops",
+    TASK_STORAGE => "task_storage",
+    USDT => "usdt",
+    CGROUP_STORAGE => "cgroup_storage",
 );
 
 #[cfg(test)]
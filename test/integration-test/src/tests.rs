@@ -1,17 +1,31 @@
+mod arena;
 mod bpf_probe_read;
 mod btf_relocations;
+mod cgroup_storage;
 mod elf;
 mod feature_probe;
+mod fentry;
 mod info;
 mod iter;
+mod ksyscall;
 mod load;
 mod log;
+mod lwt;
+mod map_of_maps;
+mod module_btf;
+mod netkit;
 mod raw_tracepoint;
 mod rbpf;
 mod relocations;
 mod ring_buf;
 mod smoke;
 mod strncmp;
+mod struct_ops;
+mod syscall;
+mod task_storage;
 mod tcx;
+mod test_run;
 mod uprobe_cookie;
+mod usdt;
+mod user_ring_buf;
 mod xdp;
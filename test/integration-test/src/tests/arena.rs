@@ -0,0 +1,33 @@
+use aya::{
+    Ebpf,
+    maps::{Arena, Array},
+    programs::Syscall,
+    util::KernelVersion,
+};
+
+#[test_log::test]
+fn arena_alloc() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(6, 9, 0) {
+        eprintln!("skipping arena_alloc test on kernel {kernel_version:?}");
+        return;
+    }
+
+    let mut ebpf = Ebpf::load(crate::ARENA).unwrap();
+    // The arena must be mapped before the program is loaded.
+    let arena = Arena::try_from(ebpf.take_map("ARENA").unwrap()).unwrap();
+    let result = Array::<_, u64>::try_from(ebpf.take_map("RESULT").unwrap()).unwrap();
+
+    let prog: &mut Syscall = ebpf.program_mut("arena_alloc").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+
+    let mut ctx = 42u64.to_ne_bytes();
+    assert_eq!(prog.run(&mut ctx).unwrap(), 0);
+
+    let memory = arena.as_ptr();
+    let start = memory.cast::<u8>().as_ptr() as u64;
+    let addr = result.get(&0, 0).unwrap();
+    assert!(addr >= start && addr < start + memory.len() as u64);
+    let value = unsafe { (addr as *const u64).read() };
+    assert_eq!(value, 42);
+}
@@ -0,0 +1,120 @@
+use std::{
+    ffi::CString,
+    fs::{self, File},
+    net::UdpSocket,
+    os::unix::fs::MetadataExt as _,
+    path::PathBuf,
+};
+
+use aya::{
+    Ebpf,
+    maps::{CgroupStorage, PerCpuCgroupStorage, cgroup_storage::CgroupStorageKey},
+    programs::{CgroupAttachMode, CgroupSkb, CgroupSkbAttachType},
+    util::KernelVersion,
+};
+use aya_obj::generated::bpf_attach_type::BPF_CGROUP_INET_EGRESS;
+use integration_common::cgroup_storage::PORT;
+
+/// Returns the cgroup v2 directory of the current process, mounting the cgroup v2 hierarchy on
+/// `/sys/fs/cgroup` if it isn't mounted yet.
+fn current_cgroup() -> PathBuf {
+    let mounts = fs::read_to_string("/proc/self/mounts").unwrap();
+    let root = mounts
+        .lines()
+        .find_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _source = fields.next()?;
+            let target = fields.next()?;
+            (fields.next()? == "cgroup2").then(|| PathBuf::from(target))
+        })
+        .unwrap_or_else(|| {
+            let root = PathBuf::from("/sys/fs/cgroup");
+            let fstype = CString::new("cgroup2").unwrap();
+            let target = CString::new(root.to_str().unwrap()).unwrap();
+            let ret = unsafe {
+                libc::mount(
+                    fstype.as_ptr(),
+                    target.as_ptr(),
+                    fstype.as_ptr(),
+                    0,
+                    std::ptr::null(),
+                )
+            };
+            assert_eq!(
+                ret,
+                0,
+                "failed to mount cgroup2: {}",
+                std::io::Error::last_os_error()
+            );
+            root
+        });
+
+    // The cgroup v2 entry is the one with hierarchy ID 0, eg: `0::/user.slice`.
+    let cgroups = fs::read_to_string("/proc/self/cgroup").unwrap();
+    let path = cgroups
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .unwrap();
+    root.join(path.trim_start_matches('/'))
+}
+
+#[test_log::test]
+fn cgroup_storage() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(4, 20, 0) {
+        eprintln!(
+            "skipping test on kernel {kernel_version:?}, per-CPU cgroup storage was added in 4.20"
+        );
+        return;
+    }
+
+    let _netns = crate::utils::NetNsGuard::new();
+
+    let cgroup_path = current_cgroup();
+    let cgroup = File::open(&cgroup_path).unwrap();
+    let key = CgroupStorageKey::new(
+        fs::metadata(&cgroup_path).unwrap().ino(),
+        BPF_CGROUP_INET_EGRESS as u32,
+    );
+
+    let mut bpf = Ebpf::load(crate::CGROUP_STORAGE).unwrap();
+    let prog: &mut CgroupSkb = bpf
+        .program_mut("count_packets")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    prog.load().unwrap();
+    let link_id = prog
+        .attach(
+            &cgroup,
+            CgroupSkbAttachType::Egress,
+            CgroupAttachMode::AllowMultiple,
+        )
+        .unwrap();
+
+    const PACKET_COUNT: u64 = 10;
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for _ in 0..PACKET_COUNT {
+        socket.send_to(b"aya", ("127.0.0.1", PORT)).unwrap();
+    }
+
+    let packets = CgroupStorage::<_, u64>::try_from(bpf.map("PACKETS").unwrap()).unwrap();
+    assert_eq!(packets.get(&key, 0).unwrap(), PACKET_COUNT);
+    let keys = packets.keys().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_matches::assert_matches!(keys.as_slice(), [k] => {
+        assert_eq!(k.cgroup_inode_id(), key.cgroup_inode_id());
+        assert_eq!(k.attach_type(), key.attach_type());
+    });
+
+    let per_cpu_packets =
+        PerCpuCgroupStorage::<_, u64>::try_from(bpf.map("PER_CPU_PACKETS").unwrap()).unwrap();
+    let values = per_cpu_packets.get(&key, 0).unwrap();
+    assert_eq!(values.iter().sum::<u64>(), PACKET_COUNT);
+
+    let prog: &mut CgroupSkb = bpf
+        .program_mut("count_packets")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    prog.detach(link_id).unwrap();
+}
@@ -0,0 +1,47 @@
+use aya::{
+    Ebpf,
+    maps::Array,
+    programs::{FEntry, FExit, TestRunOptions, Xdp},
+    util::KernelVersion,
+};
+
+#[test_log::test]
+fn fentry_fexit_program() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 5, 0) {
+        eprintln!("skipping test on kernel {kernel_version:?}, fentry and fexit were added in 5.5");
+        return;
+    }
+
+    let mut bpf = Ebpf::load(crate::MAIN).unwrap();
+    let pass: &mut Xdp = bpf.program_mut("xdp_pass").unwrap().try_into().unwrap();
+    pass.load().unwrap();
+
+    let mut trace = Ebpf::load(crate::FENTRY).unwrap();
+    let fentry: &mut FEntry = trace
+        .program_mut("fentry_xdp_pass")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    fentry
+        .load_for_program(pass.fd().unwrap().try_clone().unwrap(), "xdp_pass")
+        .unwrap();
+    fentry.attach().unwrap();
+    let fexit: &mut FExit = trace
+        .program_mut("fexit_xdp_pass")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    fexit
+        .load_for_program(pass.fd().unwrap().try_clone().unwrap(), "xdp_pass")
+        .unwrap();
+    fexit.attach().unwrap();
+
+    let packet = [0xaa; 64];
+    pass.test_run(TestRunOptions::new().data_in(&packet).repeat(3))
+        .unwrap();
+
+    let hits: Array<_, u64> = Array::try_from(trace.map("hits").unwrap()).unwrap();
+    assert_eq!(hits.get(&0, 0).unwrap(), 3);
+    assert_eq!(hits.get(&1, 0).unwrap(), 3);
+}
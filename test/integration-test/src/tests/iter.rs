@@ -1,6 +1,10 @@
 use std::io::BufRead as _;
 
-use aya::{Btf, Ebpf, programs::Iter};
+use aya::{
+    Btf, Ebpf,
+    programs::{Iter, IterLinkOptions, TaskIterTarget},
+    util::KernelVersion,
+};
 
 #[test_log::test]
 fn iter_task() {
@@ -25,3 +29,50 @@ fn iter_task() {
         "Unexpected line_init value: '{line_init}', expected one of: {expected_values:?}"
     );
 }
+
+#[test_log::test]
+fn iter_task_rust() {
+    let mut ebpf = Ebpf::load(crate::TASK_ITER).unwrap();
+    let btf = Btf::from_sys_fs().unwrap();
+    let prog: &mut Iter = ebpf.program_mut("task_iter").unwrap().try_into().unwrap();
+    prog.load("task", &btf).unwrap();
+
+    let link_id = prog.attach().unwrap();
+    let link = prog.take_link(link_id).unwrap();
+    let file = link.into_file().unwrap();
+    let reader = std::io::BufReader::new(file);
+
+    let lines = reader
+        .lines()
+        .map(Result::unwrap)
+        .take(3)
+        .collect::<Vec<_>>();
+    assert_eq!(lines, ["seq_num", "0", "1"]);
+}
+
+#[test_log::test]
+fn iter_task_tid() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(6, 1, 0) {
+        eprintln!(
+            "skipping test on kernel {kernel_version:?}, task iterator options were added in 6.1"
+        );
+        return;
+    }
+
+    let mut ebpf = Ebpf::load(crate::TASK_ITER).unwrap();
+    let btf = Btf::from_sys_fs().unwrap();
+    let prog: &mut Iter = ebpf.program_mut("task_iter").unwrap().try_into().unwrap();
+    prog.load("task", &btf).unwrap();
+
+    let tid = unsafe { libc::gettid() } as u32;
+    let link_id = prog
+        .attach_with_options(IterLinkOptions::Task(TaskIterTarget::Tid(tid)))
+        .unwrap();
+    let link = prog.take_link(link_id).unwrap();
+    let file = link.into_file().unwrap();
+    let reader = std::io::BufReader::new(file);
+
+    let lines = reader.lines().map(Result::unwrap).collect::<Vec<_>>();
+    assert_eq!(lines, ["seq_num", "0"]);
+}
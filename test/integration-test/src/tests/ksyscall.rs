@@ -0,0 +1,17 @@
+use aya::{Ebpf, maps::Array, programs::KProbe};
+use integration_common::ksyscall::{FD, OFFSET};
+
+#[test_log::test]
+fn ksyscall() {
+    let mut bpf = Ebpf::load(crate::KSYSCALL).unwrap();
+    let prog: &mut KProbe = bpf.program_mut("pread64").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+    prog.attach_syscall("pread64").unwrap();
+
+    let mut buf = [0u8; 1];
+    let ret = unsafe { libc::pread(FD, buf.as_mut_ptr().cast(), buf.len(), OFFSET) };
+    assert_eq!(ret, -1);
+
+    let map: Array<_, i64> = Array::try_from(bpf.map("RESULT").unwrap()).unwrap();
+    assert_eq!(map.get(&0, 0).unwrap(), OFFSET);
+}
@@ -4,14 +4,18 @@ use aya::{
     Ebpf,
     maps::Array,
     programs::{
-        FlowDissector, KProbe, TracePoint, UProbe, Xdp, XdpFlags,
+        FlowDissector, KProbe, KProbeMulti, ProgramError, TracePoint, UProbe, UProbeMulti, Usdt,
+        UsdtError, Xdp, XdpFlags,
         links::{FdLink, PinnedLink},
         loaded_links, loaded_programs,
+        uprobe::UProbeAttachLocation,
     },
     util::KernelVersion,
 };
 use aya_obj::programs::XdpAttachType;
 
+use crate::utils::NetNsGuard;
+
 const MAX_RETRIES: usize = 100;
 pub(crate) const RETRY_DURATION: Duration = Duration::from_millis(10);
 
@@ -240,6 +244,47 @@ fn unload_kprobe() {
     assert_unloaded("test_kprobe");
 }
 
+#[test_log::test]
+fn basic_kprobe_multi() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 18, 0) {
+        eprintln!(
+            "skipping test on kernel {kernel_version:?}, kprobe.multi links were added in 5.18"
+        );
+        return;
+    }
+
+    let mut bpf = Ebpf::load(crate::TEST).unwrap();
+    let prog: &mut KProbeMulti = bpf
+        .program_mut("test_kprobe_multi")
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    prog.load().unwrap();
+    assert_loaded("test_kprobe_multi");
+    let link = prog
+        .attach(&["try_to_wake_up", "wake_up_process"], Some(&[1, 2]))
+        .unwrap();
+
+    {
+        let _link_owned = prog.take_link(link).unwrap();
+        prog.unload().unwrap();
+        assert_loaded_and_linked("test_kprobe_multi");
+    };
+
+    assert_unloaded("test_kprobe_multi");
+    prog.load().unwrap();
+
+    assert_loaded("test_kprobe_multi");
+    prog.attach_pattern("try_to_wake_up*").unwrap();
+
+    assert_loaded("test_kprobe_multi");
+    prog.unload().unwrap();
+
+    assert_unloaded("test_kprobe_multi");
+}
+
 #[test_log::test]
 fn memmove() {
     let mut bpf = Ebpf::load(crate::MEMMOVE_TEST).unwrap();
@@ -310,6 +355,78 @@ fn basic_uprobe() {
     assert_unloaded("test_uprobe");
 }
 
+#[test_log::test]
+fn basic_uprobe_multi() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(6, 6, 0) {
+        eprintln!(
+            "skipping test on kernel {kernel_version:?}, uprobe.multi links were added in 6.6"
+        );
+        return;
+    }
+
+    let mut bpf = Ebpf::load(crate::TEST).unwrap();
+    let prog: &mut UProbeMulti = bpf
+        .program_mut("test_uprobe_multi")
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    prog.load().unwrap();
+    assert_loaded("test_uprobe_multi");
+    let locations = [
+        UProbeAttachLocation::Symbol("uprobe_function"),
+        UProbeAttachLocation::Symbol("trigger_bpf_program"),
+    ];
+    let link = prog
+        .attach(&locations, "/proc/self/exe", None, Some(&[1, 2]))
+        .unwrap();
+
+    {
+        let _link_owned = prog.take_link(link).unwrap();
+        prog.unload().unwrap();
+        assert_loaded_and_linked("test_uprobe_multi");
+    };
+
+    assert_unloaded("test_uprobe_multi");
+    prog.load().unwrap();
+
+    assert_loaded("test_uprobe_multi");
+    prog.attach(&locations, "/proc/self/exe", None, None)
+        .unwrap();
+
+    assert_loaded("test_uprobe_multi");
+    prog.unload().unwrap();
+
+    assert_unloaded("test_uprobe_multi");
+}
+
+#[test_log::test]
+fn basic_usdt() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 15, 0) {
+        eprintln!(
+            "skipping test on kernel {kernel_version:?}, bpf_get_attach_cookie was added in 5.15"
+        );
+        return;
+    }
+
+    let mut bpf = Ebpf::load(crate::TEST).unwrap();
+    let prog: &mut Usdt = bpf.program_mut("test_usdt").unwrap().try_into().unwrap();
+
+    prog.load().unwrap();
+    assert_loaded("test_usdt");
+
+    // The test binary doesn't define any USDT probe.
+    assert_matches::assert_matches!(
+        prog.attach("aya", "missing", "/proc/self/exe", None, None),
+        Err(ProgramError::UsdtError(UsdtError::ProbeNotFound { .. }))
+    );
+
+    prog.unload().unwrap();
+    assert_unloaded("test_usdt");
+}
+
 #[test_log::test]
 fn basic_flow_dissector() {
     let mut bpf = Ebpf::load(crate::TEST).unwrap();
@@ -369,6 +486,46 @@ fn pin_link() {
     assert_unloaded("pass");
 }
 
+#[test_log::test]
+fn detach_pinned_link() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 9, 0) {
+        eprintln!("skipping test on kernel {kernel_version:?}, XDP uses netlink");
+        return;
+    }
+
+    const PIN_PATH: &str = "/sys/fs/bpf/aya-xdp-test-detach";
+    let _netns = NetNsGuard::new();
+
+    // Leave a pinned link behind, like a crashed agent would.
+    let prog_id = {
+        let mut bpf = Ebpf::load(crate::TEST).unwrap();
+        let prog: &mut Xdp = bpf.program_mut("pass").unwrap().try_into().unwrap();
+        prog.load().unwrap();
+        let link_id = prog.attach("lo", XdpFlags::default()).unwrap();
+        let fd_link: FdLink = prog.take_link(link_id).unwrap().try_into().unwrap();
+        fd_link.pin(PIN_PATH).unwrap();
+        prog.info().unwrap().id()
+    };
+
+    let mut bpf = Ebpf::load(crate::TEST).unwrap();
+    let prog: &mut Xdp = bpf.program_mut("pass").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+    // The interface is still owned by the pinned link.
+    prog.attach("lo", XdpFlags::default()).unwrap_err();
+
+    let link_id = loaded_links()
+        .filter_map(|link| link.ok())
+        .find_map(|link| (link.program_id() == prog_id).then(|| link.id()))
+        .unwrap();
+    FdLink::from_id(link_id).unwrap().force_detach().unwrap();
+
+    prog.attach("lo", XdpFlags::default()).unwrap();
+
+    // The detached link stays pinned until the pin is removed.
+    PinnedLink::from_pin(PIN_PATH).unwrap().unpin().unwrap();
+}
+
 #[test_log::test]
 fn pin_lifecycle() {
     let kernel_version = KernelVersion::current().unwrap();
@@ -0,0 +1,48 @@
+use std::net::{Ipv4Addr, UdpSocket};
+
+use assert_matches::assert_matches;
+use aya::{
+    Ebpf,
+    maps::Array,
+    programs::{LwtError, LwtOut, LwtSeg6Local, ProgramError},
+};
+
+use crate::utils::NetNsGuard;
+
+#[test_log::test]
+fn lwt_out() {
+    let _netns = NetNsGuard::new();
+
+    let mut bpf = Ebpf::load(crate::LWT).unwrap();
+    let prog: &mut LwtOut = bpf.program_mut("lwt_count").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+    let dst = Ipv4Addr::new(10, 10, 10, 0);
+    let link_id = prog.attach("lo", dst.into(), 24).unwrap();
+
+    let packets = Array::<_, u64>::try_from(bpf.map("PACKETS").unwrap()).unwrap();
+    assert_eq!(packets.get(&0, 0).unwrap(), 0);
+
+    let sock = UdpSocket::bind("0.0.0.0:0").unwrap();
+    sock.send_to(b"hello", "10.10.10.1:1234").unwrap();
+    assert_eq!(packets.get(&0, 0).unwrap(), 1);
+
+    // Detaching deletes the route, so the program no longer runs.
+    let prog: &mut LwtOut = bpf.program_mut("lwt_count").unwrap().try_into().unwrap();
+    prog.detach(link_id).unwrap();
+    let _: std::io::Error = sock.send_to(b"hello", "10.10.10.1:1234").unwrap_err();
+    let packets = Array::<_, u64>::try_from(bpf.map("PACKETS").unwrap()).unwrap();
+    assert_eq!(packets.get(&0, 0).unwrap(), 1);
+}
+
+#[test_log::test]
+fn lwt_seg6local_ipv4_route() {
+    let _netns = NetNsGuard::new();
+
+    let mut bpf = Ebpf::load(crate::LWT).unwrap();
+    let prog: &mut LwtSeg6Local = bpf.program_mut("lwt_end_bpf").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+    assert_matches!(
+        prog.attach("lo", Ipv4Addr::new(10, 10, 10, 0).into(), 24),
+        Err(ProgramError::LwtError(LwtError::Ipv4Route { .. }))
+    );
+}
@@ -0,0 +1,62 @@
+use aya::{
+    Ebpf,
+    maps::{Array, ArrayOfMaps, HashOfMaps, Map, MapData},
+    programs::UProbe,
+};
+use test_case::test_case;
+
+#[test_case(crate::MAP_OF_MAPS; "legacy")]
+#[test_case(crate::MAP_OF_MAPS_BTF; "btf")]
+#[test_log::test]
+fn map_of_maps(bytes: &[u8]) {
+    let mut bpf = Ebpf::load(bytes).unwrap();
+
+    let mut take_inner = |name, value| {
+        let mut inner = Array::<_, u32>::try_from(bpf.map_mut(name).unwrap()).unwrap();
+        inner.set(0, value, 0).unwrap();
+        match bpf.take_map(name).unwrap() {
+            Map::Array(inner) => inner,
+            map => panic!("unexpected map type {map:?}"),
+        }
+    };
+    let inner_0 = take_inner("INNER_0", 42);
+    let inner_1 = take_inner("INNER_1", 24);
+
+    let mut outer_array = ArrayOfMaps::try_from(bpf.take_map("OUTER_ARRAY").unwrap()).unwrap();
+    let mut outer_hash =
+        HashOfMaps::<_, u32>::try_from(bpf.take_map("OUTER_HASH").unwrap()).unwrap();
+    let result = Array::<_, u32>::try_from(bpf.take_map("RESULT").unwrap()).unwrap();
+
+    let prog: &mut UProbe = bpf.program_mut("map_of_maps").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+    prog.attach("trigger_map_of_maps", "/proc/self/exe", None, None)
+        .unwrap();
+
+    outer_array.set(0, inner_0.fd(), 0).unwrap();
+    outer_hash.insert(0, inner_1.fd(), 0).unwrap();
+    let inner_0_id = inner_0.info().unwrap().id();
+    let inner_1_id = inner_1.info().unwrap().id();
+    assert_eq!(outer_array.get(&0, 0).unwrap(), inner_0_id);
+    assert_eq!(outer_hash.get(&0, 0).unwrap(), inner_1_id);
+
+    trigger_map_of_maps();
+    assert_eq!(result.get(&0, 0).unwrap(), 42);
+    assert_eq!(result.get(&1, 0).unwrap(), 24);
+
+    // Swap the inner maps.
+    outer_array.set(0, inner_1.fd(), 0).unwrap();
+    outer_hash.insert(0, inner_0.fd(), 0).unwrap();
+
+    trigger_map_of_maps();
+    assert_eq!(result.get(&0, 0).unwrap(), 24);
+    assert_eq!(result.get(&1, 0).unwrap(), 42);
+
+    let inner = MapData::from_id(outer_array.get(&0, 0).unwrap()).unwrap();
+    assert_eq!(inner.info().unwrap().id(), inner_1_id);
+}
+
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub extern "C" fn trigger_map_of_maps() {
+    core::hint::black_box(trigger_map_of_maps);
+}
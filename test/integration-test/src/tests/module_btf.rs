@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use assert_matches::assert_matches;
+use aya::{
+    Btf, BtfError, Ebpf,
+    programs::{FEntry, ProgramError},
+    util::KernelVersion,
+};
+
+#[test_log::test]
+fn fentry_module_function() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 11, 0) {
+        eprintln!("skipping test on kernel {kernel_version:?}, module BTF was added in 5.11");
+        return;
+    }
+    // Loading the module registers its BTF.
+    let _ = std::process::Command::new("modprobe")
+        .arg("nf_conntrack")
+        .status();
+    if !Path::new("/sys/kernel/btf/nf_conntrack").exists() {
+        eprintln!("skipping test, the nf_conntrack module isn't loaded");
+        return;
+    }
+
+    // The loader finds the nf_conntrack BTF to relocate the access to
+    // `nf_conn`, and the program is attached to a function of the module.
+    let mut bpf = Ebpf::load(crate::MODULE_BTF).unwrap();
+    let btf = Btf::from_sys_fs().unwrap();
+    let prog: &mut FEntry = bpf
+        .program_mut("fentry_nf_ct_delete")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    prog.load("nf_ct_delete", &btf).unwrap();
+    prog.attach().unwrap();
+}
+
+#[test_log::test]
+fn fentry_unknown_function() {
+    let mut bpf = Ebpf::load(crate::MODULE_BTF).unwrap();
+    let btf = Btf::from_sys_fs().unwrap();
+    let prog: &mut FEntry = bpf
+        .program_mut("fentry_nf_ct_delete")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_matches!(
+        prog.load("aya_no_such_function", &btf),
+        Err(ProgramError::Btf(BtfError::UnknownBtfTypeName { type_name }))
+            if type_name == "aya_no_such_function"
+    );
+}
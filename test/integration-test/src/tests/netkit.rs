@@ -0,0 +1,64 @@
+use aya::{
+    Ebpf, netlink_add_netkit_pair,
+    programs::{LinkOrder, NetkitAttachType, SchedClassifier},
+    util::KernelVersion,
+};
+
+use crate::utils::NetNsGuard;
+
+#[test_log::test]
+fn netkit() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(6, 7, 0) {
+        eprintln!("skipping netkit_attach test on kernel {kernel_version:?}");
+        return;
+    }
+
+    let _netns = NetNsGuard::new();
+
+    unsafe { netlink_add_netkit_pair(c"nk0", c"nk1") }.unwrap();
+
+    // As with TCX, we need a dedicated `Ebpf` instance for each program that we
+    // load since netkit does not allow the same program ID to be attached
+    // multiple times to the same device.
+    macro_rules! attach_program_with_link_order {
+        ($ebpf:ident, $program_name:ident, $attach_type:expr, $link_order:expr) => {
+            let mut $ebpf = Ebpf::load(crate::TCX).unwrap();
+            let $program_name: &mut SchedClassifier =
+                $ebpf.program_mut("tcx_next").unwrap().try_into().unwrap();
+            $program_name.load().unwrap();
+            $program_name
+                .attach_to_netkit("nk0", $attach_type, $link_order)
+                .unwrap();
+        };
+    }
+
+    attach_program_with_link_order!(e1, last, NetkitAttachType::Primary, LinkOrder::last());
+    attach_program_with_link_order!(e2, first, NetkitAttachType::Primary, LinkOrder::first());
+    attach_program_with_link_order!(
+        e3,
+        after_first,
+        NetkitAttachType::Primary,
+        LinkOrder::after_program(first).unwrap()
+    );
+    attach_program_with_link_order!(e4, peer, NetkitAttachType::Peer, LinkOrder::default());
+
+    let expected_order = [first, after_first, last]
+        .iter()
+        .map(|program| program.info().unwrap().id())
+        .collect::<Vec<_>>();
+
+    let (revision, got_order) =
+        SchedClassifier::query_netkit("nk0", NetkitAttachType::Primary).unwrap();
+    assert_eq!(revision, (expected_order.len() + 1) as u64);
+    assert_eq!(
+        got_order.iter().map(|p| p.id()).collect::<Vec<_>>(),
+        expected_order
+    );
+
+    let (_, got_peer) = SchedClassifier::query_netkit("nk0", NetkitAttachType::Peer).unwrap();
+    assert_eq!(
+        got_peer.iter().map(|p| p.id()).collect::<Vec<_>>(),
+        [peer.info().unwrap().id()]
+    );
+}
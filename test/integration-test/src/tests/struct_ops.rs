@@ -0,0 +1,67 @@
+use aya::{
+    Ebpf,
+    maps::StructOpsMap,
+    programs::{
+        StructOps,
+        links::{FdLink, LinkError},
+    },
+    util::KernelVersion,
+};
+
+const AVAILABLE_CONGESTION_CONTROL: &str = "/proc/sys/net/ipv4/tcp_available_congestion_control";
+
+fn is_registered(name: &str) -> bool {
+    std::fs::read_to_string(AVAILABLE_CONGESTION_CONTROL)
+        .unwrap()
+        .split_whitespace()
+        .any(|n| n == name)
+}
+
+fn load(map: &str) -> Option<Ebpf> {
+    // The object also declares a `.struct_ops.link` map, which can't be created
+    // on older kernels.
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(6, 4, 0) {
+        eprintln!("skipping struct_ops test on kernel {kernel_version:?}");
+        return None;
+    }
+
+    let mut bpf = Ebpf::load(crate::STRUCT_OPS).unwrap();
+    for (_, program) in bpf.programs_mut() {
+        let program: &mut StructOps = program.try_into().unwrap();
+        program.load().unwrap();
+    }
+    assert!(!is_registered(map));
+    Some(bpf)
+}
+
+#[test_log::test]
+fn struct_ops_link() {
+    let Some(bpf) = load("aya_link") else {
+        return;
+    };
+    let map = StructOpsMap::try_from(bpf.map("TCP_CC").unwrap()).unwrap();
+    assert_eq!(map.type_name(), "tcp_congestion_ops");
+    let link = map.attach(&bpf).unwrap();
+    assert!(is_registered("aya_link"));
+
+    link.detach().unwrap();
+    assert!(!is_registered("aya_link"));
+}
+
+#[test_log::test]
+fn struct_ops_no_link() {
+    let Some(bpf) = load("aya_no_link") else {
+        return;
+    };
+    let map = StructOpsMap::try_from(bpf.map("TCP_CC_NO_LINK").unwrap()).unwrap();
+    let link = map.attach(&bpf).unwrap();
+    assert!(is_registered("aya_no_link"));
+
+    // Maps registered without a link can't be converted to an FdLink.
+    assert!(matches!(
+        FdLink::try_from(link),
+        Err(LinkError::InvalidLink)
+    ));
+    assert!(!is_registered("aya_no_link"));
+}
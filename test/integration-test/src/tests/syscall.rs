@@ -0,0 +1,19 @@
+use aya::{Ebpf, programs::Syscall, util::KernelVersion};
+
+#[test_log::test]
+fn syscall_run() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 14, 0) {
+        eprintln!("skipping syscall_run test on kernel {kernel_version:?}");
+        return;
+    }
+
+    let mut ebpf = Ebpf::load(crate::SYSCALL).unwrap();
+    let prog: &mut Syscall = ebpf.program_mut("increment").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+
+    let mut ctx = 41u64.to_ne_bytes();
+    let ret = prog.run(&mut ctx).unwrap();
+    assert_eq!(ret, 42);
+    assert_eq!(u64::from_ne_bytes(ctx), 42);
+}
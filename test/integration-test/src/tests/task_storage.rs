@@ -0,0 +1,92 @@
+use std::{
+    io::{Read as _, Write as _},
+    os::{
+        fd::{AsRawFd as _, FromRawFd as _, OwnedFd},
+        unix::net::UnixStream,
+    },
+    path::Path,
+};
+
+use assert_matches::assert_matches;
+use aya::{
+    Ebpf,
+    maps::{MapError, TaskStorage},
+    programs::TracePoint,
+    util::KernelVersion,
+};
+
+#[test_log::test]
+fn task_storage() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 15, 0) {
+        eprintln!(
+            "skipping test on kernel {kernel_version:?}, task storage helpers aren't available to tracepoints"
+        );
+        return;
+    }
+    if !Path::new("/sys/kernel/debug/tracing/events/syscalls/sys_enter_getpid").exists() {
+        eprintln!("skipping test - `syscalls/sys_enter_getpid` not available");
+        return;
+    }
+
+    let mut bpf = Ebpf::load(crate::TASK_STORAGE).unwrap();
+    for (name, tracepoint) in [
+        ("task_storage_getpid", "sys_enter_getpid"),
+        ("task_storage_getppid", "sys_enter_getppid"),
+    ] {
+        let prog: &mut TracePoint = bpf.program_mut(name).unwrap().try_into().unwrap();
+        prog.load().unwrap();
+        prog.attach("syscalls", tracepoint).unwrap();
+    }
+    let mut storage =
+        TaskStorage::<_, u64>::try_from(bpf.map_mut("TASK_STORAGE").unwrap()).unwrap();
+
+    // The storage belongs to the task making the syscalls, which must be a
+    // thread group leader to have a pidfd, so the syscalls are made by a child
+    // process, one at a time.
+    let (mut parent, child) = UnixStream::pair().unwrap();
+    let pid = unsafe { libc::fork() };
+    assert!(pid >= 0);
+    if pid == 0 {
+        // Only async-signal-safe functions can be called after forking a
+        // multithreaded process.
+        let fd = child.as_raw_fd();
+        let mut byte = 0u8;
+        for syscall in [libc::SYS_getpid, libc::SYS_getpid, libc::SYS_getppid] {
+            unsafe {
+                if libc::read(fd, (&raw mut byte).cast(), 1) != 1 {
+                    libc::_exit(1);
+                }
+                libc::syscall(syscall);
+                libc::write(fd, (&raw const byte).cast(), 1);
+            }
+        }
+        unsafe { libc::_exit(0) };
+    }
+    drop(child);
+    let mut next_syscall = || {
+        parent.write_all(&[0]).unwrap();
+        parent.read_exact(&mut [0]).unwrap();
+    };
+
+    let pidfd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+    assert!(pidfd >= 0, "{}", std::io::Error::last_os_error());
+    let pidfd = unsafe { OwnedFd::from_raw_fd(pidfd as i32) };
+
+    // getpid creates the storage.
+    next_syscall();
+    assert_eq!(storage.get(&pidfd, 0).unwrap(), 1);
+
+    // Values written from userspace are seen by the program.
+    storage.insert(&pidfd, 10, 0).unwrap();
+    next_syscall();
+    assert_eq!(storage.get(&pidfd, 0).unwrap(), 11);
+
+    // getppid deletes the storage.
+    next_syscall();
+    assert_matches!(storage.get(&pidfd, 0), Err(MapError::KeyNotFound));
+
+    let mut status = 0;
+    assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+    assert!(libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0);
+}
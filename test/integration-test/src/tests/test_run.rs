@@ -0,0 +1,24 @@
+use aya::{
+    Ebpf,
+    programs::{TestRunOptions, Xdp},
+};
+
+#[test_log::test]
+fn test_run_xdp() {
+    let mut bpf = Ebpf::load(crate::PASS).unwrap();
+    let prog: &mut Xdp = bpf.program_mut("pass").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+
+    const XDP_PASS: u32 = 2;
+    let packet = [0xaa; 64];
+    let output = prog
+        .test_run(
+            TestRunOptions::new()
+                .data_in(&packet)
+                .data_out_size(packet.len())
+                .repeat(10),
+        )
+        .unwrap();
+    assert_eq!(output.retval, XDP_PASS);
+    assert_eq!(output.data_out, packet);
+}
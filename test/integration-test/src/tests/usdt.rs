@@ -0,0 +1,88 @@
+use std::arch::asm;
+
+use aya::{Ebpf, maps::Array, programs::Usdt, util::KernelVersion};
+use integration_common::usdt::UsdtEvent;
+
+const PROVIDER: &str = "aya";
+const NAME: &str = "usdt_args";
+
+#[test_log::test]
+fn usdt_args() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 15, 0) {
+        eprintln!(
+            "skipping test on kernel {kernel_version:?}, bpf_get_attach_cookie was added in 5.15"
+        );
+        return;
+    }
+
+    let mut bpf = Ebpf::load(crate::USDT).unwrap();
+    // The spec map is declared along with the program by `#[usdt]`.
+    assert!(bpf.map("usdt_args_usdt_specs").is_some());
+    let prog: &mut Usdt = bpf.program_mut("usdt_args").unwrap().try_into().unwrap();
+    prog.load().unwrap();
+    prog.attach(PROVIDER, NAME, "/proc/self/exe", None, Some(42))
+        .unwrap();
+
+    let value = 0x1234_5678_9abc_def0u64;
+    trigger_usdt_args(-7, &value);
+
+    let map: Array<_, UsdtEvent> = Array::try_from(bpf.map("RESULT").unwrap()).unwrap();
+    assert_eq!(
+        map.get(&0, 0).unwrap(),
+        UsdtEvent {
+            cookie: 42,
+            arg_count: 3,
+            args: [-7, value as i64, -5],
+        }
+    );
+}
+
+/// Fires the `aya:usdt_args` probe with a signed 32-bit register, a 64-bit
+/// value read through a pointer and a signed constant as arguments.
+///
+/// The probe is described by a `.note.stapsdt` note, as emitted by the
+/// `DTRACE_PROBE` macros of SystemTap's `sys/sdt.h`.
+#[inline(never)]
+fn trigger_usdt_args(a: i32, b: &u64) {
+    macro_rules! probe {
+        ($args:literal, $($operands:tt)*) => {
+            unsafe {
+                asm!(
+                    "990: nop",
+                    ".pushsection .note.stapsdt, \"\", \"note\"",
+                    ".balign 4",
+                    ".4byte 992f-991f, 994f-993f, 3",
+                    "991: .asciz \"stapsdt\"",
+                    "992: .balign 4",
+                    "993: .8byte 990b",
+                    // No `.stapsdt.base` section is emitted, the file can't be prelinked.
+                    ".8byte 0",
+                    // No semaphore.
+                    ".8byte 0",
+                    ".asciz \"aya\"",
+                    ".asciz \"usdt_args\"",
+                    concat!(".asciz \"", $args, "\""),
+                    "994: .balign 4",
+                    ".popsection",
+                    $($operands)*
+                )
+            }
+        };
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    probe!(
+        "-4@{a:e} 8@({b}) -4@$-5",
+        a = in(reg) a,
+        b = in(reg) b,
+        options(att_syntax, nostack, readonly, preserves_flags),
+    );
+    #[cfg(target_arch = "aarch64")]
+    probe!(
+        "-4@{a:w} 8@[{b}] -4@-5",
+        a = in(reg) a,
+        b = in(reg) b,
+        options(nostack, readonly, preserves_flags),
+    );
+}
@@ -0,0 +1,59 @@
+use aya::{
+    EbpfLoader,
+    maps::{Array, UserRingBuf},
+    programs::UProbe,
+};
+
+#[test_log::test]
+fn user_ring_buf() {
+    // The byte size is rounded up to a multiple of the page size, so use the page size to know
+    // the capacity of the ring buffer.
+    let byte_size = usize::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).unwrap();
+    let mut bpf = EbpfLoader::new()
+        .set_max_entries("USER_RING_BUF", byte_size.try_into().unwrap())
+        .load(crate::USER_RING_BUF)
+        .unwrap();
+    let mut ring_buf = UserRingBuf::try_from(bpf.take_map("USER_RING_BUF").unwrap()).unwrap();
+    let result = Array::<_, u64>::try_from(bpf.take_map("RESULT").unwrap()).unwrap();
+
+    let prog: &mut UProbe = bpf
+        .program_mut("user_ring_buf_drain")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    prog.load().unwrap();
+    prog.attach("trigger_user_ring_buf_drain", "/proc/self/exe", None, None)
+        .unwrap();
+
+    let values = [1u64, 2, 3];
+    for value in values {
+        let mut entry = ring_buf.reserve(size_of::<u64>()).unwrap();
+        entry.copy_from_slice(&value.to_ne_bytes());
+        entry.submit();
+    }
+    // Discarded entries are skipped by the consumer.
+    let mut entry = ring_buf.reserve(size_of::<u64>()).unwrap();
+    entry.copy_from_slice(&100u64.to_ne_bytes());
+    entry.discard();
+
+    trigger_user_ring_buf_drain();
+    assert_eq!(result.get(&0, 0).unwrap(), values.iter().sum::<u64>());
+    assert_eq!(result.get(&1, 0).unwrap(), values.len() as u64);
+
+    // The consumer has caught up, so the whole ring buffer is available again, but a sample that
+    // doesn't fit alongside its header is never reserved.
+    assert!(ring_buf.reserve(byte_size).is_none());
+    for _ in 0..byte_size / 16 {
+        ring_buf.reserve(size_of::<u64>()).unwrap().submit();
+    }
+    assert!(ring_buf.reserve(size_of::<u64>()).is_none());
+
+    trigger_user_ring_buf_drain();
+    assert!(ring_buf.reserve(size_of::<u64>()).is_some());
+}
+
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub extern "C" fn trigger_user_ring_buf_drain() {
+    core::hint::black_box(trigger_user_ring_buf_drain);
+}
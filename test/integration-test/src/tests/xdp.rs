@@ -1,9 +1,15 @@
 use std::{net::UdpSocket, num::NonZeroU32, time::Duration};
 
+use assert_matches::assert_matches;
 use aya::{
     Ebpf,
     maps::{Array, CpuMap, XskMap},
-    programs::{Xdp, XdpFlags},
+    programs::{
+        Xdp, XdpFlags,
+        links::{FdLink, LinkError},
+    },
+    sys::SyscallError,
+    util::KernelVersion,
 };
 use object::{Object as _, ObjectSection as _, ObjectSymbol as _, SymbolSection};
 use xdpilone::{BufIdx, IfInfo, Socket, SocketConfig, Umem, UmemConfig};
@@ -187,3 +193,49 @@ fn cpumap_chain() {
     assert_eq!(hits.get(&0, 0).unwrap(), 1);
     assert_eq!(hits.get(&1, 0).unwrap(), 1);
 }
+
+#[test_log::test]
+fn update_program() {
+    let kernel_version = KernelVersion::current().unwrap();
+    if kernel_version < KernelVersion::new(5, 9, 0) {
+        eprintln!("skipping test on kernel {kernel_version:?}, XDP uses netlink");
+        return;
+    }
+
+    let _netns = NetNsGuard::new();
+
+    let mut pass_bpf = Ebpf::load(crate::MAIN).unwrap();
+    let pass: &mut Xdp = pass_bpf
+        .program_mut("xdp_pass")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    pass.load().unwrap();
+    let link_id = pass.attach("lo", XdpFlags::default()).unwrap();
+    let link: FdLink = pass.take_link(link_id).unwrap().try_into().unwrap();
+
+    let mut drop_bpf = Ebpf::load(crate::EXT).unwrap();
+    let drop_: &mut Xdp = drop_bpf
+        .program_mut("xdp_drop")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    drop_.load().unwrap();
+
+    // The expected program doesn't match the attached one.
+    assert_matches!(
+        link.update_program(drop_.fd().unwrap(), Some(drop_.fd().unwrap())),
+        Err(LinkError::SyscallError(SyscallError {
+            call: "bpf_link_update",
+            ..
+        }))
+    );
+    assert_eq!(link.info().unwrap().program_id(), pass.info().unwrap().id());
+
+    link.update_program(drop_.fd().unwrap(), Some(pass.fd().unwrap()))
+        .unwrap();
+    assert_eq!(
+        link.info().unwrap().program_id(),
+        drop_.info().unwrap().id()
+    );
+}